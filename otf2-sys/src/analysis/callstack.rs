@@ -0,0 +1,253 @@
+//! Match the Enter and Leave events of each location into region intervals.
+
+use crate::internal::*;
+use crate::error::Status;
+use crate::event::{Event, EventKind};
+use crate::types::MeasurementMode;
+
+use std::collections::BTreeMap;
+
+/// One completed visit to a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionInterval {
+    pub location: OTF2_LocationRef,
+    pub region: OTF2_RegionRef,
+    pub begin: OTF2_TimeStamp,
+    pub end: OTF2_TimeStamp,
+    /// The number of regions which enclose this one, so 0 for a region entered on an empty stack.
+    pub depth: u32,
+    /// The region which encloses this one.
+    pub parent: Option<OTF2_RegionRef>,
+}
+
+impl RegionInterval {
+    pub fn duration(&self) -> u64 {
+        self.end - self.begin
+    }
+}
+
+/// A period in which a location recorded nothing because measurement was switched off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeasurementGap {
+    pub location: OTF2_LocationRef,
+    pub begin: OTF2_TimeStamp,
+    pub end: OTF2_TimeStamp,
+}
+
+/// An inconsistency between the Enter and Leave events of a location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CallStackIssue {
+    /// A Leave which doesn't match the innermost open region. The innermost region, if any, is
+    /// closed in its place.
+    MismatchedLeave { location: OTF2_LocationRef, time: OTF2_TimeStamp, expected: Option<OTF2_RegionRef>, found: OTF2_RegionRef },
+    /// A region which was still open at the end of the trace. It is closed at the last event of
+    /// its location.
+    UnclosedRegion { location: OTF2_LocationRef, region: OTF2_RegionRef, begin: OTF2_TimeStamp },
+}
+
+#[derive(Debug, Default)]
+struct LocationStack {
+    frames: Vec<(OTF2_RegionRef, OTF2_TimeStamp)>,
+    last_time: OTF2_TimeStamp,
+    measurement_off_since: Option<OTF2_TimeStamp>,
+}
+
+/// Tracks the call stack of each location through its Enter and Leave events.
+///
+/// Events must be added in timestamp order per location, as given by the global event reader.
+/// Call [`CallStackTracker::finish`] after the last event to close the regions still open.
+#[derive(Debug, Default)]
+pub struct CallStackTracker {
+    stacks: BTreeMap<OTF2_LocationRef, LocationStack>,
+    issues: Vec<CallStackIssue>,
+    gaps: Vec<MeasurementGap>,
+}
+
+impl CallStackTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the stack of the event's location, returning the interval which a Leave completes.
+    pub fn add_event(&mut self, event: &Event) -> Option<RegionInterval> {
+        let (location, time) = (event.data.location, event.data.time);
+        let stack = self.stacks.entry(location).or_default();
+        stack.last_time = time;
+        match event.kind {
+            EventKind::Enter { region } => {
+                stack.frames.push((region, time));
+                None
+            }
+            EventKind::Leave { region } => {
+                let expected = stack.frames.last().map(|&(top, _)| top);
+                if expected != Some(region) {
+                    self.issues.push(CallStackIssue::MismatchedLeave { location, time, expected, found: region });
+                }
+                let (region, begin) = stack.frames.pop()?;
+                Some(RegionInterval {
+                    location,
+                    region,
+                    begin,
+                    end: time,
+                    depth: stack.frames.len() as u32,
+                    parent: stack.frames.last().map(|&(parent, _)| parent),
+                })
+            }
+            EventKind::MeasurementOnOff { measurement_mode: MeasurementMode::Off } => {
+                stack.measurement_off_since.get_or_insert(time);
+                None
+            }
+            EventKind::MeasurementOnOff { measurement_mode: MeasurementMode::On } => {
+                if let Some(begin) = stack.measurement_off_since.take() {
+                    self.gaps.push(MeasurementGap { location, begin, end: time });
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// The regions open on `location`, outermost first.
+    pub fn stack(&self, location: OTF2_LocationRef) -> impl Iterator<Item = OTF2_RegionRef> + '_ {
+        self.stacks.get(&location).into_iter().flat_map(|stack| stack.frames.iter().map(|&(region, _)| region))
+    }
+
+    /// The innermost region open on `location`, and the time it was entered.
+    pub fn top(&self, location: OTF2_LocationRef) -> Option<(OTF2_RegionRef, OTF2_TimeStamp)> {
+        self.stacks.get(&location)?.frames.last().copied()
+    }
+
+    pub fn depth(&self, location: OTF2_LocationRef) -> usize {
+        self.stacks.get(&location).map_or(0, |stack| stack.frames.len())
+    }
+
+    /// Close every open region at the last event of its location, innermost first, and end any
+    /// measurement gap still open. Each open region is reported as an issue.
+    pub fn finish(&mut self) -> Vec<RegionInterval> {
+        let mut intervals = Vec::new();
+        for (&location, stack) in &mut self.stacks {
+            while let Some((region, begin)) = stack.frames.pop() {
+                self.issues.push(CallStackIssue::UnclosedRegion { location, region, begin });
+                intervals.push(RegionInterval {
+                    location,
+                    region,
+                    begin,
+                    end: stack.last_time,
+                    depth: stack.frames.len() as u32,
+                    parent: stack.frames.last().map(|&(parent, _)| parent),
+                });
+            }
+            if let Some(begin) = stack.measurement_off_since.take() {
+                self.gaps.push(MeasurementGap { location, begin, end: stack.last_time });
+            }
+        }
+        intervals
+    }
+
+    pub fn issues(&self) -> &[CallStackIssue] {
+        &self.issues
+    }
+
+    pub fn gaps(&self) -> &[MeasurementGap] {
+        &self.gaps
+    }
+}
+
+/// Yields the region intervals of `events` as each is completed, then those left open at the end
+/// of the trace.
+pub fn region_intervals<I: Iterator<Item = Status<Event>>>(events: I) -> RegionIntervals<I> {
+    RegionIntervals { events: Some(events), tracker: CallStackTracker::new(), unclosed: Vec::new() }
+}
+
+/// The iterator returned by [`region_intervals`].
+#[derive(Debug)]
+pub struct RegionIntervals<I> {
+    // None once the events are exhausted
+    events: Option<I>,
+    tracker: CallStackTracker,
+    unclosed: Vec<RegionInterval>,
+}
+
+impl<I> RegionIntervals<I> {
+    /// The tracker, which holds the issues and measurement gaps found so far.
+    pub fn tracker(&self) -> &CallStackTracker {
+        &self.tracker
+    }
+}
+
+impl<I: Iterator<Item = Status<Event>>> Iterator for RegionIntervals<I> {
+    type Item = Status<RegionInterval>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(events) = self.events.as_mut() {
+            match events.next() {
+                Some(Ok(event)) => {
+                    if let Some(interval) = self.tracker.add_event(&event) {
+                        return Some(Ok(interval));
+                    }
+                }
+                Some(Err(code)) => return Some(Err(code)),
+                None => {
+                    self.events = None;
+                    self.unclosed = self.tracker.finish();
+                    self.unclosed.reverse();
+                }
+            }
+        }
+        self.unclosed.pop().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fixtures::{enter, event, leave};
+
+    fn interval(location: u64, region: u32, begin: u64, end: u64, depth: u32, parent: Option<u32>) -> RegionInterval {
+        RegionInterval {
+            location: OTF2_LocationRef(location),
+            region: OTF2_RegionRef(region),
+            begin,
+            end,
+            depth,
+            parent: parent.map(OTF2_RegionRef),
+        }
+    }
+
+    #[test]
+    fn nested_regions_complete_innermost_first() {
+        let events = vec![enter(0, 0, 1), enter(1, 1, 1), enter(0, 2, 2), leave(0, 5, 2), leave(1, 6, 1), leave(0, 9, 1)];
+        let mut intervals = region_intervals(events.into_iter().map(Ok));
+        let found: Vec<_> = intervals.by_ref().collect::<Status<_>>().unwrap();
+        assert_eq!(found, [interval(0, 2, 2, 5, 1, Some(1)), interval(1, 1, 1, 6, 0, None), interval(0, 1, 0, 9, 0, None)]);
+        assert!(intervals.tracker().issues().is_empty());
+    }
+
+    #[test]
+    fn reports_mismatched_leaves_and_unclosed_regions() {
+        let events = vec![enter(0, 0, 1), enter(0, 1, 2), leave(0, 3, 1), leave(0, 4, 7), enter(0, 6, 3)];
+        let mut intervals = region_intervals(events.into_iter().map(Ok));
+        let found: Vec<_> = intervals.by_ref().collect::<Status<_>>().unwrap();
+        assert_eq!(found, [interval(0, 2, 1, 3, 1, Some(1)), interval(0, 1, 0, 4, 0, None), interval(0, 3, 6, 6, 0, None)]);
+        let location = OTF2_LocationRef(0);
+        assert_eq!(intervals.tracker().issues(), [
+            CallStackIssue::MismatchedLeave { location, time: 3, expected: Some(OTF2_RegionRef(2)), found: OTF2_RegionRef(1) },
+            CallStackIssue::MismatchedLeave { location, time: 4, expected: Some(OTF2_RegionRef(1)), found: OTF2_RegionRef(7) },
+            CallStackIssue::UnclosedRegion { location, region: OTF2_RegionRef(3), begin: 6 },
+        ]);
+    }
+
+    #[test]
+    fn records_measurement_gaps() {
+        let off = EventKind::MeasurementOnOff { measurement_mode: MeasurementMode::Off };
+        let on = EventKind::MeasurementOnOff { measurement_mode: MeasurementMode::On };
+        let events = vec![enter(0, 0, 1), event(0, 2, off.clone()), event(0, 5, on), event(0, 7, off), leave(0, 8, 1)];
+        let mut intervals = region_intervals(events.into_iter().map(Ok));
+        assert_eq!(intervals.by_ref().count(), 1);
+        let location = OTF2_LocationRef(0);
+        assert_eq!(intervals.tracker().gaps(), [MeasurementGap { location, begin: 2, end: 5 }, MeasurementGap { location, begin: 7, end: 8 }]);
+    }
+}
@@ -0,0 +1,325 @@
+//! Call-tree profiles: the time spent in each region, split by the path of regions it was called
+//! from.
+
+use crate::internal::*;
+use crate::definition::DefinitionSet;
+use crate::error::Status;
+use crate::event::{Event, EventKind, EventKindId, EventKindSet};
+use crate::reader::Trace;
+
+use super::callstack::{CallStackTracker, RegionInterval};
+use super::profile::RegionStats;
+use super::time_scale;
+
+use std::collections::BTreeMap;
+
+/// One call path: a region together with the regions it was called from.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallTreeNode {
+    pub region: OTF2_RegionRef,
+    pub name: String,
+    /// The index of the calling node, or `None` for a root.
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    /// The visits on all locations together.
+    pub total: RegionStats,
+    pub per_location: BTreeMap<OTF2_LocationRef, RegionStats>,
+    /// The number of calling-context samples taken in this call path.
+    pub samples: u64,
+    /// The callpath definitions with this call path.
+    pub callpaths: Vec<OTF2_CallpathRef>,
+    /// The calling-context definitions with this call path. Contexts which differ only in their
+    /// source code location share a node.
+    pub calling_contexts: Vec<OTF2_CallingContextRef>,
+}
+
+/// The call tree of a trace, merging identical call paths across visits and locations. Times are
+/// in timer ticks.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallTree {
+    /// Ticks per second, if the trace defines its clock.
+    pub timer_resolution: Option<u64>,
+    pub nodes: Vec<CallTreeNode>,
+    pub roots: Vec<usize>,
+}
+
+impl CallTree {
+    /// The node reached by following `path` from the roots, outermost region first.
+    pub fn find(&self, path: &[OTF2_RegionRef]) -> Option<usize> {
+        let mut candidates = &self.roots;
+        let mut found = None;
+        for region in path {
+            let node = *candidates.iter().find(|&&node| self.nodes[node].region == *region)?;
+            candidates = &self.nodes[node].children;
+            found = Some(node);
+        }
+        found
+    }
+
+    /// The regions on the path to `node`, outermost first.
+    pub fn path(&self, node: usize) -> Vec<OTF2_RegionRef> {
+        let mut path: Vec<_> = std::iter::successors(Some(node), |&node| self.nodes[node].parent)
+            .map(|node| self.nodes[node].region)
+            .collect();
+        path.reverse();
+        path
+    }
+
+    fn fmt_node(&self, f: &mut std::fmt::Formatter<'_>, node: usize, depth: usize, scale: f64) -> std::fmt::Result {
+        let node = &self.nodes[node];
+        let indent = depth * 2;
+        writeln!(
+            f,
+            "{:indent$}{} visits={} incl={:.6} excl={:.6}{}",
+            "",
+            node.name,
+            node.total.visits,
+            node.total.inclusive as f64 * scale,
+            node.total.exclusive as f64 * scale,
+            if node.samples > 0 { format!(" samples={}", node.samples) } else { String::new() },
+        )?;
+        node.children.iter().try_for_each(|&child| self.fmt_node(f, child, depth + 1, scale))
+    }
+}
+
+/// Shows the tree with one indented line per node, with times in seconds if the timer resolution
+/// is known.
+impl std::fmt::Display for CallTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (_, scale) = time_scale(self.timer_resolution);
+        self.roots.iter().try_for_each(|&root| self.fmt_node(f, root, 0, scale))
+    }
+}
+
+/// Builds a [`CallTree`] from the Enter, Leave and CallingContextSample events of a trace.
+///
+/// Events must be added in timestamp order per location, as given by the global event reader.
+#[derive(Debug, Default)]
+pub struct CallTreeBuilder {
+    tracker: CallStackTracker,
+    nodes: Vec<CallTreeNode>,
+    roots: Vec<usize>,
+    index: BTreeMap<(Option<usize>, OTF2_RegionRef), usize>,
+    // The node of each open frame, and the time spent in its completed children
+    open: BTreeMap<OTF2_LocationRef, Vec<(usize, u64)>>,
+    samples: BTreeMap<OTF2_CallingContextRef, u64>,
+}
+
+impl CallTreeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The tracker which matches Enter and Leave events, which holds the issues found so far.
+    pub fn tracker(&self) -> &CallStackTracker {
+        &self.tracker
+    }
+
+    pub fn add_event(&mut self, event: &Event) {
+        match event.kind {
+            EventKind::Enter { region } => {
+                let parent = self.open.get(&event.data.location).and_then(|frames| frames.last()).map(|&(node, _)| node);
+                let node = self.node(parent, region);
+                self.open.entry(event.data.location).or_default().push((node, 0));
+            }
+            EventKind::CallingContextSample { calling_context, .. } => {
+                *self.samples.entry(calling_context).or_default() += 1;
+            }
+            _ => {}
+        }
+        if let Some(interval) = self.tracker.add_event(event) {
+            self.close(&interval);
+        }
+    }
+
+    fn node(&mut self, parent: Option<usize>, region: OTF2_RegionRef) -> usize {
+        if let Some(&node) = self.index.get(&(parent, region)) {
+            return node;
+        }
+        let node = self.nodes.len();
+        self.nodes.push(CallTreeNode {
+            region,
+            name: String::new(),
+            parent,
+            children: Vec::new(),
+            total: RegionStats::default(),
+            per_location: BTreeMap::new(),
+            samples: 0,
+            callpaths: Vec::new(),
+            calling_contexts: Vec::new(),
+        });
+        match parent {
+            Some(parent) => self.nodes[parent].children.push(node),
+            None => self.roots.push(node),
+        }
+        self.index.insert((parent, region), node);
+        node
+    }
+
+    /// The node for `path`, creating any missing nodes along it.
+    fn node_for_path(&mut self, path: impl IntoIterator<Item = OTF2_RegionRef>) -> Option<usize> {
+        path.into_iter().fold(None, |parent, region| Some(self.node(parent, region)))
+    }
+
+    /// Attribute a completed interval to the node of the innermost open frame of its location.
+    fn close(&mut self, interval: &RegionInterval) {
+        let Some(frames) = self.open.get_mut(&interval.location) else {
+            return;
+        };
+        let Some((node, children)) = frames.pop() else {
+            return;
+        };
+        let duration = interval.duration();
+        if let Some((_, parent_children)) = frames.last_mut() {
+            *parent_children += duration;
+        }
+        let exclusive = duration.saturating_sub(children);
+        let node = &mut self.nodes[node];
+        node.total.add_visit(duration, exclusive);
+        node.per_location.entry(interval.location).or_default().add_visit(duration, exclusive);
+    }
+
+    /// Close the regions still open, then name each node and match it with the callpath and
+    /// calling-context definitions of the same call path. Sampled calling contexts which no
+    /// Enter event reached are added to the tree.
+    pub fn finish(mut self, definitions: &DefinitionSet) -> CallTree {
+        for interval in self.tracker.finish() {
+            self.close(&interval);
+        }
+        let callpaths: Vec<_> = definitions.callpaths.keys()
+            .filter_map(|&callpath| Some((callpath, ancestor_regions(&definitions.callpaths, callpath, |def| (def.region, def.parent))?)))
+            .collect();
+        for (callpath, path) in callpaths {
+            if let Some(node) = self.node_for_path(path) {
+                self.nodes[node].callpaths.push(callpath);
+            }
+        }
+        let contexts: Vec<_> = definitions.calling_contexts.keys()
+            .filter_map(|&context| Some((context, ancestor_regions(&definitions.calling_contexts, context, |def| (def.region, def.parent))?)))
+            .collect();
+        for (context, path) in contexts {
+            if let Some(node) = self.node_for_path(path) {
+                self.nodes[node].calling_contexts.push(context);
+                self.nodes[node].samples += self.samples.get(&context).copied().unwrap_or(0);
+            }
+        }
+        for node in &mut self.nodes {
+            node.name = definitions.region_label(node.region);
+        }
+        CallTree {
+            timer_resolution: definitions.timer_resolution(),
+            nodes: self.nodes,
+            roots: self.roots,
+        }
+    }
+}
+
+/// The regions of `reference` and its ancestors among `defs`, outermost first, given the region
+/// and parent of each definition. `None` if any is undefined or the parents form a cycle.
+fn ancestor_regions<R: Ord + Copy, D>(
+    defs: &BTreeMap<R, D>,
+    reference: R,
+    region_and_parent: impl Fn(&D) -> (OTF2_RegionRef, Option<R>),
+) -> Option<Vec<OTF2_RegionRef>> {
+    let mut path = Vec::new();
+    let mut current = Some(reference);
+    while let Some(reference) = current {
+        let (region, parent) = region_and_parent(defs.get(&reference)?);
+        path.push(region);
+        current = parent;
+        if path.len() > defs.len() {
+            return None;
+        }
+    }
+    path.reverse();
+    Some(path)
+}
+
+impl Trace {
+    /// Compute the call tree of the trace from its Enter, Leave and calling-context sample events.
+    pub fn call_tree(&mut self) -> Status<CallTree> {
+        let definitions = self.definition_set()?;
+        let mut builder = CallTreeBuilder::new();
+        let batch_size = self.options().batch_size;
+        let kinds = EventKindSet::REGIONS.with(EventKindId::CallingContextSample);
+        for event in self.iter_events_of_kinds(batch_size, kinds)? {
+            builder.add_event(&event?);
+        }
+        Ok(builder.finish(&definitions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fixtures::{enter, event, leave};
+    use crate::definition::{CallingContextDef, CallpathDef, Definition};
+
+    fn regions(path: &[u32]) -> Vec<OTF2_RegionRef> {
+        path.iter().copied().map(OTF2_RegionRef).collect()
+    }
+
+    #[test]
+    fn merges_identical_call_paths() {
+        let mut builder = CallTreeBuilder::new();
+        // main -> a -> c and main -> b -> c on location 0, main -> a -> c on location 1
+        for event in [
+            enter(0, 0, 0), enter(0, 1, 1), enter(0, 2, 3), leave(0, 4, 3), leave(0, 5, 1),
+            enter(0, 6, 2), enter(0, 7, 3), leave(0, 8, 3), leave(0, 10, 2), leave(0, 12, 0),
+            enter(1, 0, 0), enter(1, 1, 1), enter(1, 2, 3), leave(1, 6, 3), leave(1, 7, 1), leave(1, 8, 0),
+        ] {
+            builder.add_event(&event);
+        }
+        let tree = builder.finish(&DefinitionSet::new());
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(tree.nodes.len(), 5);
+        let main = &tree.nodes[tree.find(&regions(&[0])).unwrap()];
+        assert_eq!((main.total.visits, main.total.inclusive, main.total.exclusive), (2, 20, 6));
+        let a_c = tree.find(&regions(&[0, 1, 3])).unwrap();
+        assert_eq!(tree.nodes[a_c].total.inclusive, 6);
+        assert_eq!(tree.nodes[a_c].per_location[&OTF2_LocationRef(1)].inclusive, 4);
+        let b_c = tree.find(&regions(&[0, 2, 3])).unwrap();
+        assert_eq!(tree.nodes[b_c].total.inclusive, 1);
+        assert_eq!(tree.path(b_c), regions(&[0, 2, 3]));
+        assert_eq!(tree.find(&regions(&[3])), None);
+        assert_eq!(tree.to_string().lines().count(), 5);
+    }
+
+    #[test]
+    fn reconciles_with_callpath_and_calling_context_definitions() {
+        let definitions: DefinitionSet = vec![
+            Definition::Callpath { defn: OTF2_CallpathRef(0), value: CallpathDef { parent: None, region: OTF2_RegionRef(0) } },
+            Definition::Callpath { defn: OTF2_CallpathRef(1), value: CallpathDef { parent: Some(OTF2_CallpathRef(0)), region: OTF2_RegionRef(1) } },
+            Definition::CallingContext {
+                defn: OTF2_CallingContextRef(0),
+                value: CallingContextDef { region: OTF2_RegionRef(0), source_code_location: OTF2_SourceCodeLocationRef(0), parent: None },
+            },
+            Definition::CallingContext {
+                defn: OTF2_CallingContextRef(1),
+                value: CallingContextDef { region: OTF2_RegionRef(2), source_code_location: OTF2_SourceCodeLocationRef(1), parent: Some(OTF2_CallingContextRef(0)) },
+            },
+        ].into();
+        let mut builder = CallTreeBuilder::new();
+        let sample = EventKind::CallingContextSample {
+            calling_context: OTF2_CallingContextRef(1),
+            unwind_distance: 0,
+            interrupt_generator: OTF2_InterruptGeneratorRef(0),
+        };
+        for event in [enter(0, 0, 0), enter(0, 1, 1), leave(0, 2, 1), event(0, 3, sample.clone()), event(0, 4, sample), leave(0, 5, 0)] {
+            builder.add_event(&event);
+        }
+        let tree = builder.finish(&definitions);
+        let main = &tree.nodes[tree.find(&regions(&[0])).unwrap()];
+        assert_eq!(main.callpaths, [OTF2_CallpathRef(0)]);
+        assert_eq!(main.calling_contexts, [OTF2_CallingContextRef(0)]);
+        assert_eq!(tree.nodes[tree.find(&regions(&[0, 1])).unwrap()].callpaths, [OTF2_CallpathRef(1)]);
+        let sampled = &tree.nodes[tree.find(&regions(&[0, 2])).unwrap()];
+        assert_eq!((sampled.samples, sampled.total.visits), (2, 0));
+        // A node without visits has no shortest visit to report
+        assert_eq!(sampled.total, RegionStats::default());
+        assert_eq!(sampled.total.min, 0);
+        assert_eq!(sampled.calling_contexts, [OTF2_CallingContextRef(1)]);
+    }
+}
@@ -0,0 +1,307 @@
+//! Reconstruct MPI collective operations from the events of each of their members.
+
+use crate::internal::*;
+use crate::definition::{CommResolver, DefinitionSet};
+use crate::error::Status;
+use crate::event::{Event, EventKind, EventKindSet};
+use crate::reader::Trace;
+use crate::types::CollectiveOp;
+
+use std::collections::BTreeMap;
+
+/// One location's part in a collective operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollectiveMember {
+    pub location: OTF2_LocationRef,
+    /// The time of the MpiCollectiveBegin or NonBlockingCollectiveRequest event.
+    pub begin: OTF2_TimeStamp,
+    /// The time of the MpiCollectiveEnd or NonBlockingCollectiveComplete event.
+    pub end: OTF2_TimeStamp,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+/// One collective operation on a communicator, with the part each location played in it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollectiveInstance {
+    pub op: CollectiveOp,
+    pub communicator: OTF2_CommRef,
+    /// The position of this operation among those on the communicator, counting from 0.
+    pub sequence: u64,
+    /// The location of the root, for rooted operations.
+    pub root: Option<OTF2_LocationRef>,
+    pub non_blocking: bool,
+    /// Members in location order.
+    pub members: Vec<CollectiveMember>,
+}
+
+impl CollectiveInstance {
+    /// The time the first member began.
+    pub fn begin(&self) -> OTF2_TimeStamp {
+        self.members.iter().map(|member| member.begin).min().unwrap_or_default()
+    }
+
+    /// The time the last member began. No member of a synchronising operation can end before
+    /// this.
+    pub fn last_begin(&self) -> OTF2_TimeStamp {
+        self.members.iter().map(|member| member.begin).max().unwrap_or_default()
+    }
+
+    /// The time the last member ended.
+    pub fn end(&self) -> OTF2_TimeStamp {
+        self.members.iter().map(|member| member.end).max().unwrap_or_default()
+    }
+
+    pub fn member(&self, location: OTF2_LocationRef) -> Option<&CollectiveMember> {
+        self.members.iter().find(|member| member.location == location)
+    }
+
+    pub fn bytes_sent(&self) -> u64 {
+        self.members.iter().map(|member| member.bytes_sent).sum()
+    }
+
+    pub fn bytes_received(&self) -> u64 {
+        self.members.iter().map(|member| member.bytes_received).sum()
+    }
+}
+
+/// An inconsistency found while reconstructing collectives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CollectiveIssue {
+    /// An end event without the begin or request event which should precede it. It is dropped.
+    UnpairedEnd { location: OTF2_LocationRef, time: OTF2_TimeStamp },
+    /// A begin or request event which no end event followed.
+    UnpairedBegin { location: OTF2_LocationRef, time: OTF2_TimeStamp },
+    /// A member which recorded a different operation than the first member of the instance.
+    MismatchedOperation { communicator: OTF2_CommRef, sequence: u64, location: OTF2_LocationRef, op: CollectiveOp },
+    /// An instance which not every rank of its communicator took part in.
+    Incomplete { communicator: OTF2_CommRef, sequence: u64, expected: usize, found: usize },
+}
+
+/// The collective operations of a trace.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Collectives {
+    /// Instances in the order they began.
+    pub instances: Vec<CollectiveInstance>,
+    pub issues: Vec<CollectiveIssue>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Operation {
+    op: CollectiveOp,
+    root: Option<OTF2_LocationRef>,
+    non_blocking: bool,
+    member: CollectiveMember,
+}
+
+/// Matches the collective events of each location into [`CollectiveInstance`]s.
+///
+/// The MPI standard requires every rank to call the collectives of a communicator in the same
+/// order, so the n-th operation of each member on a communicator belong together. Non-blocking
+/// operations are ordered by their request. Operations on `MPI_COMM_SELF` form an instance of
+/// their own per location.
+#[derive(Debug, Default)]
+pub struct CollectiveMatcher {
+    comms: CommResolver,
+    begins: BTreeMap<OTF2_LocationRef, OTF2_TimeStamp>,
+    requests: BTreeMap<(OTF2_LocationRef, u64), OTF2_TimeStamp>,
+    // The operations of each location on each communicator
+    operations: BTreeMap<(OTF2_CommRef, OTF2_LocationRef), Vec<Operation>>,
+    issues: Vec<CollectiveIssue>,
+}
+
+impl CollectiveMatcher {
+    pub fn new(definitions: &DefinitionSet) -> Self {
+        Self { comms: CommResolver::new(definitions), ..Self::default() }
+    }
+
+    pub fn add_event(&mut self, event: &Event) {
+        let (location, time) = (event.data.location, event.data.time);
+        let (begin, non_blocking, op, communicator, root, bytes_sent, bytes_received) = match event.kind {
+            EventKind::MpiCollectiveBegin {} => {
+                if let Some(begin) = self.begins.insert(location, time) {
+                    self.issues.push(CollectiveIssue::UnpairedBegin { location, time: begin });
+                }
+                return;
+            }
+            EventKind::NonBlockingCollectiveRequest { request_id } => {
+                if let Some(begin) = self.requests.insert((location, request_id), time) {
+                    self.issues.push(CollectiveIssue::UnpairedBegin { location, time: begin });
+                }
+                return;
+            }
+            EventKind::MpiCollectiveEnd { collective_op, communicator, root, size_sent, size_received } => {
+                (self.begins.remove(&location), false, collective_op, communicator, root, size_sent, size_received)
+            }
+            EventKind::NonBlockingCollectiveComplete { collective_op, communicator, root, size_sent, size_received, request_id } => {
+                (self.requests.remove(&(location, request_id)), true, collective_op, communicator, root, size_sent, size_received)
+            }
+            _ => return,
+        };
+        let Some(begin) = begin else {
+            self.issues.push(CollectiveIssue::UnpairedEnd { location, time });
+            return;
+        };
+        let root = (root != OTF2_UNDEFINED_UINT32).then(|| self.comms.location(communicator, root, location)).flatten();
+        self.operations.entry((communicator, location)).or_default().push(Operation {
+            op,
+            root,
+            non_blocking,
+            member: CollectiveMember { location, begin, end: time, bytes_sent, bytes_received },
+        });
+    }
+
+    pub fn finish(mut self) -> Collectives {
+        self.issues.extend(self.begins.iter().map(|(&location, &time)| CollectiveIssue::UnpairedBegin { location, time }));
+        self.issues.extend(self.requests.iter().map(|(&(location, _), &time)| CollectiveIssue::UnpairedBegin { location, time }));
+        // Keyed by communicator, the location for MPI_COMM_SELF, and sequence
+        let mut instances: BTreeMap<(OTF2_CommRef, Option<OTF2_LocationRef>, u64), CollectiveInstance> = BTreeMap::new();
+        for ((communicator, location), mut operations) in self.operations {
+            let comm_self = self.comms.is_self(communicator);
+            operations.sort_by_key(|operation| operation.member.begin);
+            for (sequence, operation) in (0..).zip(operations) {
+                let key = (communicator, comm_self.then_some(location), sequence);
+                let instance = instances.entry(key).or_insert_with(|| CollectiveInstance {
+                    op: operation.op,
+                    communicator,
+                    sequence,
+                    root: operation.root,
+                    non_blocking: operation.non_blocking,
+                    members: Vec::new(),
+                });
+                if operation.op != instance.op {
+                    self.issues.push(CollectiveIssue::MismatchedOperation { communicator, sequence, location, op: operation.op });
+                }
+                instance.root = instance.root.or(operation.root);
+                instance.members.push(operation.member);
+            }
+        }
+        let mut instances: Vec<_> = instances.into_values().collect();
+        for instance in &instances {
+            let expected = self.comms.member_count(instance.communicator).unwrap_or(instance.members.len());
+            if expected != instance.members.len() {
+                self.issues.push(CollectiveIssue::Incomplete {
+                    communicator: instance.communicator,
+                    sequence: instance.sequence,
+                    expected,
+                    found: instance.members.len(),
+                });
+            }
+        }
+        instances.sort_by_key(|instance| (instance.begin(), instance.communicator, instance.sequence));
+        Collectives { instances, issues: self.issues }
+    }
+}
+
+impl Trace {
+    /// Reconstruct the MPI collective operations of the trace.
+    pub fn collectives(&mut self) -> Status<Collectives> {
+        let definitions = self.definition_set()?;
+        let mut matcher = CollectiveMatcher::new(&definitions);
+        let batch_size = self.options().batch_size;
+        for event in self.iter_events_of_kinds(batch_size, EventKindSet::MPI_COLLECTIVE)? {
+            matcher.add_event(&event?);
+        }
+        Ok(matcher.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fixtures::{comm, event, group, inter_comm, mpi_world};
+    use crate::types::{GroupType, Paradigm};
+
+    fn definitions() -> DefinitionSet {
+        let mut definitions = mpi_world(&[0, 1]);
+        definitions.extend([
+            group(2, GroupType::CommSelf, Paradigm::Mpi, vec![]),
+            group(3, GroupType::CommGroup, Paradigm::Mpi, vec![0]),
+            group(4, GroupType::CommGroup, Paradigm::Mpi, vec![1]),
+            comm(1, 2, None),
+            // Between location 0 and location 1, each alone in its group
+            inter_comm(2, 3, 4, 0),
+        ]);
+        definitions.into()
+    }
+
+    fn end(location: u64, time: u64, collective_op: CollectiveOp, communicator: u32, root: u32) -> Event {
+        event(location, time, EventKind::MpiCollectiveEnd { collective_op, communicator: OTF2_CommRef(communicator), root, size_sent: 8, size_received: 4 })
+    }
+
+    #[test]
+    fn groups_members_by_sequence_on_communicator() {
+        let mut matcher = CollectiveMatcher::new(&definitions());
+        let begin = EventKind::MpiCollectiveBegin {};
+        let events = [
+            event(0, 0, begin.clone()), end(0, 5, CollectiveOp::Barrier, 0, OTF2_UNDEFINED_UINT32),
+            event(0, 6, begin.clone()), end(0, 7, CollectiveOp::Barrier, 1, OTF2_UNDEFINED_UINT32),
+            event(1, 3, begin.clone()), end(1, 5, CollectiveOp::Barrier, 0, OTF2_UNDEFINED_UINT32),
+            event(1, 8, EventKind::NonBlockingCollectiveRequest { request_id: 4 }),
+            event(0, 8, begin), end(0, 9, CollectiveOp::Bcast, 0, 1),
+            event(1, 12, EventKind::NonBlockingCollectiveComplete {
+                collective_op: CollectiveOp::Bcast,
+                communicator: OTF2_CommRef(0),
+                root: 1,
+                size_sent: 8,
+                size_received: 0,
+                request_id: 4,
+            }),
+        ];
+        events.iter().for_each(|event| matcher.add_event(event));
+        let collectives = matcher.finish();
+        assert!(collectives.issues.is_empty(), "{:?}", collectives.issues);
+        let summary: Vec<_> = collectives.instances.iter()
+            .map(|instance| (instance.op, instance.communicator.0, instance.sequence, instance.members.len()))
+            .collect();
+        assert_eq!(summary, [(CollectiveOp::Barrier, 0, 0, 2), (CollectiveOp::Barrier, 1, 0, 1), (CollectiveOp::Bcast, 0, 1, 2)]);
+        let barrier = &collectives.instances[0];
+        assert_eq!((barrier.begin(), barrier.last_begin(), barrier.end(), barrier.root), (0, 3, 5, None));
+        let bcast = &collectives.instances[2];
+        assert_eq!(bcast.root, Some(OTF2_LocationRef(1)));
+        assert_eq!(bcast.member(OTF2_LocationRef(1)).map(|member| (member.begin, member.end)), Some((8, 12)));
+        assert_eq!((bcast.bytes_sent(), bcast.bytes_received()), (16, 4));
+    }
+
+    #[test]
+    fn reports_inconsistent_collectives() {
+        let mut matcher = CollectiveMatcher::new(&definitions());
+        let begin = EventKind::MpiCollectiveBegin {};
+        let events = [
+            event(0, 0, begin.clone()), end(0, 1, CollectiveOp::Barrier, 0, OTF2_UNDEFINED_UINT32),
+            event(1, 0, begin.clone()), end(1, 1, CollectiveOp::Allreduce, 0, OTF2_UNDEFINED_UINT32),
+            end(1, 2, CollectiveOp::Barrier, 0, OTF2_UNDEFINED_UINT32),
+            event(0, 3, begin.clone()), end(0, 4, CollectiveOp::Barrier, 0, OTF2_UNDEFINED_UINT32),
+            event(1, 5, begin),
+        ];
+        events.iter().for_each(|event| matcher.add_event(event));
+        let collectives = matcher.finish();
+        let comm = OTF2_CommRef(0);
+        assert_eq!(collectives.issues, [
+            CollectiveIssue::UnpairedEnd { location: OTF2_LocationRef(1), time: 2 },
+            CollectiveIssue::UnpairedBegin { location: OTF2_LocationRef(1), time: 5 },
+            CollectiveIssue::MismatchedOperation { communicator: comm, sequence: 0, location: OTF2_LocationRef(1), op: CollectiveOp::Allreduce },
+            CollectiveIssue::Incomplete { communicator: comm, sequence: 1, expected: 2, found: 1 },
+        ]);
+    }
+
+    #[test]
+    fn inter_communicator_members_share_instances() {
+        let mut matcher = CollectiveMatcher::new(&definitions());
+        let begin = EventKind::MpiCollectiveBegin {};
+        let events = [
+            event(0, 0, begin.clone()), end(0, 2, CollectiveOp::Barrier, 2, OTF2_UNDEFINED_UINT32),
+            event(1, 1, begin.clone()), end(1, 2, CollectiveOp::Barrier, 2, OTF2_UNDEFINED_UINT32),
+            event(0, 3, begin), end(0, 4, CollectiveOp::Barrier, 2, OTF2_UNDEFINED_UINT32),
+        ];
+        events.iter().for_each(|event| matcher.add_event(event));
+        let collectives = matcher.finish();
+        let sizes: Vec<_> = collectives.instances.iter().map(|instance| (instance.sequence, instance.members.len())).collect();
+        assert_eq!(sizes, [(0, 2), (1, 1)]);
+        assert_eq!(collectives.issues, [CollectiveIssue::Incomplete { communicator: OTF2_CommRef(2), sequence: 1, expected: 2, found: 1 }]);
+    }
+}
@@ -0,0 +1,392 @@
+//! Find the critical path of a trace: the chain of work and dependencies across locations which
+//! determined when the last location finished.
+
+use crate::internal::*;
+use crate::definition::{CommResolver, DefinitionSet};
+use crate::error::Status;
+use crate::event::{Event, EventKind, EventKindId, EventKindSet};
+use crate::reader::Trace;
+use crate::types::CollectiveOp;
+
+use super::callstack::CallStackTracker;
+use super::collectives::{CollectiveInstance, CollectiveMatcher};
+use super::messages::MessageMatcher;
+use super::time_scale;
+
+use std::collections::BTreeMap;
+
+/// A stretch of the critical path on one location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathSegment {
+    pub location: OTF2_LocationRef,
+    pub begin: OTF2_TimeStamp,
+    pub end: OTF2_TimeStamp,
+}
+
+/// The time the critical path spent in one region, not counting the regions called from it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CriticalPathRegion {
+    /// `None` for time spent outside of any region.
+    pub region: Option<OTF2_RegionRef>,
+    pub name: String,
+    pub time: u64,
+}
+
+/// The critical path of a trace. Times are in timer ticks.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CriticalPath {
+    /// Ticks per second, if the trace defines its clock.
+    pub timer_resolution: Option<u64>,
+    /// Segments in time order. Each begins where the dependency which led to it was satisfied.
+    pub segments: Vec<PathSegment>,
+    /// The regions in decreasing order of their time on the path.
+    pub regions: Vec<CriticalPathRegion>,
+}
+
+impl CriticalPath {
+    /// The time between the start and end of the path.
+    pub fn length(&self) -> u64 {
+        match (self.segments.first(), self.segments.last()) {
+            (Some(first), Some(last)) => last.end - first.begin,
+            _ => 0,
+        }
+    }
+
+    pub fn region(&self, region: OTF2_RegionRef) -> Option<&CriticalPathRegion> {
+        self.regions.iter().find(|entry| entry.region == Some(region))
+    }
+}
+
+/// Shows the time of each region on the path as a table, in seconds if the timer resolution is
+/// known.
+impl std::fmt::Display for CriticalPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (unit, scale) = time_scale(self.timer_resolution);
+        let length = self.length();
+        let width = self.regions.iter().map(|entry| entry.name.len()).chain([6]).max().unwrap_or_default();
+        writeln!(f, "{:<width$} {:>14} {:>8}", "Region", format!("Time ({unit})"), "Share")?;
+        for entry in &self.regions {
+            let share = if length == 0 { 0.0 } else { 100.0 * entry.time as f64 / length as f64 };
+            writeln!(f, "{:<width$} {:>14.6} {:>7.2}%", entry.name, entry.time as f64 * scale, share)?;
+        }
+        Ok(())
+    }
+}
+
+/// The event at `to` couldn't happen before the event at `from`. The location of `to` was
+/// waiting for it from `wait_begin`.
+#[derive(Debug, Clone, Copy)]
+struct Dependency {
+    from: (OTF2_LocationRef, OTF2_TimeStamp),
+    to: OTF2_TimeStamp,
+    wait_begin: OTF2_TimeStamp,
+}
+
+/// Finds the critical path from the region, MPI and thread events of a trace.
+///
+/// Locations depend on each other through matched MPI messages, synchronising collectives,
+/// thread forks and joins, and the creation of tasks which another thread executes. Walking
+/// backwards from the last event of the trace, the path stays on a location until the latest
+/// dependency it had to wait for, which is one whose source happened after the location started
+/// waiting in the region enclosing its target.
+#[derive(Debug, Default)]
+pub struct CriticalPathAnalyzer {
+    tracker: CallStackTracker,
+    messages: MessageMatcher,
+    collectives: CollectiveMatcher,
+    comms: CommResolver,
+    // First and last event of each location
+    spans: BTreeMap<OTF2_LocationRef, (OTF2_TimeStamp, OTF2_TimeStamp)>,
+    // The innermost region of each location from each time on
+    timelines: BTreeMap<OTF2_LocationRef, Vec<(OTF2_TimeStamp, Option<OTF2_RegionRef>)>>,
+    // The start of the region enclosing each receive
+    wait_begins: BTreeMap<(OTF2_LocationRef, OTF2_TimeStamp), OTF2_TimeStamp>,
+    dependencies: BTreeMap<OTF2_LocationRef, Vec<Dependency>>,
+    forks: BTreeMap<OTF2_LocationRef, OTF2_TimeStamp>,
+    // The ThreadTeamEnd events of each team since its last join
+    team_ends: BTreeMap<OTF2_CommRef, Vec<(OTF2_LocationRef, OTF2_TimeStamp)>>,
+    // The creation of tasks not yet started, by team, creating thread and generation
+    tasks: BTreeMap<(OTF2_CommRef, u32, u32), (OTF2_LocationRef, OTF2_TimeStamp)>,
+}
+
+impl CriticalPathAnalyzer {
+    /// The events needed to find the critical path.
+    pub const EVENT_KINDS: EventKindSet = EventKindSet::REGIONS
+        .union(EventKindSet::MPI_POINT_TO_POINT)
+        .union(EventKindSet::MPI_COLLECTIVE)
+        .with(EventKindId::ThreadFork)
+        .with(EventKindId::ThreadJoin)
+        .with(EventKindId::ThreadTeamBegin)
+        .with(EventKindId::ThreadTeamEnd)
+        .with(EventKindId::ThreadTaskCreate)
+        .with(EventKindId::ThreadTaskSwitch)
+        .with(EventKindId::ThreadTaskComplete);
+
+    pub fn new(definitions: &DefinitionSet) -> Self {
+        Self {
+            messages: MessageMatcher::new(definitions),
+            collectives: CollectiveMatcher::new(definitions),
+            comms: CommResolver::new(definitions),
+            ..Self::default()
+        }
+    }
+
+    pub fn add_event(&mut self, event: &Event) {
+        let (location, time) = (event.data.location, event.data.time);
+        let span = self.spans.entry(location).or_insert((time, time));
+        span.1 = time;
+        // Where the location started waiting, if it was waiting at this event
+        let wait_begin = self.tracker.top(location).map_or(time, |(_, begin)| begin);
+        match event.kind {
+            EventKind::MpiRecv { .. } | EventKind::MpiIrecv { .. } => {
+                self.wait_begins.insert((location, time), wait_begin);
+            }
+            EventKind::ThreadFork { .. } => {
+                self.forks.insert(location, time);
+            }
+            EventKind::ThreadTeamBegin { thread_team } => {
+                let master = self.comms.locations(thread_team).and_then(|members| members.first().copied());
+                let fork = master.filter(|&master| master != location).and_then(|master| Some((master, *self.forks.get(&master)?)));
+                if let Some(fork) = fork {
+                    // The thread was idle until the fork
+                    self.depend(location, time, fork, 0);
+                }
+            }
+            EventKind::ThreadTeamEnd { thread_team } => {
+                self.team_ends.entry(thread_team).or_default().push((location, time));
+            }
+            EventKind::ThreadJoin { .. } => {
+                let teams: Vec<_> = self.team_ends.keys().copied()
+                    .filter(|&team| self.comms.locations(team).and_then(|members| members.first()) == Some(&location))
+                    .collect();
+                for team in teams {
+                    let ends = self.team_ends.remove(&team).unwrap_or_default();
+                    let own_end = ends.iter().find(|&&(member, _)| member == location).map_or(time, |&(_, end)| end);
+                    for (member, end) in ends.into_iter().filter(|&(member, _)| member != location) {
+                        self.depend(location, time, (member, end), own_end);
+                    }
+                }
+            }
+            EventKind::ThreadTaskCreate { thread_team, creating_thread, generation_number } => {
+                self.tasks.insert((thread_team, creating_thread, generation_number), (location, time));
+            }
+            EventKind::ThreadTaskSwitch { thread_team, creating_thread, generation_number }
+            | EventKind::ThreadTaskComplete { thread_team, creating_thread, generation_number } => {
+                let created = self.tasks.remove(&(thread_team, creating_thread, generation_number));
+                if let Some(created) = created.filter(|&(creator, _)| creator != location) {
+                    self.depend(location, time, created, wait_begin);
+                }
+            }
+            _ => {}
+        }
+        self.messages.add_event(event);
+        self.collectives.add_event(event);
+        let changed = matches!(event.kind, EventKind::Enter { .. } | EventKind::Leave { .. });
+        self.tracker.add_event(event);
+        if changed {
+            let region = self.tracker.top(location).map(|(region, _)| region);
+            self.timelines.entry(location).or_default().push((time, region));
+        }
+    }
+
+    fn depend(&mut self, location: OTF2_LocationRef, to: OTF2_TimeStamp, from: (OTF2_LocationRef, OTF2_TimeStamp), wait_begin: OTF2_TimeStamp) {
+        self.dependencies.entry(location).or_default().push(Dependency { from, to, wait_begin });
+    }
+
+    fn collective_dependencies(&mut self, instance: &CollectiveInstance) {
+        if instance.non_blocking {
+            return;
+        }
+        // The member other than `location` which began last
+        let last_arrival = |location| {
+            instance.members.iter()
+                .filter(|other| other.location != location)
+                .map(|other| (other.location, other.begin))
+                .max_by_key(|&(_, begin)| begin)
+        };
+        let root = instance.root.and_then(|root| instance.member(root));
+        for member in &instance.members {
+            let from = match (instance.op, root) {
+                // Only the root waits for the others
+                (CollectiveOp::Gather | CollectiveOp::Gatherv | CollectiveOp::Reduce, Some(root)) => {
+                    if root.location == member.location { last_arrival(member.location) } else { None }
+                }
+                // Everyone waits for the root
+                (CollectiveOp::Bcast | CollectiveOp::Scatter | CollectiveOp::Scatterv, Some(root)) => {
+                    (root.location != member.location).then_some((root.location, root.begin))
+                }
+                _ => last_arrival(member.location),
+            };
+            if let Some(from) = from {
+                self.depend(member.location, member.end, from, member.begin);
+            }
+        }
+    }
+
+    pub fn finish(mut self, definitions: &DefinitionSet) -> CriticalPath {
+        let messages = std::mem::take(&mut self.messages).finish();
+        for message in &messages.messages {
+            let receive = (message.receiver_location, message.recv_time);
+            let wait_begin = self.wait_begins.get(&receive).copied().unwrap_or(message.recv_time);
+            self.depend(message.receiver_location, message.recv_time, (message.sender_location, message.send_time), wait_begin);
+        }
+        let collectives = std::mem::take(&mut self.collectives).finish();
+        for instance in &collectives.instances {
+            self.collective_dependencies(instance);
+        }
+        for dependencies in self.dependencies.values_mut() {
+            dependencies.sort_by_key(|dependency| dependency.to);
+        }
+        let segments = self.walk();
+        let mut times: BTreeMap<Option<OTF2_RegionRef>, u64> = BTreeMap::new();
+        for segment in &segments {
+            self.attribute(segment, &mut times);
+        }
+        let mut regions: Vec<_> = times.into_iter()
+            .filter(|&(_, time)| time > 0)
+            .map(|(region, time)| CriticalPathRegion {
+                region,
+                name: match region {
+                    Some(region) => definitions.region_label(region),
+                    None => String::from("(no region)"),
+                },
+                time,
+            })
+            .collect();
+        regions.sort_by(|a, b| b.time.cmp(&a.time).then(a.region.cmp(&b.region)));
+        CriticalPath {
+            timer_resolution: definitions.timer_resolution(),
+            segments,
+            regions,
+        }
+    }
+
+    /// Walk backwards from the last event of the trace, returning the segments in time order.
+    fn walk(&self) -> Vec<PathSegment> {
+        let mut segments = Vec::new();
+        let Some((mut location, mut time)) = self.spans.iter().map(|(&location, &(_, last))| (location, last)).max_by_key(|&(_, last)| last) else {
+            return segments;
+        };
+        loop {
+            let dependencies = self.dependencies.get(&location).map_or(&[][..], Vec::as_slice);
+            let before = dependencies.partition_point(|dependency| dependency.to <= time);
+            let waited = dependencies[..before].iter().rev()
+                .find(|dependency| dependency.from.1 > dependency.wait_begin && dependency.from.1 < time);
+            match waited {
+                Some(dependency) => {
+                    segments.push(PathSegment { location, begin: dependency.from.1, end: time });
+                    (location, time) = dependency.from;
+                }
+                None => {
+                    let first = self.spans.get(&location).map_or(time, |&(first, _)| first);
+                    segments.push(PathSegment { location, begin: first.min(time), end: time });
+                    break;
+                }
+            }
+        }
+        segments.reverse();
+        segments
+    }
+
+    /// Add the time of `segment` to the innermost region open at each moment.
+    fn attribute(&self, segment: &PathSegment, times: &mut BTreeMap<Option<OTF2_RegionRef>, u64>) {
+        let timeline = self.timelines.get(&segment.location).map_or(&[][..], Vec::as_slice);
+        let start = timeline.partition_point(|&(time, _)| time <= segment.begin);
+        let mut region = start.checked_sub(1).and_then(|index| timeline[index].1);
+        let mut from = segment.begin;
+        for &(time, next) in timeline[start..].iter().take_while(|&&(time, _)| time < segment.end) {
+            *times.entry(region).or_default() += time - from;
+            (from, region) = (time, next);
+        }
+        *times.entry(region).or_default() += segment.end - from;
+    }
+}
+
+impl Trace {
+    /// Find the critical path of the trace.
+    pub fn critical_path(&mut self) -> Status<CriticalPath> {
+        let definitions = self.definition_set()?;
+        let mut analyzer = CriticalPathAnalyzer::new(&definitions);
+        let batch_size = self.options().batch_size;
+        for event in self.iter_events_of_kinds(batch_size, CriticalPathAnalyzer::EVENT_KINDS)? {
+            analyzer.add_event(&event?);
+        }
+        Ok(analyzer.finish(&definitions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fixtures::{comm, enter, event, group, leave, mpi_world};
+    use crate::types::{GroupType, Paradigm};
+
+    // Locations 0 and 1 as MPI ranks on communicator 0 and as a thread team on communicator 1
+    fn definitions() -> DefinitionSet {
+        let mut definitions = mpi_world(&[0, 1]);
+        definitions.extend([
+            group(2, GroupType::CommLocations, Paradigm::OpenMp, vec![0, 1]),
+            group(3, GroupType::CommGroup, Paradigm::OpenMp, vec![0, 1]),
+            comm(1, 3, None),
+        ]);
+        definitions.into()
+    }
+
+    const MAIN: u32 = 0;
+    const COMPUTE: u32 = 1;
+    const SEND: u32 = 2;
+    const RECV: u32 = 3;
+
+    fn segments(path: &CriticalPath) -> Vec<(u64, u64, u64)> {
+        path.segments.iter().map(|segment| (segment.location.0, segment.begin, segment.end)).collect()
+    }
+
+    #[test]
+    fn follows_a_late_message() {
+        let mut analyzer = CriticalPathAnalyzer::new(&definitions());
+        let communicator = OTF2_CommRef(0);
+        let events = [
+            enter(0, 0, MAIN), enter(0, 0, COMPUTE), enter(1, 0, MAIN), enter(1, 2, RECV),
+            leave(0, 8, COMPUTE), enter(0, 8, SEND),
+            event(0, 8, EventKind::MpiSend { receiver: 1, communicator, msg_tag: 0, msg_length: 8 }),
+            leave(0, 9, SEND), leave(0, 9, MAIN),
+            event(1, 10, EventKind::MpiRecv { sender: 0, communicator, msg_tag: 0, msg_length: 8 }),
+            leave(1, 10, RECV), enter(1, 10, COMPUTE), leave(1, 20, COMPUTE), leave(1, 20, MAIN),
+        ];
+        events.iter().for_each(|event| analyzer.add_event(event));
+        let path = analyzer.finish(&DefinitionSet::new());
+        assert_eq!(segments(&path), [(0, 0, 8), (1, 8, 20)]);
+        assert_eq!(path.length(), 20);
+        assert_eq!(path.region(OTF2_RegionRef(COMPUTE)).map(|entry| entry.time), Some(18));
+        assert_eq!(path.region(OTF2_RegionRef(RECV)).map(|entry| entry.time), Some(2));
+        assert_eq!(path.region(OTF2_RegionRef(SEND)), None);
+    }
+
+    #[test]
+    fn follows_forks_tasks_and_joins() {
+        let mut analyzer = CriticalPathAnalyzer::new(&definitions());
+        let (thread_team, model) = (OTF2_CommRef(1), Paradigm::OpenMp);
+        let task = EventKind::ThreadTaskSwitch { thread_team, creating_thread: 0, generation_number: 1 };
+        let events = [
+            event(0, 5, EventKind::ThreadFork { model, number_of_requested_threads: 2 }),
+            event(0, 5, EventKind::ThreadTeamBegin { thread_team }),
+            event(1, 6, EventKind::ThreadTeamBegin { thread_team }),
+            enter(1, 6, COMPUTE),
+            event(0, 7, EventKind::ThreadTaskCreate { thread_team, creating_thread: 0, generation_number: 1 }),
+            event(0, 10, EventKind::ThreadTeamEnd { thread_team }),
+            event(1, 12, task),
+            leave(1, 30, COMPUTE),
+            event(1, 30, EventKind::ThreadTeamEnd { thread_team }),
+            event(0, 31, EventKind::ThreadJoin { model }),
+        ];
+        events.iter().for_each(|event| analyzer.add_event(event));
+        let path = analyzer.finish(&DefinitionSet::new());
+        assert_eq!(segments(&path), [(0, 5, 7), (1, 7, 30), (0, 30, 31)]);
+        assert_eq!(path.length(), 26);
+        assert_eq!(path.region(OTF2_RegionRef(COMPUTE)).map(|entry| entry.time), Some(23));
+        assert_eq!(path.regions.iter().find(|entry| entry.region.is_none()).map(|entry| entry.time), Some(3));
+    }
+}
@@ -0,0 +1,64 @@
+//! Events and definitions shared by the analysis tests.
+
+use crate::attribute::Attributes;
+use crate::definition::{CommDef, Definition, GroupDef, InterCommDef};
+use crate::event::{Event, EventKind};
+use crate::internal::*;
+use crate::types::{CommFlags, GroupFlags, GroupType, Paradigm};
+
+pub(crate) fn event(location: u64, time: u64, kind: EventKind) -> Event {
+    Event::new(OTF2_LocationRef(location), time, Attributes::default(), kind)
+}
+
+pub(crate) fn enter(location: u64, time: u64, region: u32) -> Event {
+    event(location, time, EventKind::Enter { region: OTF2_RegionRef(region) })
+}
+
+pub(crate) fn leave(location: u64, time: u64, region: u32) -> Event {
+    event(location, time, EventKind::Leave { region: OTF2_RegionRef(region) })
+}
+
+pub(crate) fn send(location: u64, time: u64, communicator: u32, receiver: u32, msg_tag: u32, msg_length: u64) -> Event {
+    event(location, time, EventKind::MpiSend { receiver, communicator: OTF2_CommRef(communicator), msg_tag, msg_length })
+}
+
+pub(crate) fn recv(location: u64, time: u64, communicator: u32, sender: u32, msg_tag: u32, msg_length: u64) -> Event {
+    event(location, time, EventKind::MpiRecv { sender, communicator: OTF2_CommRef(communicator), msg_tag, msg_length })
+}
+
+pub(crate) fn group(defn: u32, group_type: GroupType, paradigm: Paradigm, members: Vec<u64>) -> Definition {
+    Definition::Group {
+        defn: OTF2_GroupRef(defn),
+        value: GroupDef { name: OTF2_StringRef(0), group_type, paradigm, group_flags: GroupFlags::empty(), members },
+    }
+}
+
+pub(crate) fn comm(defn: u32, group: u32, parent: Option<u32>) -> Definition {
+    Definition::Comm {
+        defn: OTF2_CommRef(defn),
+        value: CommDef { name: OTF2_StringRef(0), group: OTF2_GroupRef(group), parent: parent.map(OTF2_CommRef), flags: CommFlags::empty() },
+    }
+}
+
+pub(crate) fn inter_comm(defn: u32, group_a: u32, group_b: u32, common_communicator: u32) -> Definition {
+    Definition::InterComm {
+        defn: OTF2_CommRef(defn),
+        value: InterCommDef {
+            name: OTF2_StringRef(0),
+            group_a: OTF2_GroupRef(group_a),
+            group_b: OTF2_GroupRef(group_b),
+            common_communicator: Some(OTF2_CommRef(common_communicator)),
+            flags: CommFlags::empty(),
+        },
+    }
+}
+
+/// An MPI world of `locations`: group 0 lists them, and communicator 0 spans them through group 1
+/// in that rank order. Tests add their own groups and communicators from 2 on.
+pub(crate) fn mpi_world(locations: &[u64]) -> Vec<Definition> {
+    vec![
+        group(0, GroupType::CommLocations, Paradigm::Mpi, locations.to_vec()),
+        group(1, GroupType::CommGroup, Paradigm::Mpi, (0..locations.len() as u64).collect()),
+        comm(0, 1, None),
+    ]
+}
@@ -0,0 +1,303 @@
+//! Load-imbalance statistics: how unevenly the exclusive time of each region is spread over
+//! locations and location groups.
+
+use crate::internal::*;
+use crate::definition::DefinitionSet;
+use crate::error::Status;
+use crate::reader::Trace;
+
+use super::profile::FlatProfile;
+use super::time_scale;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The distribution of one region's exclusive time over locations or location groups. Times
+/// are in timer ticks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImbalanceStats {
+    /// The number of locations or groups, including those which never visited the region.
+    pub count: usize,
+    pub total: u64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: u64,
+    pub max: u64,
+    /// The reference of the location or group with the most time.
+    pub most_loaded: u64,
+    /// The reference of the location or group with the least time.
+    pub least_loaded: u64,
+}
+
+impl ImbalanceStats {
+    /// Compute the statistics of `(id, time)` pairs, or `None` if there are none.
+    pub fn from_loads(loads: impl IntoIterator<Item = (u64, u64)>) -> Option<Self> {
+        let loads: Vec<_> = loads.into_iter().collect();
+        let (most_loaded, max) = loads.iter().copied().max_by_key(|&(id, time)| (time, std::cmp::Reverse(id)))?;
+        let (least_loaded, min) = loads.iter().copied().min_by_key(|&(id, time)| (time, id))?;
+        let total: u64 = loads.iter().map(|&(_, time)| time).sum();
+        let mean = total as f64 / loads.len() as f64;
+        let variance = loads.iter().map(|&(_, time)| (time as f64 - mean).powi(2)).sum::<f64>() / loads.len() as f64;
+        Some(Self { count: loads.len(), total, mean, std_dev: variance.sqrt(), min, max, most_loaded, least_loaded })
+    }
+
+    /// The time the most loaded member spent beyond the mean, which perfect balance would save.
+    pub fn imbalance_time(&self) -> f64 {
+        self.max as f64 - self.mean
+    }
+
+    /// The ratio of the maximum to the mean, 1 for perfect balance.
+    pub fn max_over_mean(&self) -> f64 {
+        if self.mean == 0.0 { 1.0 } else { self.max as f64 / self.mean }
+    }
+
+    /// The maximum's excess over the mean as a percentage of the maximum, 0 for perfect balance.
+    pub fn percent_imbalance(&self) -> f64 {
+        if self.max == 0 { 0.0 } else { 100.0 * self.imbalance_time() / self.max as f64 }
+    }
+}
+
+/// The load balance of one region.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionImbalance {
+    pub region: OTF2_RegionRef,
+    pub name: String,
+    pub per_location: ImbalanceStats,
+    /// The time of each location group is the sum over its locations. `None` if no location is
+    /// in a defined group.
+    pub per_location_group: Option<ImbalanceStats>,
+}
+
+impl RegionImbalance {
+    /// The statistics over locations or location groups, `None` for groups if no location is in
+    /// a defined group.
+    pub fn stats(&self, level: ImbalanceLevel) -> Option<&ImbalanceStats> {
+        match level {
+            ImbalanceLevel::Location => Some(&self.per_location),
+            ImbalanceLevel::LocationGroup => self.per_location_group.as_ref(),
+        }
+    }
+}
+
+/// Whether an [`ImbalanceReport`] is sorted and shown by its per-location or per-location-group
+/// statistics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImbalanceLevel {
+    #[default]
+    Location,
+    LocationGroup,
+}
+
+/// What to sort an [`ImbalanceReport`] by. Every order but `Name` puts the largest first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImbalanceOrder {
+    #[default]
+    ImbalanceTime,
+    PercentImbalance,
+    MaxOverMean,
+    Total,
+    Name,
+}
+
+/// The load balance of every region, over the locations which entered any region.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImbalanceReport {
+    /// Ticks per second, if the trace defines its clock.
+    pub timer_resolution: Option<u64>,
+    /// In decreasing order of imbalance time, unless sorted otherwise.
+    pub regions: Vec<RegionImbalance>,
+    /// The statistics used by [`ImbalanceReport::sort_by`] and shown by `Display`.
+    pub level: ImbalanceLevel,
+}
+
+impl ImbalanceReport {
+    pub fn from_profile(profile: &FlatProfile, definitions: &DefinitionSet) -> Self {
+        let locations: BTreeSet<_> = profile.regions.iter().flat_map(|region| region.per_location.keys().copied()).collect();
+        let groups: BTreeMap<_, _> = locations.iter()
+            .filter_map(|location| Some((*location, definitions.locations.get(location)?.location_group)))
+            .collect();
+        let regions = profile.regions.iter()
+            .filter_map(|region| {
+                let time = |location| region.per_location.get(&location).map_or(0, |stats| stats.exclusive);
+                let per_location = ImbalanceStats::from_loads(locations.iter().map(|&location| (location.0, time(location))))?;
+                let mut group_times: BTreeMap<OTF2_LocationGroupRef, u64> = BTreeMap::new();
+                for (&location, &group) in &groups {
+                    *group_times.entry(group).or_default() += time(location);
+                }
+                Some(RegionImbalance {
+                    region: region.region,
+                    name: region.name.clone(),
+                    per_location,
+                    per_location_group: ImbalanceStats::from_loads(group_times.into_iter().map(|(group, time)| (u64::from(group.0), time))),
+                })
+            })
+            .collect();
+        let mut report = Self { timer_resolution: profile.timer_resolution, regions, level: ImbalanceLevel::Location };
+        report.sort_by(ImbalanceOrder::ImbalanceTime);
+        report
+    }
+
+    pub fn region(&self, region: OTF2_RegionRef) -> Option<&RegionImbalance> {
+        self.regions.iter().find(|imbalance| imbalance.region == region)
+    }
+
+    /// Sort the regions by their statistics at `self.level`. Regions without statistics at that
+    /// level come last.
+    pub fn sort_by(&mut self, order: ImbalanceOrder) {
+        let level = self.level;
+        let key = |imbalance: &RegionImbalance| {
+            let Some(stats) = imbalance.stats(level) else {
+                return f64::NEG_INFINITY;
+            };
+            match order {
+                ImbalanceOrder::ImbalanceTime => stats.imbalance_time(),
+                ImbalanceOrder::PercentImbalance => stats.percent_imbalance(),
+                ImbalanceOrder::MaxOverMean => stats.max_over_mean(),
+                ImbalanceOrder::Total => stats.total as f64,
+                ImbalanceOrder::Name => 0.0,
+            }
+        };
+        match order {
+            ImbalanceOrder::Name => self.regions.sort_by(|a, b| a.name.cmp(&b.name)),
+            _ => self.regions.sort_by(|a, b| key(b).total_cmp(&key(a)).then(a.region.cmp(&b.region))),
+        }
+    }
+}
+
+/// Shows the statistics of each region at `self.level` as a table, in seconds if the timer
+/// resolution is known. Regions without statistics at that level are left out.
+impl std::fmt::Display for ImbalanceReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (unit, scale) = time_scale(self.timer_resolution);
+        let width = self.regions.iter().map(|imbalance| imbalance.name.len()).chain([6]).max().unwrap_or_default();
+        writeln!(
+            f,
+            "{:<width$} {:>14} {:>14} {:>14} {:>14} {:>9} {:>8} {:>12} {:>12}",
+            "Region", format!("Mean ({unit})"), "Max", "Imbalance", "Std dev", "Max/mean", "%Imbal", "Most loaded", "Least loaded"
+        )?;
+        for imbalance in &self.regions {
+            let Some(stats) = imbalance.stats(self.level) else {
+                continue;
+            };
+            writeln!(
+                f,
+                "{:<width$} {:>14.6} {:>14.6} {:>14.6} {:>14.6} {:>9.3} {:>7.2}% {:>12} {:>12}",
+                imbalance.name,
+                stats.mean * scale,
+                stats.max as f64 * scale,
+                stats.imbalance_time() * scale,
+                stats.std_dev * scale,
+                stats.max_over_mean(),
+                stats.percent_imbalance(),
+                stats.most_loaded,
+                stats.least_loaded,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Trace {
+    /// Compute the load imbalance of each region from the flat profile of the trace.
+    pub fn load_imbalance(&mut self) -> Status<ImbalanceReport> {
+        let definitions = self.definition_set()?;
+        let profile = self.flat_profile_with(&definitions)?;
+        Ok(ImbalanceReport::from_profile(&profile, &definitions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{RegionProfile, RegionStats};
+    use crate::definition::{Definition, LocationDef};
+    use crate::types::LocationType;
+
+    fn stats(exclusive: u64) -> RegionStats {
+        let mut stats = RegionStats::default();
+        stats.add_visit(exclusive, exclusive);
+        stats
+    }
+
+    fn region(region: u32, name: &str, times: &[(u64, u64)]) -> RegionProfile {
+        let per_location: BTreeMap<_, _> = times.iter().map(|&(location, time)| (OTF2_LocationRef(location), stats(time))).collect();
+        let mut total = RegionStats::default();
+        per_location.values().for_each(|stats| total.merge(stats));
+        RegionProfile { region: OTF2_RegionRef(region), name: name.to_string(), total, per_location }
+    }
+
+    // Locations 0 and 1 in group 0, locations 2 and 3 in group 1
+    fn definitions() -> DefinitionSet {
+        (0..4).map(|location| Definition::Location {
+            defn: OTF2_LocationRef(location),
+            value: LocationDef { name: OTF2_StringRef(0), location_type: LocationType::CpuThread, num_events: 0, location_group: OTF2_LocationGroupRef(location as u32 / 2) },
+        }).collect()
+    }
+
+    #[test]
+    fn computes_imbalance_per_location_and_group() {
+        let profile = FlatProfile {
+            timer_resolution: None,
+            regions: vec![
+                region(0, "solve", &[(0, 10), (1, 10), (2, 10), (3, 30)]),
+                region(1, "io", &[(0, 24)]),
+                region(2, "main", &[(0, 5), (1, 5), (2, 5), (3, 5)]),
+            ],
+        };
+        let mut report = ImbalanceReport::from_profile(&profile, &definitions());
+        let order: Vec<_> = report.regions.iter().map(|imbalance| imbalance.name.as_str()).collect();
+        assert_eq!(order, ["io", "solve", "main"]);
+
+        let solve = report.region(OTF2_RegionRef(0)).unwrap();
+        let stats = &solve.per_location;
+        assert_eq!((stats.count, stats.total, stats.min, stats.max), (4, 60, 10, 30));
+        assert_eq!((stats.mean, stats.imbalance_time(), stats.max_over_mean()), (15.0, 15.0, 2.0));
+        assert_eq!(stats.percent_imbalance(), 50.0);
+        assert_eq!((stats.most_loaded, stats.least_loaded), (3, 0));
+        let groups = solve.per_location_group.unwrap();
+        assert_eq!((groups.count, groups.min, groups.max, groups.most_loaded), (2, 20, 40, 1));
+
+        // Locations which never entered a region count as idle in it
+        let io = &report.region(OTF2_RegionRef(1)).unwrap().per_location;
+        assert_eq!((io.count, io.mean, io.least_loaded), (4, 6.0, 1));
+
+        let main = &report.region(OTF2_RegionRef(2)).unwrap().per_location;
+        assert_eq!((main.imbalance_time(), main.percent_imbalance(), main.std_dev), (0.0, 0.0, 0.0));
+
+        report.sort_by(ImbalanceOrder::Name);
+        assert_eq!(report.regions[0].name, "io");
+        report.sort_by(ImbalanceOrder::Total);
+        assert_eq!(report.regions[0].name, "solve");
+        assert_eq!(report.to_string().lines().count(), 4);
+    }
+
+    #[test]
+    fn sorts_and_shows_location_groups() {
+        let profile = FlatProfile {
+            timer_resolution: None,
+            regions: vec![
+                // Balanced within group 0 but absent from group 1
+                region(0, "halo", &[(0, 10), (1, 10)]),
+                region(1, "io", &[(0, 16)]),
+                region(2, "main", &[(0, 5), (1, 5), (2, 5), (3, 5)]),
+            ],
+        };
+        let mut report = ImbalanceReport::from_profile(&profile, &definitions());
+        let order: Vec<_> = report.regions.iter().map(|imbalance| imbalance.name.as_str()).collect();
+        assert_eq!(order, ["io", "halo", "main"]);
+
+        report.level = ImbalanceLevel::LocationGroup;
+        report.sort_by(ImbalanceOrder::ImbalanceTime);
+        let order: Vec<_> = report.regions.iter().map(|imbalance| imbalance.name.as_str()).collect();
+        assert_eq!(order, ["halo", "io", "main"]);
+        let halo = report.regions[0].stats(ImbalanceLevel::LocationGroup).unwrap();
+        assert_eq!((halo.mean, halo.imbalance_time(), halo.most_loaded), (10.0, 10.0, 0));
+        let table = report.to_string();
+        assert_eq!(table.lines().count(), 4);
+        assert!(table.lines().nth(1).unwrap().starts_with("halo"), "{table}");
+    }
+}
@@ -0,0 +1,329 @@
+//! Location by location matrices of the data exchanged by messages and RMA operations.
+
+use crate::internal::*;
+use crate::definition::{CommResolver, DefinitionSet};
+use crate::error::Status;
+use crate::event::{Event, EventKind, EventKindId, EventKindSet};
+use crate::reader::Trace;
+
+use super::messages::{Message, MessageMatcher};
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+/// What the rows and columns of a [`CommMatrix`] stand for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatrixGrouping {
+    #[default]
+    Location,
+    LocationGroup,
+    /// The system-tree node which is the parent of each location group, usually a compute node.
+    SystemTreeNode,
+}
+
+/// A row or column of a [`CommMatrix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatrixMember {
+    /// The reference of the location, location group or system-tree node.
+    pub id: u64,
+    pub name: String,
+}
+
+/// The data sent from one member to another. RMA puts count as data sent by the origin, and
+/// gets as data sent by the target.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatrixEntry {
+    pub sender: u64,
+    pub receiver: u64,
+    pub messages: u64,
+    pub bytes: u64,
+    pub rma_operations: u64,
+    pub rma_bytes: u64,
+}
+
+/// A sparse communication matrix. Members are sorted by id, and entries by sender, then receiver.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommMatrix {
+    pub grouping: MatrixGrouping,
+    pub members: Vec<MatrixMember>,
+    pub entries: Vec<MatrixEntry>,
+    /// The number of messages and RMA operations left out because a location couldn't be
+    /// resolved or grouped.
+    pub skipped: u64,
+}
+
+impl CommMatrix {
+    pub fn entry(&self, sender: u64, receiver: u64) -> Option<&MatrixEntry> {
+        self.entries
+            .binary_search_by_key(&(sender, receiver), |entry| (entry.sender, entry.receiver))
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    pub fn member_name(&self, id: u64) -> Option<&str> {
+        self.members
+            .binary_search_by_key(&id, |member| member.id)
+            .ok()
+            .map(|index| self.members[index].name.as_str())
+    }
+
+    /// Write one line per non-empty entry, after a header line.
+    pub fn write_csv(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "sender,receiver,sender_name,receiver_name,messages,bytes,rma_operations,rma_bytes")?;
+        for entry in &self.entries {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{}",
+                entry.sender,
+                entry.receiver,
+                csv_field(self.member_name(entry.sender).unwrap_or_default()),
+                csv_field(self.member_name(entry.receiver).unwrap_or_default()),
+                entry.messages,
+                entry.bytes,
+                entry.rma_operations,
+                entry.rma_bytes,
+            )?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn write_json(&self, out: impl Write) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(out, self)
+    }
+}
+
+/// Quote a field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Builds a [`CommMatrix`] from matched messages and the RMA put and get events of a trace.
+#[derive(Debug)]
+pub struct CommMatrixBuilder {
+    grouping: MatrixGrouping,
+    members: BTreeMap<u64, String>,
+    groups: BTreeMap<OTF2_LocationRef, u64>,
+    comms: CommResolver,
+    rma_wins: BTreeMap<OTF2_RmaWinRef, OTF2_CommRef>,
+    entries: BTreeMap<(u64, u64), MatrixEntry>,
+    skipped: u64,
+}
+
+impl CommMatrixBuilder {
+    pub fn new(definitions: &DefinitionSet, grouping: MatrixGrouping) -> Self {
+        let name = |string| definitions.string(string).unwrap_or_default().to_string();
+        let members = match grouping {
+            MatrixGrouping::Location => definitions.locations.iter().map(|(defn, def)| (defn.0, name(def.name))).collect(),
+            MatrixGrouping::LocationGroup => definitions.location_groups.iter().map(|(defn, def)| (u64::from(defn.0), name(def.name))).collect(),
+            MatrixGrouping::SystemTreeNode => definitions.system_tree_nodes.iter().map(|(defn, def)| (u64::from(defn.0), name(def.name))).collect(),
+        };
+        let groups = definitions.locations.iter()
+            .filter_map(|(&location, def)| {
+                let group = match grouping {
+                    MatrixGrouping::Location => location.0,
+                    MatrixGrouping::LocationGroup => u64::from(def.location_group.0),
+                    MatrixGrouping::SystemTreeNode => u64::from(definitions.location_groups.get(&def.location_group)?.system_tree_parent.0),
+                };
+                Some((location, group))
+            })
+            .collect();
+        Self {
+            grouping,
+            members,
+            groups,
+            comms: CommResolver::new(definitions),
+            rma_wins: definitions.rma_wins.iter().map(|(&win, def)| (win, def.comm)).collect(),
+            entries: BTreeMap::new(),
+            skipped: 0,
+        }
+    }
+
+    /// The row or column of `location`. Undefined locations have a row of their own when
+    /// grouping by location.
+    fn group(&self, location: OTF2_LocationRef) -> Option<u64> {
+        match self.grouping {
+            MatrixGrouping::Location => Some(location.0),
+            _ => self.groups.get(&location).copied(),
+        }
+    }
+
+    fn entry(&mut self, sender: OTF2_LocationRef, receiver: OTF2_LocationRef) -> Option<&mut MatrixEntry> {
+        let (Some(sender), Some(receiver)) = (self.group(sender), self.group(receiver)) else {
+            self.skipped += 1;
+            return None;
+        };
+        Some(self.entries.entry((sender, receiver)).or_insert(MatrixEntry { sender, receiver, ..MatrixEntry::default() }))
+    }
+
+    pub fn add_message(&mut self, message: &Message) {
+        if let Some(entry) = self.entry(message.sender_location, message.receiver_location) {
+            entry.messages += 1;
+            entry.bytes += message.bytes;
+        }
+    }
+
+    /// Add an `RmaPut` or `RmaGet` event. Events of any other kind are ignored.
+    pub fn add_event(&mut self, event: &Event) {
+        let location = event.data.location;
+        let (win, remote, bytes, put) = match event.kind {
+            EventKind::RmaPut { win, remote, bytes, .. } => (win, remote, bytes, true),
+            EventKind::RmaGet { win, remote, bytes, .. } => (win, remote, bytes, false),
+            _ => return,
+        };
+        let target = self.rma_wins.get(&win).and_then(|&comm| self.comms.location(comm, remote, location));
+        let Some(target) = target else {
+            self.skipped += 1;
+            return;
+        };
+        let (sender, receiver) = if put { (location, target) } else { (target, location) };
+        if let Some(entry) = self.entry(sender, receiver) {
+            entry.rma_operations += 1;
+            entry.rma_bytes += bytes;
+        }
+    }
+
+    pub fn finish(self) -> CommMatrix {
+        let kind = match self.grouping {
+            MatrixGrouping::Location => "location",
+            MatrixGrouping::LocationGroup => "location group",
+            MatrixGrouping::SystemTreeNode => "system tree node",
+        };
+        let mut members = self.members;
+        for &(sender, receiver) in self.entries.keys() {
+            for id in [sender, receiver] {
+                members.entry(id).or_insert_with(|| format!("{kind} {id}"));
+            }
+        }
+        CommMatrix {
+            grouping: self.grouping,
+            members: members.into_iter().map(|(id, name)| MatrixMember { id, name }).collect(),
+            entries: self.entries.into_values().collect(),
+            skipped: self.skipped,
+        }
+    }
+}
+
+impl Trace {
+    /// Match the MPI messages of the trace and add them, with its RMA puts and gets, into a
+    /// communication matrix.
+    pub fn communication_matrix(&mut self, grouping: MatrixGrouping) -> Status<CommMatrix> {
+        let definitions = self.definition_set()?;
+        let mut matcher = MessageMatcher::new(&definitions);
+        let mut builder = CommMatrixBuilder::new(&definitions, grouping);
+        let batch_size = self.options().batch_size;
+        let kinds = EventKindSet::MPI_POINT_TO_POINT.with(EventKindId::RmaPut).with(EventKindId::RmaGet);
+        for event in self.iter_events_of_kinds(batch_size, kinds)? {
+            let event = event?;
+            matcher.add_event(&event);
+            builder.add_event(&event);
+        }
+        matcher.finish().messages.iter().for_each(|message| builder.add_message(message));
+        Ok(builder.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribute::Attributes;
+    use crate::definition::{CommDef, Definition, GroupDef, LocationDef, LocationGroupDef, RmaWinDef};
+    use crate::types::{CommFlags, GroupFlags, GroupType, LocationGroupType, LocationType, Paradigm, RmaWinFlags};
+
+    // Two processes: "rank 0" with locations 0 and 1, and "rank, 1" with location 2
+    fn definitions() -> DefinitionSet {
+        let strings = ["rank 0", "rank, 1", "thread 0", "thread 1", "node"].into_iter().enumerate()
+            .map(|(defn, value)| Definition::String { defn: OTF2_StringRef(defn as u32), value: value.to_string() });
+        let groups = [0, 1].map(|defn| Definition::LocationGroup {
+            defn: OTF2_LocationGroupRef(defn),
+            value: LocationGroupDef {
+                name: OTF2_StringRef(defn),
+                location_group_type: LocationGroupType::Process,
+                system_tree_parent: OTF2_SystemTreeNodeRef(0),
+                creating_location_group: None,
+            },
+        });
+        let locations = [(0, 0, 2), (1, 0, 3), (2, 1, 2)].map(|(defn, group, name)| Definition::Location {
+            defn: OTF2_LocationRef(defn),
+            value: LocationDef { name: OTF2_StringRef(name), location_type: LocationType::CpuThread, num_events: 0, location_group: OTF2_LocationGroupRef(group) },
+        });
+        let comms = [
+            Definition::Group {
+                defn: OTF2_GroupRef(0),
+                value: GroupDef { name: OTF2_StringRef(4), group_type: GroupType::CommLocations, paradigm: Paradigm::Mpi, group_flags: GroupFlags::empty(), members: vec![0, 2] },
+            },
+            Definition::Group {
+                defn: OTF2_GroupRef(1),
+                value: GroupDef { name: OTF2_StringRef(4), group_type: GroupType::CommGroup, paradigm: Paradigm::Mpi, group_flags: GroupFlags::empty(), members: vec![0, 1] },
+            },
+            Definition::Comm { defn: OTF2_CommRef(0), value: CommDef { name: OTF2_StringRef(4), group: OTF2_GroupRef(1), parent: None, flags: CommFlags::empty() } },
+            Definition::RmaWin { defn: OTF2_RmaWinRef(0), value: RmaWinDef { name: OTF2_StringRef(4), comm: OTF2_CommRef(0), flags: RmaWinFlags::empty() } },
+        ];
+        strings.chain(groups).chain(locations).chain(comms).collect()
+    }
+
+    fn message(sender: u64, receiver: u64, bytes: u64) -> Message {
+        Message {
+            sender_location: OTF2_LocationRef(sender),
+            receiver_location: OTF2_LocationRef(receiver),
+            communicator: OTF2_CommRef(0),
+            tag: 0,
+            bytes,
+            send_time: 0,
+            non_blocking: false,
+            send_complete_time: None,
+            recv_request_time: None,
+            recv_time: 1,
+        }
+    }
+
+    fn rma(location: u64, kind: EventKind) -> Event {
+        Event::new(OTF2_LocationRef(location), 0, Attributes::default(), kind)
+    }
+
+    fn build(grouping: MatrixGrouping) -> CommMatrix {
+        let mut builder = CommMatrixBuilder::new(&definitions(), grouping);
+        [message(0, 2, 8), message(0, 2, 16), message(1, 2, 4), message(2, 0, 1)].iter().for_each(|message| builder.add_message(message));
+        // Location 0 puts to and gets from rank 1 of the window, which is location 2
+        builder.add_event(&rma(0, EventKind::RmaPut { win: OTF2_RmaWinRef(0), remote: 1, bytes: 100, matching_id: 0 }));
+        builder.add_event(&rma(0, EventKind::RmaGet { win: OTF2_RmaWinRef(0), remote: 1, bytes: 50, matching_id: 1 }));
+        builder.add_event(&rma(0, EventKind::RmaGet { win: OTF2_RmaWinRef(7), remote: 1, bytes: 50, matching_id: 2 }));
+        builder.finish()
+    }
+
+    #[test]
+    fn aggregates_messages_and_rma_per_location() {
+        let matrix = build(MatrixGrouping::Location);
+        assert_eq!(matrix.entry(0, 2), Some(&MatrixEntry { sender: 0, receiver: 2, messages: 2, bytes: 24, rma_operations: 1, rma_bytes: 100 }));
+        assert_eq!(matrix.entry(2, 0), Some(&MatrixEntry { sender: 2, receiver: 0, messages: 1, bytes: 1, rma_operations: 1, rma_bytes: 50 }));
+        assert_eq!(matrix.entry(1, 0), None);
+        assert_eq!(matrix.skipped, 1);
+        let mut csv = Vec::new();
+        matrix.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("0,2,thread 0,thread 0,2,24,1,100"));
+        assert_eq!(csv.lines().count(), 4);
+    }
+
+    #[test]
+    fn groups_by_location_group_and_node() {
+        let matrix = build(MatrixGrouping::LocationGroup);
+        assert_eq!(matrix.entry(0, 1).map(|entry| (entry.messages, entry.bytes)), Some((3, 28)));
+        let mut csv = Vec::new();
+        matrix.write_csv(&mut csv).unwrap();
+        assert!(String::from_utf8(csv).unwrap().contains("1,0,\"rank, 1\",rank 0,1,1,1,50"));
+        let matrix = build(MatrixGrouping::SystemTreeNode);
+        assert_eq!(matrix.entries.len(), 1);
+        assert_eq!(matrix.entry(0, 0).map(|entry| (entry.messages, entry.rma_operations)), Some((4, 2)));
+        assert_eq!(matrix.member_name(0), Some("system tree node 0"));
+        assert_eq!(matrix.member_name(1), None);
+    }
+}
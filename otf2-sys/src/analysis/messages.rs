@@ -0,0 +1,421 @@
+//! Match MPI point-to-point sends with their receives.
+
+use crate::internal::*;
+use crate::definition::{CommResolver, DefinitionSet};
+use crate::error::Status;
+use crate::event::{Event, EventKind, EventKindSet};
+use crate::reader::Trace;
+
+use std::collections::{BTreeMap, VecDeque};
+
+/// One message, from the send which posted it to the receive which completed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Message {
+    pub sender_location: OTF2_LocationRef,
+    pub receiver_location: OTF2_LocationRef,
+    pub communicator: OTF2_CommRef,
+    pub tag: u32,
+    pub bytes: u64,
+    /// The time of the MpiSend or MpiIsend event.
+    pub send_time: OTF2_TimeStamp,
+    /// Whether the message was sent by MpiIsend, even if its completion wasn't recorded.
+    pub non_blocking: bool,
+    /// The time of the MpiIsendComplete event of a non-blocking send.
+    pub send_complete_time: Option<OTF2_TimeStamp>,
+    /// The time of the MpiIrecvRequest event of a non-blocking receive.
+    pub recv_request_time: Option<OTF2_TimeStamp>,
+    /// The time of the MpiRecv or MpiIrecv event.
+    pub recv_time: OTF2_TimeStamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MessageDirection {
+    Send,
+    Receive,
+}
+
+/// A send or receive which no operation on the other side matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnmatchedOperation {
+    pub direction: MessageDirection,
+    pub location: OTF2_LocationRef,
+    pub time: OTF2_TimeStamp,
+    pub communicator: OTF2_CommRef,
+    /// The rank of the other side in `communicator`, as recorded in the event.
+    pub peer_rank: u32,
+    /// The location of the other side, or `None` if the rank couldn't be resolved.
+    pub peer: Option<OTF2_LocationRef>,
+    pub tag: u32,
+    pub bytes: u64,
+}
+
+/// The result of matching the point-to-point events of a trace.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageMatching {
+    /// Messages in the order they were matched.
+    pub messages: Vec<Message>,
+    pub unmatched: Vec<UnmatchedOperation>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingSend {
+    id: usize,
+    non_blocking: bool,
+    time: OTF2_TimeStamp,
+    bytes: u64,
+    rank: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingRecv {
+    request_time: Option<OTF2_TimeStamp>,
+    time: OTF2_TimeStamp,
+    bytes: u64,
+    rank: u32,
+}
+
+// Communicator, sender, receiver and tag
+type Channel = (OTF2_CommRef, OTF2_LocationRef, OTF2_LocationRef, u32);
+
+/// A receive in the order it was posted on its location, with what it received once complete.
+#[derive(Debug, Clone, Copy)]
+struct PostedRecv {
+    request_id: Option<u64>,
+    request_time: Option<OTF2_TimeStamp>,
+    completed: Option<(OTF2_CommRef, u32, PendingRecv)>,
+}
+
+/// Matches the sends and receives of MPI point-to-point messages.
+///
+/// Operations on the same communicator between the same pair of locations with the same tag are
+/// matched in the order they were posted, following MPI's non-overtaking rule. Since clocks of
+/// different locations may be skewed, a receive may be added before the send it matches.
+/// A completed receive is held back until every receive posted before it on its location has
+/// completed too, so that non-blocking receives waited on out of order still match in the order
+/// of their MpiIrecvRequest events.
+#[derive(Debug, Default)]
+pub struct MessageMatcher {
+    comms: CommResolver,
+    sends: BTreeMap<Channel, VecDeque<PendingSend>>,
+    recvs: BTreeMap<Channel, VecDeque<PendingRecv>>,
+    next_send: usize,
+    // Non-blocking sends not yet completed, by location and request, with the channel they wait
+    // on if not yet matched, and the completion times of sends not yet matched
+    open_isends: BTreeMap<(OTF2_LocationRef, u64), (usize, Option<Channel>)>,
+    send_completions: BTreeMap<usize, OTF2_TimeStamp>,
+    matched_sends: BTreeMap<usize, usize>,
+    // The receives of each location which can't be matched yet, in posting order
+    posted: BTreeMap<OTF2_LocationRef, VecDeque<PostedRecv>>,
+    result: MessageMatching,
+}
+
+impl MessageMatcher {
+    pub fn new(definitions: &DefinitionSet) -> Self {
+        Self { comms: CommResolver::new(definitions), ..Self::default() }
+    }
+
+    pub fn add_event(&mut self, event: &Event) {
+        let (location, time) = (event.data.location, event.data.time);
+        match event.kind {
+            EventKind::MpiSend { receiver, communicator, msg_tag, msg_length } => {
+                let send = self.pending_send(false, time, receiver, msg_length);
+                self.send(location, communicator, msg_tag, send);
+            }
+            EventKind::MpiIsend { receiver, communicator, msg_tag, msg_length, request_id } => {
+                let send = self.pending_send(true, time, receiver, msg_length);
+                let channel = self.send(location, communicator, msg_tag, send);
+                self.open_isends.insert((location, request_id), (send.id, channel));
+            }
+            EventKind::MpiIsendComplete { request_id } => {
+                if let Some((id, _)) = self.open_isends.remove(&(location, request_id)) {
+                    match self.matched_sends.remove(&id) {
+                        Some(message) => self.result.messages[message].send_complete_time = Some(time),
+                        None => { self.send_completions.insert(id, time); },
+                    }
+                }
+            }
+            EventKind::MpiIrecvRequest { request_id } => {
+                let posted = PostedRecv { request_id: Some(request_id), request_time: Some(time), completed: None };
+                self.posted.entry(location).or_default().push_back(posted);
+            }
+            EventKind::MpiRecv { sender, communicator, msg_tag, msg_length } => {
+                let recv = PendingRecv { request_time: None, time, bytes: msg_length, rank: sender };
+                let posted = PostedRecv { request_id: None, request_time: None, completed: Some((communicator, msg_tag, recv)) };
+                self.posted.entry(location).or_default().push_back(posted);
+                self.drain_posted(location);
+            }
+            EventKind::MpiIrecv { sender, communicator, msg_tag, msg_length, request_id } => {
+                let posted = self.posted.entry(location).or_default();
+                let request = posted.iter_mut().find(|posted| posted.request_id == Some(request_id) && posted.completed.is_none());
+                match request {
+                    Some(request) => {
+                        let recv = PendingRecv { request_time: request.request_time, time, bytes: msg_length, rank: sender };
+                        request.completed = Some((communicator, msg_tag, recv));
+                    }
+                    // The request wasn't recorded, so the receive was posted no earlier than now
+                    None => {
+                        let recv = PendingRecv { request_time: None, time, bytes: msg_length, rank: sender };
+                        posted.push_back(PostedRecv { request_id: None, request_time: None, completed: Some((communicator, msg_tag, recv)) });
+                    }
+                }
+                self.drain_posted(location);
+            }
+            EventKind::MpiRequestCancelled { request_id } => {
+                if let Some((id, channel)) = self.open_isends.remove(&(location, request_id)) {
+                    // A cancelled send delivers nothing, so it mustn't match a later receive
+                    if let Some(queue) = channel.and_then(|channel| self.sends.get_mut(&channel)) {
+                        queue.retain(|send| send.id != id);
+                    }
+                }
+                if let Some(posted) = self.posted.get_mut(&location) {
+                    posted.retain(|posted| posted.request_id != Some(request_id) || posted.completed.is_some());
+                    self.drain_posted(location);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn pending_send(&mut self, non_blocking: bool, time: OTF2_TimeStamp, rank: u32, bytes: u64) -> PendingSend {
+        let id = self.next_send;
+        self.next_send += 1;
+        PendingSend { id, non_blocking, time, bytes, rank }
+    }
+
+    /// Match a send, or queue it and return the channel it waits on.
+    fn send(&mut self, location: OTF2_LocationRef, communicator: OTF2_CommRef, tag: u32, send: PendingSend) -> Option<Channel> {
+        let Some(receiver) = self.comms.location(communicator, send.rank, location) else {
+            self.result.unmatched.push(unmatched_send(location, communicator, tag, None, &send));
+            return None;
+        };
+        let channel = (communicator, location, receiver, tag);
+        match self.recvs.get_mut(&channel).and_then(VecDeque::pop_front) {
+            Some(recv) => {
+                self.matched(channel, send, recv);
+                None
+            }
+            None => {
+                self.sends.entry(channel).or_default().push_back(send);
+                Some(channel)
+            }
+        }
+    }
+
+    /// Match the completed receives at the front of the posting order of `location`.
+    fn drain_posted(&mut self, location: OTF2_LocationRef) {
+        while let Some(posted) = self.posted.get_mut(&location) {
+            let Some((communicator, tag, recv)) = posted.front().and_then(|front| front.completed) else {
+                break;
+            };
+            posted.pop_front();
+            self.recv(location, communicator, tag, recv);
+        }
+    }
+
+    fn recv(&mut self, location: OTF2_LocationRef, communicator: OTF2_CommRef, tag: u32, recv: PendingRecv) {
+        let Some(sender) = self.comms.location(communicator, recv.rank, location) else {
+            self.result.unmatched.push(unmatched_recv(location, communicator, tag, None, &recv));
+            return;
+        };
+        let channel = (communicator, sender, location, tag);
+        match self.sends.get_mut(&channel).and_then(VecDeque::pop_front) {
+            Some(send) => self.matched(channel, send, recv),
+            None => self.recvs.entry(channel).or_default().push_back(recv),
+        }
+    }
+
+    fn matched(&mut self, (communicator, sender, receiver, tag): Channel, send: PendingSend, recv: PendingRecv) {
+        let send_complete_time = self.send_completions.remove(&send.id);
+        if send.non_blocking && send_complete_time.is_none() {
+            self.matched_sends.insert(send.id, self.result.messages.len());
+        }
+        self.result.messages.push(Message {
+            sender_location: sender,
+            receiver_location: receiver,
+            communicator,
+            tag,
+            // The receive buffer may be larger than the message, so trust the sender
+            bytes: send.bytes,
+            send_time: send.time,
+            non_blocking: send.non_blocking,
+            send_complete_time,
+            recv_request_time: recv.request_time,
+            recv_time: recv.time,
+        });
+    }
+
+    /// The messages matched so far, and every operation left without a partner.
+    pub fn finish(mut self) -> MessageMatching {
+        // Receives posted but never completed no longer hold back those behind them
+        for posted in self.posted.values_mut() {
+            posted.retain(|posted| posted.completed.is_some());
+        }
+        let locations: Vec<_> = self.posted.keys().copied().collect();
+        locations.into_iter().for_each(|location| self.drain_posted(location));
+        for ((communicator, sender, receiver, tag), sends) in self.sends {
+            self.result.unmatched.extend(sends.iter().map(|send| unmatched_send(sender, communicator, tag, Some(receiver), send)));
+        }
+        for ((communicator, sender, receiver, tag), recvs) in self.recvs {
+            self.result.unmatched.extend(recvs.iter().map(|recv| unmatched_recv(receiver, communicator, tag, Some(sender), recv)));
+        }
+        self.result.unmatched.sort_by_key(|operation| (operation.time, operation.location));
+        self.result
+    }
+}
+
+fn unmatched_send(location: OTF2_LocationRef, communicator: OTF2_CommRef, tag: u32, peer: Option<OTF2_LocationRef>, send: &PendingSend) -> UnmatchedOperation {
+    UnmatchedOperation {
+        direction: MessageDirection::Send,
+        location,
+        time: send.time,
+        communicator,
+        peer_rank: send.rank,
+        peer,
+        tag,
+        bytes: send.bytes,
+    }
+}
+
+fn unmatched_recv(location: OTF2_LocationRef, communicator: OTF2_CommRef, tag: u32, peer: Option<OTF2_LocationRef>, recv: &PendingRecv) -> UnmatchedOperation {
+    UnmatchedOperation {
+        direction: MessageDirection::Receive,
+        location,
+        time: recv.time,
+        communicator,
+        peer_rank: recv.rank,
+        peer,
+        tag,
+        bytes: recv.bytes,
+    }
+}
+
+impl Trace {
+    /// Match the MPI point-to-point sends and receives of the trace into messages.
+    pub fn match_messages(&mut self) -> Status<MessageMatching> {
+        let definitions = self.definition_set()?;
+        let mut matcher = MessageMatcher::new(&definitions);
+        let batch_size = self.options().batch_size;
+        for event in self.iter_events_of_kinds(batch_size, EventKindSet::MPI_POINT_TO_POINT)? {
+            matcher.add_event(&event?);
+        }
+        Ok(matcher.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fixtures::{comm, event, group, mpi_world, recv, send};
+    use crate::types::{GroupType, Paradigm};
+
+    // Ranks of MPI_COMM_WORLD on locations 10, 20 and 30, and a communicator of world ranks 2 and 0
+    fn definitions() -> DefinitionSet {
+        let mut definitions = mpi_world(&[10, 20, 30]);
+        definitions.extend([
+            group(2, GroupType::CommGroup, Paradigm::Mpi, vec![2, 0]),
+            group(3, GroupType::CommSelf, Paradigm::Mpi, vec![]),
+            comm(1, 2, None),
+            comm(2, 3, None),
+        ]);
+        definitions.into()
+    }
+
+    #[test]
+    fn matches_in_order_through_rank_translation() {
+        let mut matcher = MessageMatcher::new(&definitions());
+        let events = [
+            // A receive recorded before its send, as happens with skewed clocks
+            recv(30, 1, 1, 1, 5, 64),
+            send(10, 2, 1, 0, 5, 8),
+            // Two non-blocking sends with the same tag, completed after they are received
+            event(10, 3, EventKind::MpiIsend { receiver: 1, communicator: OTF2_CommRef(0), msg_tag: 0, msg_length: 16, request_id: 1 }),
+            event(10, 4, EventKind::MpiIsend { receiver: 1, communicator: OTF2_CommRef(0), msg_tag: 0, msg_length: 32, request_id: 2 }),
+            event(20, 4, EventKind::MpiIrecvRequest { request_id: 7 }),
+            event(20, 5, EventKind::MpiIrecv { sender: 0, communicator: OTF2_CommRef(0), msg_tag: 0, msg_length: 16, request_id: 7 }),
+            recv(20, 6, 0, 0, 0, 32),
+            event(10, 7, EventKind::MpiIsendComplete { request_id: 2 }),
+            event(10, 8, EventKind::MpiIsendComplete { request_id: 1 }),
+        ];
+        events.iter().for_each(|event| matcher.add_event(event));
+        let matching = matcher.finish();
+        assert!(matching.unmatched.is_empty(), "{:?}", matching.unmatched);
+        let summary: Vec<_> = matching.messages.iter()
+            .map(|message| (message.sender_location.0, message.receiver_location.0, message.bytes, message.send_time, message.recv_time))
+            .collect();
+        assert_eq!(summary, [(10, 30, 8, 2, 1), (10, 20, 16, 3, 5), (10, 20, 32, 4, 6)]);
+        assert!(!matching.messages[0].non_blocking && matching.messages[1].non_blocking);
+        assert_eq!(matching.messages[1].send_complete_time, Some(8));
+        assert_eq!(matching.messages[1].recv_request_time, Some(4));
+        assert_eq!(matching.messages[2].send_complete_time, Some(7));
+        assert_eq!(matching.messages[2].recv_request_time, None);
+    }
+
+    #[test]
+    fn reports_unmatched_operations() {
+        let mut matcher = MessageMatcher::new(&definitions());
+        let events = [
+            send(10, 1, 0, 1, 3, 8),
+            recv(20, 2, 0, 0, 4, 8),
+            // Rank 5 doesn't exist, and communicator 9 isn't defined
+            send(10, 3, 0, 5, 0, 8),
+            recv(10, 4, 9, 0, 0, 8),
+            // Sending to oneself on MPI_COMM_SELF
+            send(30, 5, 2, 0, 0, 8),
+            recv(30, 6, 2, 0, 0, 8),
+        ];
+        events.iter().for_each(|event| matcher.add_event(event));
+        let matching = matcher.finish();
+        assert_eq!(matching.messages.len(), 1);
+        assert_eq!((matching.messages[0].sender_location, matching.messages[0].receiver_location), (OTF2_LocationRef(30), OTF2_LocationRef(30)));
+        let unmatched: Vec<_> = matching.unmatched.iter().map(|operation| (operation.direction, operation.time, operation.peer)).collect();
+        assert_eq!(unmatched, [
+            (MessageDirection::Send, 1, Some(OTF2_LocationRef(20))),
+            (MessageDirection::Receive, 2, Some(OTF2_LocationRef(10))),
+            (MessageDirection::Send, 3, None),
+            (MessageDirection::Receive, 4, None),
+        ]);
+    }
+
+    #[test]
+    fn cancelled_sends_match_nothing() {
+        let mut matcher = MessageMatcher::new(&definitions());
+        let events = [
+            event(10, 1, EventKind::MpiIsend { receiver: 1, communicator: OTF2_CommRef(0), msg_tag: 0, msg_length: 100, request_id: 1 }),
+            event(10, 2, EventKind::MpiRequestCancelled { request_id: 1 }),
+            send(10, 3, 0, 1, 0, 8),
+            recv(20, 4, 0, 0, 0, 8),
+        ];
+        events.iter().for_each(|event| matcher.add_event(event));
+        let matching = matcher.finish();
+        assert!(matching.unmatched.is_empty(), "{:?}", matching.unmatched);
+        assert_eq!(matching.messages.iter().map(|message| (message.bytes, message.send_time)).collect::<Vec<_>>(), [(8, 3)]);
+    }
+
+    #[test]
+    fn receives_match_in_posting_order() {
+        let mut matcher = MessageMatcher::new(&definitions());
+        let irecv = |time, request_id, msg_length| {
+            event(20, time, EventKind::MpiIrecv { sender: 0, communicator: OTF2_CommRef(0), msg_tag: 0, msg_length, request_id })
+        };
+        let events = [
+            event(20, 1, EventKind::MpiIrecvRequest { request_id: 1 }),
+            event(20, 2, EventKind::MpiIrecvRequest { request_id: 2 }),
+            send(10, 3, 0, 1, 0, 8),
+            send(10, 4, 0, 1, 0, 16),
+            // The second request is waited on first
+            irecv(5, 2, 16),
+            irecv(6, 1, 8),
+        ];
+        events.iter().for_each(|event| matcher.add_event(event));
+        let matching = matcher.finish();
+        assert!(matching.unmatched.is_empty(), "{:?}", matching.unmatched);
+        let summary: Vec<_> = matching.messages.iter()
+            .map(|message| (message.bytes, message.send_time, message.recv_request_time, message.recv_time))
+            .collect();
+        assert_eq!(summary, [(8, 3, Some(1), 6), (16, 4, Some(2), 5)]);
+    }
+}
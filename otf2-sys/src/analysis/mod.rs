@@ -0,0 +1,42 @@
+mod callstack;
+mod calltree;
+mod collectives;
+mod criticalpath;
+#[cfg(test)]
+pub(crate) mod fixtures;
+mod imbalance;
+mod matrix;
+mod messages;
+mod profile;
+mod waitstate;
+
+pub use callstack::{region_intervals, CallStackIssue, CallStackTracker, MeasurementGap, RegionInterval, RegionIntervals};
+pub use calltree::{CallTree, CallTreeBuilder, CallTreeNode};
+pub use collectives::{CollectiveInstance, CollectiveIssue, CollectiveMatcher, CollectiveMember, Collectives};
+pub use criticalpath::{CriticalPath, CriticalPathAnalyzer, CriticalPathRegion, PathSegment};
+pub use imbalance::{ImbalanceLevel, ImbalanceOrder, ImbalanceReport, ImbalanceStats, RegionImbalance};
+pub use matrix::{CommMatrix, CommMatrixBuilder, MatrixEntry, MatrixGrouping, MatrixMember};
+pub use messages::{Message, MessageDirection, MessageMatcher, MessageMatching, UnmatchedOperation};
+pub use profile::{FlatProfile, FlatProfileBuilder, RegionProfile, RegionStats};
+pub use waitstate::{WaitState, WaitStateAnalyzer, WaitStateReport, WaitTime};
+
+/// The unit to show times in and the factor converting ticks to it: seconds if the timer
+/// resolution is known, otherwise ticks.
+pub(crate) fn time_scale(timer_resolution: Option<u64>) -> (&'static str, f64) {
+    match timer_resolution {
+        Some(resolution) if resolution > 0 => ("s", 1.0 / resolution as f64),
+        _ => ("ticks", 1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_seconds_only_for_a_known_resolution() {
+        assert_eq!(time_scale(Some(1000)), ("s", 0.001));
+        assert_eq!(time_scale(Some(0)), ("ticks", 1.0));
+        assert_eq!(time_scale(None), ("ticks", 1.0));
+    }
+}
@@ -0,0 +1,251 @@
+//! Flat profiles: the time spent in each region, per location and over all locations.
+
+use crate::internal::*;
+use crate::definition::DefinitionSet;
+use crate::error::Status;
+use crate::event::EventKindSet;
+use crate::reader::Trace;
+
+use super::callstack::{region_intervals, RegionInterval};
+use super::time_scale;
+
+use std::collections::BTreeMap;
+
+/// The visits to one region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionStats {
+    pub visits: u64,
+    /// The total time between entering and leaving the region.
+    pub inclusive: u64,
+    /// The inclusive time less the time spent in regions called from this one.
+    pub exclusive: u64,
+    /// The shortest inclusive time of one visit, or 0 without visits.
+    pub min: u64,
+    /// The longest inclusive time of one visit.
+    pub max: u64,
+}
+
+impl RegionStats {
+    pub fn add_visit(&mut self, inclusive: u64, exclusive: u64) {
+        self.merge(&RegionStats { visits: 1, inclusive, exclusive, min: inclusive, max: inclusive });
+    }
+
+    pub fn merge(&mut self, other: &RegionStats) {
+        // The min of stats without visits is a placeholder, not a visit of length 0
+        self.min = match (self.visits, other.visits) {
+            (_, 0) => self.min,
+            (0, _) => other.min,
+            _ => self.min.min(other.min),
+        };
+        self.visits += other.visits;
+        self.inclusive += other.inclusive;
+        self.exclusive += other.exclusive;
+        self.max = self.max.max(other.max);
+    }
+
+    /// The mean inclusive time of one visit.
+    pub fn mean(&self) -> f64 {
+        if self.visits == 0 { 0.0 } else { self.inclusive as f64 / self.visits as f64 }
+    }
+}
+
+/// The profile of one region.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionProfile {
+    pub region: OTF2_RegionRef,
+    pub name: String,
+    /// The visits on all locations together.
+    pub total: RegionStats,
+    pub per_location: BTreeMap<OTF2_LocationRef, RegionStats>,
+}
+
+/// The time spent in each region. Times are in timer ticks.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlatProfile {
+    /// Ticks per second, if the trace defines its clock.
+    pub timer_resolution: Option<u64>,
+    /// The regions in decreasing order of total exclusive time.
+    pub regions: Vec<RegionProfile>,
+}
+
+impl FlatProfile {
+    pub fn region(&self, region: OTF2_RegionRef) -> Option<&RegionProfile> {
+        self.regions.iter().find(|profile| profile.region == region)
+    }
+}
+
+/// Shows the total of each region as a table, in seconds if the timer resolution is known.
+impl std::fmt::Display for FlatProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (unit, scale) = time_scale(self.timer_resolution);
+        let width = self.regions.iter().map(|profile| profile.name.len()).chain([6]).max().unwrap_or_default();
+        writeln!(
+            f,
+            "{:<width$} {:>10} {:>14} {:>14} {:>14} {:>14} {:>14}",
+            "Region", "Visits", format!("Incl ({unit})"), format!("Excl ({unit})"), "Min", "Mean", "Max"
+        )?;
+        for profile in &self.regions {
+            let stats = &profile.total;
+            writeln!(
+                f,
+                "{:<width$} {:>10} {:>14.6} {:>14.6} {:>14.6} {:>14.6} {:>14.6}",
+                profile.name,
+                stats.visits,
+                stats.inclusive as f64 * scale,
+                stats.exclusive as f64 * scale,
+                stats.min as f64 * scale,
+                stats.mean() * scale,
+                stats.max as f64 * scale,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Accumulates region intervals, as given by a [`CallStackTracker`](super::CallStackTracker),
+/// into a [`FlatProfile`].
+///
+/// The intervals of each location must be added in the order they complete. A recursive region
+/// counts the time of each nested visit towards its inclusive time again.
+#[derive(Debug, Default)]
+pub struct FlatProfileBuilder {
+    stats: BTreeMap<OTF2_RegionRef, BTreeMap<OTF2_LocationRef, RegionStats>>,
+    // The time spent in the completed children of each open frame, indexed by depth
+    child_time: BTreeMap<OTF2_LocationRef, Vec<u64>>,
+}
+
+impl FlatProfileBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_interval(&mut self, interval: &RegionInterval) {
+        let duration = interval.duration();
+        let depth = interval.depth as usize;
+        let child_time = self.child_time.entry(interval.location).or_default();
+        if child_time.len() < depth + 2 {
+            child_time.resize(depth + 2, 0);
+        }
+        let children = std::mem::take(&mut child_time[depth + 1]);
+        child_time[depth] += duration;
+        self.stats
+            .entry(interval.region)
+            .or_default()
+            .entry(interval.location)
+            .or_default()
+            .add_visit(duration, duration.saturating_sub(children));
+    }
+
+    /// Build the profile, naming regions from `definitions`.
+    pub fn finish(self, definitions: &DefinitionSet) -> FlatProfile {
+        let mut regions: Vec<_> = self.stats
+            .into_iter()
+            .map(|(region, per_location)| {
+                let mut total = RegionStats::default();
+                per_location.values().for_each(|stats| total.merge(stats));
+                let name = definitions.region_label(region);
+                RegionProfile { region, name, total, per_location }
+            })
+            .collect();
+        regions.sort_by(|a, b| b.total.exclusive.cmp(&a.total.exclusive).then(a.region.cmp(&b.region)));
+        FlatProfile { timer_resolution: definitions.timer_resolution(), regions }
+    }
+}
+
+impl Trace {
+    /// Compute the flat profile of the trace from its Enter and Leave events. Regions still open
+    /// at the end of the trace are closed at the last event of their location.
+    pub fn flat_profile(&mut self) -> Status<FlatProfile> {
+        let definitions = self.definition_set()?;
+        self.flat_profile_with(&definitions)
+    }
+
+    /// As [`Trace::flat_profile`], naming regions from already read `definitions`.
+    pub(crate) fn flat_profile_with(&mut self, definitions: &DefinitionSet) -> Status<FlatProfile> {
+        let mut builder = FlatProfileBuilder::new();
+        let batch_size = self.options().batch_size;
+        for interval in region_intervals(self.iter_events_of_kinds(batch_size, EventKindSet::REGIONS)?) {
+            builder.add_interval(&interval?);
+        }
+        Ok(builder.finish(definitions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definition::{Definition, RegionDef};
+    use crate::types::{Paradigm, RegionFlags, RegionRole};
+
+    fn interval(location: u64, region: u32, begin: u64, end: u64, depth: u32) -> RegionInterval {
+        RegionInterval { location: OTF2_LocationRef(location), region: OTF2_RegionRef(region), begin, end, depth, parent: None }
+    }
+
+    fn definitions() -> DefinitionSet {
+        let region = |defn, name| Definition::Region {
+            defn: OTF2_RegionRef(defn),
+            value: RegionDef {
+                name: OTF2_StringRef(name),
+                canonical_name: OTF2_StringRef(name),
+                description: OTF2_StringRef(name),
+                region_role: RegionRole::Function,
+                paradigm: Paradigm::User,
+                region_flags: RegionFlags::empty(),
+                source_file: OTF2_StringRef(name),
+                begin_line_number: 0,
+                end_line_number: 0,
+            },
+        };
+        vec![
+            Definition::String { defn: OTF2_StringRef(0), value: "main".to_string() },
+            Definition::String { defn: OTF2_StringRef(1), value: "work".to_string() },
+            region(0, 0),
+            region(1, 1),
+        ].into()
+    }
+
+    #[test]
+    fn exclusive_time_excludes_children() {
+        let mut builder = FlatProfileBuilder::new();
+        // main [0, 100) calls work [10, 30) and [40, 90) on location 0; work [0, 50) on location 1
+        for interval in [interval(0, 1, 10, 30, 1), interval(0, 1, 40, 90, 1), interval(0, 0, 0, 100, 0), interval(1, 1, 0, 50, 0)] {
+            builder.add_interval(&interval);
+        }
+        let profile = builder.finish(&definitions());
+        let names: Vec<_> = profile.regions.iter().map(|profile| profile.name.as_str()).collect();
+        assert_eq!(names, ["work", "main"]);
+        let main = profile.region(OTF2_RegionRef(0)).unwrap();
+        assert_eq!(main.total, RegionStats { visits: 1, inclusive: 100, exclusive: 30, min: 100, max: 100 });
+        let work = profile.region(OTF2_RegionRef(1)).unwrap();
+        assert_eq!(work.total, RegionStats { visits: 3, inclusive: 120, exclusive: 120, min: 20, max: 50 });
+        assert_eq!(work.total.mean(), 40.0);
+        assert_eq!(work.per_location[&OTF2_LocationRef(0)].visits, 2);
+        assert!(profile.to_string().lines().nth(1).unwrap().starts_with("work"));
+    }
+
+    #[test]
+    fn stats_without_visits_have_no_min() {
+        let mut stats = RegionStats::default();
+        assert_eq!(stats.min, 0);
+        stats.merge(&RegionStats::default());
+        stats.add_visit(5, 5);
+        stats.merge(&RegionStats::default());
+        assert_eq!((stats.min, stats.max), (5, 5));
+        let mut empty = RegionStats::default();
+        empty.merge(&stats);
+        assert_eq!(empty, stats);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn profiles_round_trip_through_json() {
+        let mut builder = FlatProfileBuilder::new();
+        builder.add_interval(&interval(3, 1, 0, 10, 0));
+        let profile = builder.finish(&definitions());
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(serde_json::from_str::<FlatProfile>(&json).unwrap(), profile);
+    }
+}
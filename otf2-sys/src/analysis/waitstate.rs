@@ -0,0 +1,340 @@
+//! Find time spent waiting in MPI operations for other locations to catch up, following the
+//! wait-state patterns of Scalasca.
+
+use crate::internal::*;
+use crate::definition::DefinitionSet;
+use crate::error::Status;
+use crate::event::{Event, EventKind, EventKindSet};
+use crate::reader::Trace;
+use crate::types::CollectiveOp;
+
+use super::callstack::{CallStackTracker, RegionInterval};
+use super::collectives::{CollectiveInstance, CollectiveMatcher};
+use super::messages::{Message, MessageMatcher};
+use super::time_scale;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A pattern of waiting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WaitState {
+    /// A receive which began before the matching send was posted.
+    LateSender,
+    /// A blocking send which began before the matching receive was posted, for example because
+    /// the message was too large to buffer.
+    LateReceiver,
+    /// A member of a barrier which arrived before the last member.
+    WaitAtBarrier,
+    /// A member of an all-to-all operation, such as an allreduce, which arrived before the last
+    /// member.
+    WaitAtNxN,
+    /// A member of a broadcast or scatter which arrived before the root.
+    LateBroadcast,
+}
+
+/// The time one location spent waiting in one region in one pattern. Times are in timer ticks.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaitTime {
+    pub state: WaitState,
+    /// The region of the MPI call which waited, if the call was recorded in one.
+    pub region: Option<OTF2_RegionRef>,
+    pub name: String,
+    pub location: OTF2_LocationRef,
+    pub time: u64,
+    /// The number of operations which waited.
+    pub count: u64,
+}
+
+/// The waiting time found in a trace.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaitStateReport {
+    /// Ticks per second, if the trace defines its clock.
+    pub timer_resolution: Option<u64>,
+    /// Sorted by pattern, region and location. Only non-zero waiting times are included.
+    pub entries: Vec<WaitTime>,
+}
+
+impl WaitStateReport {
+    /// The total waiting time in `state`, over all regions and locations.
+    pub fn total(&self, state: WaitState) -> u64 {
+        self.entries.iter().filter(|entry| entry.state == state).map(|entry| entry.time).sum()
+    }
+
+    /// The waiting time in each region, in all patterns if `state` is `None`.
+    pub fn per_region(&self, state: Option<WaitState>) -> BTreeMap<Option<OTF2_RegionRef>, u64> {
+        self.sum_by(state, |entry| entry.region)
+    }
+
+    /// The waiting time on each location, in all patterns if `state` is `None`.
+    pub fn per_location(&self, state: Option<WaitState>) -> BTreeMap<OTF2_LocationRef, u64> {
+        self.sum_by(state, |entry| entry.location)
+    }
+
+    fn sum_by<K: Ord>(&self, state: Option<WaitState>, key: impl Fn(&WaitTime) -> K) -> BTreeMap<K, u64> {
+        let mut sums = BTreeMap::new();
+        for entry in self.entries.iter().filter(|entry| state.is_none_or(|state| entry.state == state)) {
+            *sums.entry(key(entry)).or_default() += entry.time;
+        }
+        sums
+    }
+}
+
+/// Shows the waiting time of each pattern in each region, summed over locations, in seconds if
+/// the timer resolution is known.
+impl std::fmt::Display for WaitStateReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (unit, scale) = time_scale(self.timer_resolution);
+        let mut rows: BTreeMap<(WaitState, &str), (u64, u64)> = BTreeMap::new();
+        for entry in &self.entries {
+            let row = rows.entry((entry.state, entry.name.as_str())).or_default();
+            row.0 += entry.time;
+            row.1 += entry.count;
+        }
+        let width = self.entries.iter().map(|entry| entry.name.len()).chain([6]).max().unwrap_or_default();
+        writeln!(f, "{:<14} {:<width$} {:>10} {:>14}", "Pattern", "Region", "Count", format!("Time ({unit})"))?;
+        for ((state, name), (time, count)) in rows {
+            writeln!(f, "{:<14} {:<width$} {:>10} {:>14.6}", format!("{state:?}"), name, count, time as f64 * scale)?;
+        }
+        Ok(())
+    }
+}
+
+// The region enclosing an event: location, entry time and depth identify its interval
+type Frame = (OTF2_LocationRef, OTF2_TimeStamp, usize);
+
+/// Finds wait states from the region, point-to-point and collective events of a trace.
+///
+/// The waiting time of a point-to-point operation is measured from the start of the innermost
+/// region enclosing its event, normally the MPI call. Non-blocking sends and collectives are
+/// not checked, as their waiting happens in a later completion call.
+#[derive(Debug, Default)]
+pub struct WaitStateAnalyzer {
+    tracker: CallStackTracker,
+    messages: MessageMatcher,
+    collectives: CollectiveMatcher,
+    // The frame enclosing each communication event, by location and time, and the region and
+    // end time of those frames
+    frames: BTreeMap<(OTF2_LocationRef, OTF2_TimeStamp), Frame>,
+    wanted: BTreeSet<Frame>,
+    intervals: BTreeMap<Frame, RegionInterval>,
+}
+
+impl WaitStateAnalyzer {
+    pub fn new(definitions: &DefinitionSet) -> Self {
+        Self {
+            messages: MessageMatcher::new(definitions),
+            collectives: CollectiveMatcher::new(definitions),
+            ..Self::default()
+        }
+    }
+
+    pub fn add_event(&mut self, event: &Event) {
+        let location = event.data.location;
+        let communication = matches!(
+            event.kind,
+            EventKind::MpiSend { .. } | EventKind::MpiIsend { .. } | EventKind::MpiRecv { .. } | EventKind::MpiIrecv { .. } | EventKind::MpiCollectiveEnd { .. }
+        );
+        let top = communication.then(|| self.tracker.top(location)).flatten();
+        if let Some((_, begin)) = top {
+            let frame = (location, begin, self.tracker.depth(location) - 1);
+            self.frames.insert((location, event.data.time), frame);
+            self.wanted.insert(frame);
+        }
+        self.messages.add_event(event);
+        self.collectives.add_event(event);
+        if let Some(interval) = self.tracker.add_event(event) {
+            self.completed(interval);
+        }
+    }
+
+    fn completed(&mut self, interval: RegionInterval) {
+        let frame = (interval.location, interval.begin, interval.depth as usize);
+        if self.wanted.remove(&frame) {
+            self.intervals.insert(frame, interval);
+        }
+    }
+
+    /// The interval of the region enclosing the event at `time` on `location`.
+    fn enclosing(&self, location: OTF2_LocationRef, time: OTF2_TimeStamp) -> Option<&RegionInterval> {
+        self.intervals.get(self.frames.get(&(location, time))?)
+    }
+
+    pub fn finish(mut self, definitions: &DefinitionSet) -> WaitStateReport {
+        for interval in self.tracker.finish() {
+            self.completed(interval);
+        }
+        let messages = std::mem::take(&mut self.messages).finish();
+        let collectives = std::mem::take(&mut self.collectives).finish();
+        let mut waits = BTreeMap::new();
+        for message in &messages.messages {
+            self.message_waits(message, &mut waits);
+        }
+        for instance in &collectives.instances {
+            self.collective_waits(instance, &mut waits);
+        }
+        let entries = waits.into_iter()
+            .map(|((state, region, location), (time, count))| WaitTime {
+                state,
+                region,
+                name: match region {
+                    Some(region) => definitions.region_label(region),
+                    None => String::from("(no region)"),
+                },
+                location,
+                time,
+                count,
+            })
+            .collect();
+        WaitStateReport {
+            timer_resolution: definitions.timer_resolution(),
+            entries,
+        }
+    }
+
+    fn message_waits(&self, message: &Message, waits: &mut Waits) {
+        let recv = self.enclosing(message.receiver_location, message.recv_time);
+        if let Some(recv) = recv {
+            let wait = message.send_time.min(message.recv_time).saturating_sub(recv.begin);
+            add_wait(waits, WaitState::LateSender, Some(recv.region), recv.location, wait);
+        }
+        if message.non_blocking {
+            return;
+        }
+        if let (Some(send), Some(recv)) = (self.enclosing(message.sender_location, message.send_time), recv) {
+            let wait = recv.begin.min(send.end).saturating_sub(send.begin);
+            add_wait(waits, WaitState::LateReceiver, Some(send.region), send.location, wait);
+        }
+    }
+
+    fn collective_waits(&self, instance: &CollectiveInstance, waits: &mut Waits) {
+        if instance.non_blocking {
+            return;
+        }
+        let (state, arrival) = match instance.op {
+            CollectiveOp::Barrier => (WaitState::WaitAtBarrier, instance.last_begin()),
+            CollectiveOp::Allgather | CollectiveOp::Allgatherv | CollectiveOp::Alltoall | CollectiveOp::Alltoallv
+            | CollectiveOp::Alltoallw | CollectiveOp::Allreduce | CollectiveOp::ReduceScatter | CollectiveOp::ReduceScatterBlock => {
+                (WaitState::WaitAtNxN, instance.last_begin())
+            }
+            CollectiveOp::Bcast | CollectiveOp::Scatter | CollectiveOp::Scatterv => {
+                let Some(root) = instance.root.and_then(|root| instance.member(root)) else {
+                    return;
+                };
+                (WaitState::LateBroadcast, root.begin)
+            }
+            _ => return,
+        };
+        for member in &instance.members {
+            if Some(member.location) == instance.root && state == WaitState::LateBroadcast {
+                continue;
+            }
+            let region = self.enclosing(member.location, member.end).map(|interval| interval.region);
+            let wait = arrival.min(member.end).saturating_sub(member.begin);
+            add_wait(waits, state, region, member.location, wait);
+        }
+    }
+}
+
+// Total time and count per pattern, region and location
+type Waits = BTreeMap<(WaitState, Option<OTF2_RegionRef>, OTF2_LocationRef), (u64, u64)>;
+
+fn add_wait(waits: &mut Waits, state: WaitState, region: Option<OTF2_RegionRef>, location: OTF2_LocationRef, wait: u64) {
+    if wait > 0 {
+        let entry = waits.entry((state, region, location)).or_default();
+        entry.0 += wait;
+        entry.1 += 1;
+    }
+}
+
+impl Trace {
+    /// Find the wait states of the MPI operations in the trace.
+    pub fn wait_states(&mut self) -> Status<WaitStateReport> {
+        let definitions = self.definition_set()?;
+        let mut analyzer = WaitStateAnalyzer::new(&definitions);
+        let batch_size = self.options().batch_size;
+        let kinds = EventKindSet::REGIONS.union(EventKindSet::MPI_POINT_TO_POINT).union(EventKindSet::MPI_COLLECTIVE);
+        for event in self.iter_events_of_kinds(batch_size, kinds)? {
+            analyzer.add_event(&event?);
+        }
+        Ok(analyzer.finish(&definitions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fixtures::{self, enter, event, leave, mpi_world};
+
+    fn definitions() -> DefinitionSet {
+        mpi_world(&[0, 1]).into()
+    }
+
+    const SEND: u32 = 1;
+    const RECV: u32 = 2;
+    const BARRIER: u32 = 3;
+    const BCAST: u32 = 4;
+
+    fn send(location: u64, time: u64, receiver: u32) -> Event {
+        fixtures::send(location, time, 0, receiver, 0, 8)
+    }
+
+    fn recv(location: u64, time: u64, sender: u32) -> Event {
+        fixtures::recv(location, time, 0, sender, 0, 8)
+    }
+
+    fn collective(location: u64, begin: u64, end: u64, region: u32, collective_op: CollectiveOp, root: u32) -> [Event; 4] {
+        [
+            enter(location, begin, region),
+            event(location, begin, EventKind::MpiCollectiveBegin {}),
+            event(location, end, EventKind::MpiCollectiveEnd { collective_op, communicator: OTF2_CommRef(0), root, size_sent: 8, size_received: 8 }),
+            leave(location, end, region),
+        ]
+    }
+
+    #[test]
+    fn classifies_wait_states() {
+        let mut analyzer = WaitStateAnalyzer::new(&definitions());
+        let mut events = vec![
+            // Location 1 waits 3 ticks for a late sender
+            enter(1, 2, RECV), enter(0, 5, SEND), send(0, 5, 1), leave(0, 6, SEND), recv(1, 7, 0), leave(1, 7, RECV),
+            // Location 0 waits 4 ticks for a late receiver
+            enter(0, 10, SEND), send(0, 10, 1), enter(1, 14, RECV), recv(1, 15, 0), leave(1, 15, RECV), leave(0, 15, SEND),
+        ];
+        // Location 0 waits 3 ticks in the barrier, then 2 ticks for the root of the broadcast
+        events.extend(collective(0, 20, 25, BARRIER, CollectiveOp::Barrier, OTF2_UNDEFINED_UINT32));
+        events.extend(collective(1, 23, 25, BARRIER, CollectiveOp::Barrier, OTF2_UNDEFINED_UINT32));
+        events.extend(collective(0, 30, 33, BCAST, CollectiveOp::Bcast, 1));
+        events.extend(collective(1, 32, 34, BCAST, CollectiveOp::Bcast, 1));
+        events.iter().for_each(|event| analyzer.add_event(event));
+        let report = analyzer.finish(&DefinitionSet::new());
+        let found: Vec<_> = report.entries.iter()
+            .map(|entry| (entry.state, entry.region.map(|region| region.0), entry.location.0, entry.time, entry.count))
+            .collect();
+        assert_eq!(found, [
+            (WaitState::LateSender, Some(RECV), 1, 3, 1),
+            (WaitState::LateReceiver, Some(SEND), 0, 4, 1),
+            (WaitState::WaitAtBarrier, Some(BARRIER), 0, 3, 1),
+            (WaitState::LateBroadcast, Some(BCAST), 0, 2, 1),
+        ]);
+        assert_eq!(report.per_location(None), BTreeMap::from([(OTF2_LocationRef(0), 9), (OTF2_LocationRef(1), 3)]));
+        assert_eq!(report.total(WaitState::WaitAtNxN), 0);
+        assert_eq!(report.to_string().lines().count(), 5);
+    }
+
+    #[test]
+    fn non_blocking_sends_never_wait_for_receivers() {
+        let mut analyzer = WaitStateAnalyzer::new(&definitions());
+        // The request of the Isend is freed, so its completion is never recorded
+        let events = [
+            enter(0, 0, SEND),
+            event(0, 0, EventKind::MpiIsend { receiver: 1, communicator: OTF2_CommRef(0), msg_tag: 0, msg_length: 8, request_id: 1 }),
+            enter(1, 4, RECV), recv(1, 5, 0), leave(1, 5, RECV), leave(0, 10, SEND),
+        ];
+        events.iter().for_each(|event| analyzer.add_event(event));
+        let report = analyzer.finish(&DefinitionSet::new());
+        assert_eq!(report.entries, []);
+    }
+}
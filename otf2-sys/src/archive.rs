@@ -0,0 +1,305 @@
+//! Safe wrapper around the OTF2_Archive operations needed to add to a trace.
+
+use crate::internal::*;
+use crate::definition::{write_definition, Definition};
+use crate::error::{Status, StatusCode};
+use crate::event::{write_event, Event};
+use crate::info::TraceInfo;
+use crate::thumbnail::Thumbnail;
+use crate::locking::MUTEX_LOCKING_CALLBACKS;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::CString;
+
+use OTF2_ErrorCode::*;
+
+const EVENT_CHUNK_SIZE: u64 = 1024 * 1024;
+const DEFINITION_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug)]
+pub struct Archive {
+    handle: Handle<OTF2_Archive>,
+    // The writers used by `write_definition` and `write_event`, opened on first use
+    def_writer: Option<Handle<OTF2_GlobalDefWriter>>,
+    evt_writers: BTreeMap<OTF2_LocationRef, Handle<OTF2_EvtWriter>>,
+    attributes: Option<Handle<OTF2_AttributeList>>,
+    // Every location which has been defined or written to, each of which needs a local
+    // definition file
+    locations: BTreeSet<OTF2_LocationRef>,
+}
+
+// SAFETY: as for `Trace`, every archive is opened with `MUTEX_LOCKING_CALLBACKS` and all access to
+// OTF2 goes through `&mut self`.
+unsafe impl Send for Archive {}
+unsafe impl Sync for Archive {}
+
+impl core::ops::Drop for Archive {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(mut attributes) = self.attributes.take() {
+                let _ = OTF2_AttributeList_Delete(attributes.take());
+            }
+            if !self.handle.is_null() {
+                let _ = OTF2_Archive_Close(self.handle.take());
+            }
+        }
+    }
+}
+
+/// Flush the event buffers whenever they are full. Timestamps aren't recorded for flushes.
+static FLUSH_CALLBACKS: OTF2_FlushCallbacks = OTF2_FlushCallbacks {
+    otf2_pre_flush: Some(pre_flush),
+    otf2_post_flush: None,
+};
+
+unsafe extern "C" fn pre_flush(
+    _user_data: *mut std::os::raw::c_void,
+    _file_type: OTF2_FileType,
+    _location: OTF2_LocationRef,
+    _caller_data: *mut std::os::raw::c_void,
+    _is_final: bool,
+) -> OTF2_FlushType {
+    OTF2_FlushType_enum::OTF2_FLUSH as OTF2_FlushType
+}
+
+/// The length of an array argument, which OTF2 takes as a narrower integer.
+pub(crate) fn array_length<T, N: TryFrom<usize>>(values: &[T]) -> Status<N> {
+    N::try_from(values.len()).map_err(|_| StatusCode::from_raw(OTF2_ERROR_INVALID_ARGUMENT))
+}
+
+fn to_c_string(value: &str) -> Status<CString> {
+    CString::new(value).map_err(|_| StatusCode::from_raw(OTF2_ERROR_INVALID_ARGUMENT))
+}
+
+impl Archive {
+    /// Open the archive `name` in the directory `path`. Uses the POSIX substrate without
+    /// compression.
+    pub fn open(path: CString, name: CString, mode: OTF2_FileMode) -> Status<Self> {
+        let handle = Handle::from_raw(unsafe {
+            OTF2_Archive_Open(
+                path.as_ptr(),
+                name.as_ptr(),
+                mode,
+                EVENT_CHUNK_SIZE,
+                DEFINITION_CHUNK_SIZE,
+                OTF2_FileSubstrate_enum::OTF2_SUBSTRATE_POSIX as OTF2_FileSubstrate,
+                OTF2_Compression_enum::OTF2_COMPRESSION_NONE as OTF2_Compression,
+            )
+        }).ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
+        let mut archive = Archive {
+            handle,
+            def_writer: None,
+            evt_writers: BTreeMap::new(),
+            attributes: None,
+            locations: BTreeSet::new(),
+        };
+        unsafe {
+            OTF2_Archive_SetLockingCallbacks(archive.handle.as_mut_ptr(), &MUTEX_LOCKING_CALLBACKS, std::ptr::null_mut())?;
+            OTF2_Archive_SetSerialCollectiveCallbacks(archive.handle.as_mut_ptr())?;
+        }
+        Ok(archive)
+    }
+
+    /// Create a new archive `name` in the directory `path` to write definitions and events to.
+    /// Call [`Archive::finish`] once everything is written.
+    pub fn create(path: CString, name: CString) -> Status<Self> {
+        let mut archive = Self::open(path, name, OTF2_FileMode_enum::OTF2_FILEMODE_WRITE as OTF2_FileMode)?;
+        unsafe {
+            OTF2_Archive_SetFlushCallbacks(archive.handle.as_mut_ptr(), &FLUSH_CALLBACKS, std::ptr::null_mut())?;
+            OTF2_Archive_OpenEvtFiles(archive.handle.as_mut_ptr())?;
+        }
+        Ok(archive)
+    }
+
+    /// Record the creator, description, machine name and properties of `info` in the archive.
+    /// The version, trace id and number of locations are determined by OTF2.
+    pub fn set_info(&mut self, info: &TraceInfo) -> Status<()> {
+        let archive = self.handle.as_mut_ptr();
+        unsafe {
+            OTF2_Archive_SetCreator(archive, to_c_string(&info.creator)?.as_ptr())?;
+            OTF2_Archive_SetDescription(archive, to_c_string(&info.description)?.as_ptr())?;
+            OTF2_Archive_SetMachineName(archive, to_c_string(&info.machine_name)?.as_ptr())?;
+            for (name, value) in &info.properties {
+                OTF2_Archive_SetProperty(archive, to_c_string(name)?.as_ptr(), to_c_string(value)?.as_ptr(), true)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a global definition.
+    pub fn write_definition(&mut self, definition: &Definition) -> Status<()> {
+        if self.def_writer.is_none() {
+            let writer = Handle::from_raw(unsafe { OTF2_Archive_GetGlobalDefWriter(self.handle.as_mut_ptr()) })
+                .ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
+            self.def_writer = Some(writer);
+        }
+        if let Definition::Location { defn, .. } = definition {
+            self.locations.insert(*defn);
+        }
+        write_definition(self.def_writer.as_mut().expect("opened above"), definition)
+    }
+
+    /// Write an event to the event file of its location. The events of each location must be
+    /// written in time order.
+    pub fn write_event(&mut self, event: &Event) -> Status<()> {
+        let location = event.data.location;
+        if !self.evt_writers.contains_key(&location) {
+            let writer = Handle::from_raw(unsafe { OTF2_Archive_GetEvtWriter(self.handle.as_mut_ptr(), location) })
+                .ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
+            self.evt_writers.insert(location, writer);
+            self.locations.insert(location);
+        }
+        if self.attributes.is_none() {
+            let attributes = Handle::from_raw(unsafe { OTF2_AttributeList_New() })
+                .ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
+            self.attributes = Some(attributes);
+        }
+        let writer = self.evt_writers.get_mut(&location).expect("opened above");
+        let attributes = self.attributes.as_mut().expect("created above");
+        let result = write_event(writer, attributes, event);
+        if result.is_err() {
+            // OTF2 only empties the list once a record is written, so the attributes of a failed
+            // record would otherwise be attached to the next one
+            let _ = attributes.remove_all();
+        }
+        result
+    }
+
+    /// Close the event files, write an empty local definition file for every location, then
+    /// close the archive.
+    pub fn finish(mut self) -> Status<()> {
+        let archive = self.handle.as_mut_ptr();
+        unsafe {
+            for (_, mut writer) in std::mem::take(&mut self.evt_writers) {
+                OTF2_Archive_CloseEvtWriter(archive, writer.take())?;
+            }
+            OTF2_Archive_CloseEvtFiles(archive)?;
+            OTF2_Archive_OpenDefFiles(archive)?;
+            for &location in &self.locations {
+                let writer = OTF2_Archive_GetDefWriter(archive, location);
+                if writer.is_null() {
+                    return Err(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED));
+                }
+                OTF2_Archive_CloseDefWriter(archive, writer)?;
+            }
+            OTF2_Archive_CloseDefFiles(archive)?;
+            OTF2_Archive_Close(self.handle.take())?;
+        }
+        Ok(())
+    }
+
+    /// Write a thumbnail. OTF2 closes the thumbnail writer along with the archive.
+    pub fn write_thumbnail(&mut self, thumbnail: &Thumbnail) -> Status<()> {
+        let header = &thumbnail.header;
+        if thumbnail.samples.len() != header.number_of_samples as usize
+            || thumbnail.samples.iter().any(|sample| sample.values.len() != header.refs_to_defs.len())
+        {
+            return Err(StatusCode::from_raw(OTF2_ERROR_INVALID_ARGUMENT));
+        }
+        let name = to_c_string(&header.name)?;
+        let description = to_c_string(&header.description)?;
+        let mut writer = Handle::from_raw(unsafe {
+            OTF2_Archive_GetThumbWriter(
+                self.handle.as_mut_ptr(),
+                name.as_ptr(),
+                description.as_ptr(),
+                header.kind.to_raw(),
+                header.number_of_samples,
+                header.number_of_metrics(),
+                header.refs_to_defs.as_ptr(),
+            )
+        }).ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
+        for sample in &thumbnail.samples {
+            unsafe { OTF2_ThumbWriter_WriteSample(writer.as_mut_ptr(), sample.baseline, header.number_of_metrics(), sample.values.as_ptr()) }?;
+        }
+        Ok(())
+    }
+}
+
+/// Small traces written to the temporary directory for tests which read them back with OTF2.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+    use crate::attribute::Attributes;
+    use crate::definition::{ClockPropertiesDef, LocationDef, LocationGroupDef, RegionDef, SystemTreeNodeDef};
+    use crate::event::EventKind;
+    use crate::types::{LocationGroupType, LocationType, Paradigm, RegionFlags, RegionRole};
+    use std::path::PathBuf;
+
+    /// A directory of the temporary directory which is removed on drop.
+    #[derive(Debug)]
+    pub(crate) struct TempDir(PathBuf);
+
+    impl TempDir {
+        /// A fresh directory named after `name` and the test process.
+        pub(crate) fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("otf2-sys-{name}-{}", std::process::id()));
+            // Left over from an earlier run whose process id was reused
+            let _ = std::fs::remove_dir_all(&dir);
+            TempDir(dir)
+        }
+
+        pub(crate) fn path(&self) -> CString {
+            CString::new(self.0.to_str().unwrap()).unwrap()
+        }
+
+        /// The anchor file of the archive `name` in this directory.
+        pub(crate) fn anchor_file(&self, name: &str) -> CString {
+            CString::new(self.0.join(format!("{name}.otf2")).to_str().unwrap()).unwrap()
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// The definitions of one process whose thread `i` records `num_events[i]` events, all in
+    /// region 0 named by string 0.
+    pub(crate) fn definitions(num_events: &[u64]) -> Vec<Definition> {
+        let main = RegionDef {
+            name: OTF2_StringRef(0),
+            canonical_name: OTF2_StringRef(0),
+            description: OTF2_StringRef(0),
+            region_role: RegionRole::Function,
+            paradigm: Paradigm::User,
+            region_flags: RegionFlags::empty(),
+            source_file: OTF2_StringRef(0),
+            begin_line_number: 0,
+            end_line_number: 0,
+        };
+        let trace_length = num_events.iter().copied().max().unwrap_or(0) * 10;
+        let mut definitions = vec![
+            Definition::String { defn: OTF2_StringRef(0), value: "main".to_string() },
+            Definition::ClockProperties { value: ClockPropertiesDef { timer_resolution: 1000, global_offset: 0, trace_length, realtime_timestamp: 0 } },
+            Definition::SystemTreeNode { defn: OTF2_SystemTreeNodeRef(0), value: SystemTreeNodeDef { name: OTF2_StringRef(0), class_name: OTF2_StringRef(0), parent: None } },
+            Definition::LocationGroup {
+                defn: OTF2_LocationGroupRef(0),
+                value: LocationGroupDef { name: OTF2_StringRef(0), location_group_type: LocationGroupType::Process, system_tree_parent: OTF2_SystemTreeNodeRef(0), creating_location_group: None },
+            },
+        ];
+        definitions.extend(num_events.iter().enumerate().map(|(location, &num_events)| Definition::Location {
+            defn: OTF2_LocationRef(location as u64),
+            value: LocationDef { name: OTF2_StringRef(0), location_type: LocationType::CpuThread, num_events, location_group: OTF2_LocationGroupRef(0) },
+        }));
+        definitions.push(Definition::Region { defn: OTF2_RegionRef(0), value: main });
+        definitions
+    }
+
+    /// Write the archive `name` to `dir` with the [`definitions`] of `num_events`, each thread
+    /// alternately entering and leaving region 0. Every count must be even.
+    pub(crate) fn write_trace(dir: &TempDir, name: &str, num_events: &[u64]) -> Status<()> {
+        let mut archive = Archive::create(dir.path(), CString::new(name).unwrap())?;
+        for definition in definitions(num_events) {
+            archive.write_definition(&definition)?;
+        }
+        for (location, &count) in num_events.iter().enumerate() {
+            for time in 0..count {
+                let region = OTF2_RegionRef(0);
+                let kind = if time % 2 == 0 { EventKind::Enter { region } } else { EventKind::Leave { region } };
+                archive.write_event(&Event::new(OTF2_LocationRef(location as u64), time * 10, Attributes::default(), kind))?;
+            }
+        }
+        archive.finish()
+    }
+}
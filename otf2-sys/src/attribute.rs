@@ -1,5 +1,6 @@
 use crate::internal::*;
 use crate::error::{Status, StatusCode};
+use crate::definition::{DefinitionRef, DefinitionSet};
 use std::ffi::{CString, CStr};
 use std::ops::ControlFlow;
 
@@ -60,6 +61,113 @@ impl Handle<OTF2_AttributeList> {
         }?;
         Ok((attribute, AttributeValue::new(kind, value)))
     }
+
+    /// Add each of `attributes` to the list, e.g. before passing it to a writer. OTF2's writers
+    /// empty the list once they have written the record.
+    pub(crate) fn add_attributes(&mut self, attributes: &Attributes) -> Status<()> {
+        for (attribute, value) in attributes {
+            let (kind, value) = value.to_raw();
+            unsafe { OTF2_AttributeList_AddAttribute(self.as_mut_ptr(), *attribute, kind, value) }?;
+        }
+        Ok(())
+    }
+
+    /// Remove every attribute from the list.
+    pub(crate) fn remove_all(&mut self) -> Status<()> {
+        unsafe { OTF2_AttributeList_RemoveAllAttributes(self.as_mut_ptr()) }?;
+        Ok(())
+    }
+}
+
+/// The attributes of a record, stored inline. Most records have none, in which case nothing is
+/// allocated.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Vec<(OTF2_AttributeRef, AttributeValue)>", into = "Vec<(OTF2_AttributeRef, AttributeValue)>"))]
+pub struct Attributes(Option<Box<[(OTF2_AttributeRef, AttributeValue)]>>);
+
+impl Attributes {
+    /// The value of `attribute`, if present.
+    pub fn get(&self, attribute: OTF2_AttributeRef) -> Option<AttributeValue> {
+        self.iter().find(|(key, _)| *key == attribute).map(|(_, value)| *value)
+    }
+}
+
+impl std::ops::Deref for Attributes {
+    type Target = [(OTF2_AttributeRef, AttributeValue)];
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_deref().unwrap_or_default()
+    }
+}
+
+impl<'a> std::iter::IntoIterator for &'a Attributes {
+    type Item = &'a (OTF2_AttributeRef, AttributeValue);
+    type IntoIter = std::slice::Iter<'a, (OTF2_AttributeRef, AttributeValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl std::iter::FromIterator<(OTF2_AttributeRef, AttributeValue)> for Attributes {
+    fn from_iter<I: IntoIterator<Item = (OTF2_AttributeRef, AttributeValue)>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl From<Vec<(OTF2_AttributeRef, AttributeValue)>> for Attributes {
+    fn from(value: Vec<(OTF2_AttributeRef, AttributeValue)>) -> Self {
+        if value.is_empty() { Self(None) } else { Self(Some(value.into_boxed_slice())) }
+    }
+}
+
+impl From<Attributes> for Vec<(OTF2_AttributeRef, AttributeValue)> {
+    fn from(value: Attributes) -> Self {
+        value.0.map(Vec::from).unwrap_or_default()
+    }
+}
+
+/// A borrowed view of the attribute list passed to a record callback. Attributes are only decoded
+/// when asked for, and the view is only valid for the duration of the callback.
+#[derive(Debug)]
+pub struct AttributeListRef<'a> {
+    handle: Handle<OTF2_AttributeList>,
+    _marker: std::marker::PhantomData<&'a OTF2_AttributeList>,
+}
+
+impl AttributeListRef<'_> {
+    /// # Safety
+    ///
+    /// `list` must be a valid attribute list which outlives the returned view.
+    pub(crate) unsafe fn from_raw(list: *mut OTF2_AttributeList) -> Self {
+        Self { handle: Handle::from_raw_unchecked(list), _marker: std::marker::PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        if self.handle.is_null() { 0 } else { self.handle.length() as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<(OTF2_AttributeRef, AttributeValue)> {
+        if index < self.len() {
+            self.handle.get_attribute_by_index(index as u32).ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (OTF2_AttributeRef, AttributeValue)> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
+    /// Decode every attribute into an owned [`Attributes`].
+    pub fn to_attributes(&self) -> Attributes {
+        if self.is_empty() { Attributes::default() } else { self.iter().collect() }
+    }
 }
 
 declare_enum_union_wrapper!(
@@ -94,3 +202,168 @@ declare_enum_union_wrapper!(
         OTF2_TYPE_LOCATION_GROUP => LocationGroup(OTF2_LocationGroupRef) from locationGroupRef,
     }
 );
+
+impl AttributeValue {
+    /// The OTF2 type of this value.
+    pub fn kind(&self) -> OTF2_Type_enum {
+        use OTF2_Type_enum::*;
+        match self {
+            Self::None(_) => OTF2_TYPE_NONE,
+            Self::Uint8(_) => OTF2_TYPE_UINT8,
+            Self::Uint16(_) => OTF2_TYPE_UINT16,
+            Self::Uint32(_) => OTF2_TYPE_UINT32,
+            Self::Uint64(_) => OTF2_TYPE_UINT64,
+            Self::Int8(_) => OTF2_TYPE_INT8,
+            Self::Int16(_) => OTF2_TYPE_INT16,
+            Self::Int32(_) => OTF2_TYPE_INT32,
+            Self::Int64(_) => OTF2_TYPE_INT64,
+            Self::Float32(_) => OTF2_TYPE_FLOAT,
+            Self::Float64(_) => OTF2_TYPE_DOUBLE,
+            Self::String(_) => OTF2_TYPE_STRING,
+            Self::Attribute(_) => OTF2_TYPE_ATTRIBUTE,
+            Self::Location(_) => OTF2_TYPE_LOCATION,
+            Self::Region(_) => OTF2_TYPE_REGION,
+            Self::Group(_) => OTF2_TYPE_GROUP,
+            Self::Metric(_) => OTF2_TYPE_METRIC,
+            Self::Comm(_) => OTF2_TYPE_COMM,
+            Self::Parameter(_) => OTF2_TYPE_PARAMETER,
+            Self::RmaWin(_) => OTF2_TYPE_RMA_WIN,
+            Self::SourceCodeLocation(_) => OTF2_TYPE_SOURCE_CODE_LOCATION,
+            Self::CallingContext(_) => OTF2_TYPE_CALLING_CONTEXT,
+            Self::InterruptGenerator(_) => OTF2_TYPE_INTERRUPT_GENERATOR,
+            Self::IoFile(_) => OTF2_TYPE_IO_FILE,
+            Self::IoHandle(_) => OTF2_TYPE_IO_HANDLE,
+            Self::LocationGroup(_) => OTF2_TYPE_LOCATION_GROUP,
+        }
+    }
+
+    /// The value as a `u64`, if it is an unsigned integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Self::Uint8(v) => Some(v.into()),
+            Self::Uint16(v) => Some(v.into()),
+            Self::Uint32(v) => Some(v.into()),
+            Self::Uint64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value as an `i64`, if it is a signed integer or an unsigned integer narrower than 64
+    /// bits.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::Int8(v) => Some(v.into()),
+            Self::Int16(v) => Some(v.into()),
+            Self::Int32(v) => Some(v.into()),
+            Self::Int64(v) => Some(v),
+            Self::Uint8(v) => Some(v.into()),
+            Self::Uint16(v) => Some(v.into()),
+            Self::Uint32(v) => Some(v.into()),
+            _ => None,
+        }
+    }
+
+    /// The value as an `f64`, if it is a float or an integer which converts without loss.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Float32(v) => Some(v.into()),
+            Self::Float64(v) => Some(v),
+            Self::Int8(v) => Some(v.into()),
+            Self::Int16(v) => Some(v.into()),
+            Self::Int32(v) => Some(v.into()),
+            Self::Uint8(v) => Some(v.into()),
+            Self::Uint16(v) => Some(v.into()),
+            Self::Uint32(v) => Some(v.into()),
+            _ => None,
+        }
+    }
+
+    /// The definition this value refers to, if it is a reference defined in `definitions`.
+    pub fn definition<'d>(&self, definitions: &'d DefinitionSet) -> Option<DefinitionRef<'d>> {
+        definitions.referenced(self)
+    }
+}
+
+impl std::fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None(_) => write!(f, "none"),
+            Self::Uint8(v) => write!(f, "{v}"),
+            Self::Uint16(v) => write!(f, "{v}"),
+            Self::Uint32(v) => write!(f, "{v}"),
+            Self::Uint64(v) => write!(f, "{v}"),
+            Self::Int8(v) => write!(f, "{v}"),
+            Self::Int16(v) => write!(f, "{v}"),
+            Self::Int32(v) => write!(f, "{v}"),
+            Self::Int64(v) => write!(f, "{v}"),
+            Self::Float32(v) => write!(f, "{v}"),
+            Self::Float64(v) => write!(f, "{v}"),
+            Self::String(v) => write!(f, "{v}"),
+            Self::Attribute(v) => write!(f, "{v}"),
+            Self::Location(v) => write!(f, "{v}"),
+            Self::Region(v) => write!(f, "{v}"),
+            Self::Group(v) => write!(f, "{v}"),
+            Self::Metric(v) => write!(f, "{v}"),
+            Self::Comm(v) => write!(f, "{v}"),
+            Self::Parameter(v) => write!(f, "{v}"),
+            Self::RmaWin(v) => write!(f, "{v}"),
+            Self::SourceCodeLocation(v) => write!(f, "{v}"),
+            Self::CallingContext(v) => write!(f, "{v}"),
+            Self::InterruptGenerator(v) => write!(f, "{v}"),
+            Self::IoFile(v) => write!(f, "{v}"),
+            Self::IoHandle(v) => write!(f, "{v}"),
+            Self::LocationGroup(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_attributes_are_not_allocated() {
+        let attributes = Attributes::from(Vec::new());
+        assert!(attributes.0.is_none());
+        assert!(attributes.is_empty());
+        assert_eq!(Vec::from(attributes), Vec::new());
+    }
+
+    #[test]
+    fn attributes_look_up_by_ref() {
+        let attributes: Attributes = [
+            (OTF2_AttributeRef(1), AttributeValue::Uint32(7)),
+            (OTF2_AttributeRef(2), AttributeValue::Int64(-3)),
+        ].into_iter().collect();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes.get(OTF2_AttributeRef(2)), Some(AttributeValue::Int64(-3)));
+        assert_eq!(attributes.get(OTF2_AttributeRef(3)), None);
+    }
+
+    #[test]
+    fn display_shows_the_typed_value() {
+        assert_eq!(AttributeValue::Uint64(1 << 40).to_string(), "1099511627776");
+        assert_eq!(AttributeValue::Float64(2.5).to_string(), "2.5");
+        assert_eq!(AttributeValue::Int16(-4).to_string(), "-4");
+        assert_eq!(AttributeValue::Region(OTF2_RegionRef(12)).to_string(), "OTF2_RegionRef(12)");
+    }
+
+    #[test]
+    fn numeric_accessors_widen_without_loss() {
+        assert_eq!(AttributeValue::Uint8(200).as_u64(), Some(200));
+        assert_eq!(AttributeValue::Int8(-1).as_u64(), None);
+        assert_eq!(AttributeValue::Uint32(u32::MAX).as_i64(), Some(u32::MAX as i64));
+        assert_eq!(AttributeValue::Uint64(1).as_i64(), None);
+        assert_eq!(AttributeValue::Float32(0.5).as_f64(), Some(0.5));
+        assert_eq!(AttributeValue::Int64(1).as_f64(), None);
+    }
+
+    #[test]
+    fn raw_values_convert_back_unchanged() {
+        for value in [AttributeValue::Uint16(7), AttributeValue::Float64(-0.25), AttributeValue::Location(OTF2_LocationRef(3)), AttributeValue::None(())] {
+            let (kind, raw) = value.to_raw();
+            assert_eq!(AttributeValue::new(kind, raw), value);
+        }
+        assert_eq!(MetricValue::Int64(-9).to_raw().0.to_enum(), OTF2_Type_enum::OTF2_TYPE_INT64);
+    }
+}
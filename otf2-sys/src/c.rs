@@ -134,60 +134,8 @@ impl std::fmt::Debug for OTF2_MetricValue {
     }
 }
 
-impl OTF2_AttributeValue {
-    pub fn display(&self, t: OTF2_Type) -> String {
-        let t: OTF2_Type_enum = t.into();
-        unsafe {
-            match t {
-                OTF2_Type_enum::OTF2_TYPE_NONE => format!("OTF2_TYPE_NONE({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_UINT8 => format!("OTF2_TYPE_UINT8({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_UINT16 => format!("OTF2_TYPE_UINT16({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_UINT32 => format!("OTF2_TYPE_UINT32({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_UINT64 => format!("OTF2_TYPE_UINT64({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_INT8 => format!("OTF2_TYPE_INT8({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_INT16 => format!("OTF2_TYPE_INT16({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_INT32 => format!("OTF2_TYPE_INT32({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_INT64 => format!("OTF2_TYPE_INT64({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_FLOAT => format!("OTF2_TYPE_FLOAT({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_DOUBLE => format!("OTF2_TYPE_DOUBLE({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_STRING => format!("OTF2_TYPE_STRING({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_ATTRIBUTE => {
-                    format!("OTF2_TYPE_ATTRIBUTE({})", self.0.uint8)
-                }
-                OTF2_Type_enum::OTF2_TYPE_LOCATION => {
-                    format!("OTF2_TYPE_LOCATION({})", self.0.uint8)
-                }
-                OTF2_Type_enum::OTF2_TYPE_REGION => format!("OTF2_TYPE_REGION({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_GROUP => format!("OTF2_TYPE_GROUP({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_METRIC => format!("OTF2_TYPE_METRIC({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_COMM => format!("OTF2_TYPE_COMM({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_PARAMETER => {
-                    format!("OTF2_TYPE_PARAMETER({})", self.0.uint8)
-                }
-                OTF2_Type_enum::OTF2_TYPE_RMA_WIN => format!("OTF2_TYPE_RMA_WIN({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_SOURCE_CODE_LOCATION => {
-                    format!("OTF2_TYPE_SOURCE_CODE_LOCATION({})", self.0.uint8)
-                }
-                OTF2_Type_enum::OTF2_TYPE_CALLING_CONTEXT => {
-                    format!("OTF2_TYPE_CALLING_CONTEXT({})", self.0.uint8)
-                }
-                OTF2_Type_enum::OTF2_TYPE_INTERRUPT_GENERATOR => {
-                    format!("OTF2_TYPE_INTERRUPT_GENERATOR({})", self.0.uint8)
-                }
-                OTF2_Type_enum::OTF2_TYPE_IO_FILE => format!("OTF2_TYPE_IO_FILE({})", self.0.uint8),
-                OTF2_Type_enum::OTF2_TYPE_IO_HANDLE => {
-                    format!("OTF2_TYPE_IO_HANDLE({})", self.0.uint8)
-                }
-                OTF2_Type_enum::OTF2_TYPE_LOCATION_GROUP => {
-                    format!("OTF2_TYPE_LOCATION_GROUP({})", self.0.uint8)
-                }
-            }
-        }
-    }
-}
-
 crate::internal::declare_enum_union_wrapper!(
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum MetricValue(union: OTF2_MetricValue) {
         OTF2_TYPE_UINT64 => Uint64(u64) from unsigned_int,
@@ -0,0 +1,304 @@
+//! OTF2 collective callbacks implemented with threads, so that several threads in one process can
+//! read an archive collectively in the way that MPI ranks do.
+
+use crate::internal::*;
+
+use std::os::raw::c_void;
+use std::sync::{Arc, Barrier, Mutex, PoisonError};
+
+use OTF2_CallbackCode::*;
+
+/// A communicator shared by the threads which read an archive together. Each thread reads with
+/// its own rank, see [`crate::reader::open_collective`].
+#[derive(Debug, Clone)]
+pub struct ThreadCommunicator(Arc<Shared>);
+
+#[derive(Debug)]
+struct Shared {
+    size: u32,
+    barrier: Barrier,
+    exchange: Mutex<Exchange>,
+}
+
+/// Data in flight during a collective operation: one buffer per rank, plus the element counts of
+/// the root for `scatterv`.
+#[derive(Debug, Default)]
+struct Exchange {
+    buffers: Vec<Vec<u8>>,
+    counts: Vec<u32>,
+}
+
+impl ThreadCommunicator {
+    pub fn new(size: u32) -> Self {
+        assert!(size > 0, "a communicator needs at least one rank");
+        Self(Arc::new(Shared {
+            size,
+            barrier: Barrier::new(size as usize),
+            exchange: Mutex::new(Exchange { buffers: vec![Vec::new(); size as usize], counts: Vec::new() }),
+        }))
+    }
+
+    pub fn size(&self) -> u32 {
+        self.0.size
+    }
+
+    /// The context passed to OTF2 for the thread with rank `rank`.
+    pub(crate) fn context(&self, rank: u32) -> Box<RankContext> {
+        assert!(rank < self.size(), "rank {rank} out of range for communicator of size {}", self.size());
+        Box::new(RankContext { comm: self.clone(), rank })
+    }
+
+    /// The share of `locations` read by `rank`: those at the indices `i` with `i % size == rank`.
+    pub fn split_locations<T: Copy>(&self, rank: u32, locations: &[T]) -> Vec<T> {
+        locations.iter()
+            .enumerate()
+            .filter(|(i, _)| *i as u32 % self.size() == rank)
+            .map(|(_, location)| *location)
+            .collect()
+    }
+
+    fn barrier(&self) {
+        self.0.barrier.wait();
+    }
+
+    fn with_exchange<R>(&self, f: impl FnOnce(&mut Exchange) -> R) -> R {
+        f(&mut self.0.exchange.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// The `OTF2_CollectiveContext` of one thread. Owned by the `Trace` it was registered with.
+#[derive(Debug)]
+pub(crate) struct RankContext {
+    comm: ThreadCommunicator,
+    rank: u32,
+}
+
+impl RankContext {
+    pub(crate) fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    pub(crate) fn size(&self) -> u32 {
+        self.comm.size()
+    }
+
+    pub(crate) fn split_locations<T: Copy>(&self, locations: &[T]) -> Vec<T> {
+        self.comm.split_locations(self.rank, locations)
+    }
+
+    pub(crate) fn as_context_ptr(&mut self) -> *mut OTF2_CollectiveContext {
+        self as *mut Self as *mut _
+    }
+}
+
+/// OTF2 keeps a pointer to the callbacks rather than a copy, so they live in a static.
+pub(crate) static THREAD_COLLECTIVE_CALLBACKS: OTF2_CollectiveCallbacks = OTF2_CollectiveCallbacks {
+    otf2_release: None,
+    otf2_get_size: Some(callbacks::get_size),
+    otf2_get_rank: Some(callbacks::get_rank),
+    // Local communicators are only needed by substrates which share files between ranks
+    otf2_create_local_comm: None,
+    otf2_free_local_comm: None,
+    otf2_barrier: Some(callbacks::barrier),
+    otf2_bcast: Some(callbacks::bcast),
+    otf2_gather: Some(callbacks::gather),
+    otf2_gatherv: Some(callbacks::gatherv),
+    otf2_scatter: Some(callbacks::scatter),
+    otf2_scatterv: Some(callbacks::scatterv),
+};
+
+fn type_size(kind: OTF2_Type) -> Option<usize> {
+    use OTF2_Type_enum::*;
+    match kind.to_enum() {
+        OTF2_TYPE_UINT8 | OTF2_TYPE_INT8 => Some(1),
+        OTF2_TYPE_UINT16 | OTF2_TYPE_INT16 => Some(2),
+        OTF2_TYPE_UINT32 | OTF2_TYPE_INT32 | OTF2_TYPE_FLOAT => Some(4),
+        OTF2_TYPE_UINT64 | OTF2_TYPE_INT64 | OTF2_TYPE_DOUBLE => Some(8),
+        _ => None,
+    }
+}
+
+mod callbacks {
+    use super::*;
+
+    #[inline]
+    fn as_rank<'a>(context: *mut OTF2_CollectiveContext) -> &'a RankContext {
+        assert!(!context.is_null(), "collective context must not be null pointer");
+        unsafe { &*(context as *const RankContext) }
+    }
+
+    macro_rules! element_size {
+        ($kind:expr) => {
+            match type_size($kind) {
+                Some(size) => size,
+                None => return OTF2_CALLBACK_ERROR,
+            }
+        };
+    }
+
+    pub unsafe extern "C" fn get_size(_: *mut c_void, context: *mut OTF2_CollectiveContext, size: *mut u32) -> OTF2_CallbackCode {
+        unsafe { *size = as_rank(context).size() };
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    pub unsafe extern "C" fn get_rank(_: *mut c_void, context: *mut OTF2_CollectiveContext, rank: *mut u32) -> OTF2_CallbackCode {
+        unsafe { *rank = as_rank(context).rank() };
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    pub unsafe extern "C" fn barrier(_: *mut c_void, context: *mut OTF2_CollectiveContext) -> OTF2_CallbackCode {
+        as_rank(context).comm.barrier();
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    pub unsafe extern "C" fn bcast(_: *mut c_void, context: *mut OTF2_CollectiveContext, data: *mut c_void, elements: u32, kind: OTF2_Type, root: u32) -> OTF2_CallbackCode {
+        let RankContext { comm, rank } = as_rank(context);
+        let bytes = elements as usize * element_size!(kind);
+        if *rank == root {
+            let data = unsafe { slice_from_raw!(data as *const u8, bytes) };
+            comm.with_exchange(|exchange| exchange.buffers[root as usize] = data.to_vec());
+        }
+        comm.barrier();
+        if *rank != root {
+            let data = unsafe { std::slice::from_raw_parts_mut(data as *mut u8, bytes) };
+            comm.with_exchange(|exchange| data.copy_from_slice(&exchange.buffers[root as usize]));
+        }
+        comm.barrier();
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    /// Deposit this rank's input, then let the root concatenate all inputs into `out_data`.
+    unsafe fn gather_bytes(comm: &ThreadCommunicator, rank: u32, in_data: &[u8], out_data: *mut c_void, root: u32) {
+        comm.with_exchange(|exchange| exchange.buffers[rank as usize] = in_data.to_vec());
+        comm.barrier();
+        if rank == root {
+            comm.with_exchange(|exchange| {
+                let mut offset = 0;
+                for buffer in &exchange.buffers {
+                    let out = unsafe { std::slice::from_raw_parts_mut((out_data as *mut u8).add(offset), buffer.len()) };
+                    out.copy_from_slice(buffer);
+                    offset += buffer.len();
+                }
+            });
+        }
+        comm.barrier();
+    }
+
+    pub unsafe extern "C" fn gather(_: *mut c_void, context: *mut OTF2_CollectiveContext, in_data: *const c_void, out_data: *mut c_void, elements: u32, kind: OTF2_Type, root: u32) -> OTF2_CallbackCode {
+        let RankContext { comm, rank } = as_rank(context);
+        let in_data = unsafe { slice_from_raw!(in_data as *const u8, elements as usize * element_size!(kind)) };
+        unsafe { gather_bytes(comm, *rank, in_data, out_data, root) };
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    pub unsafe extern "C" fn gatherv(_: *mut c_void, context: *mut OTF2_CollectiveContext, in_data: *const c_void, in_elements: u32, out_data: *mut c_void, _out_elements: *const u32, kind: OTF2_Type, root: u32) -> OTF2_CallbackCode {
+        let RankContext { comm, rank } = as_rank(context);
+        let in_data = unsafe { slice_from_raw!(in_data as *const u8, in_elements as usize * element_size!(kind)) };
+        unsafe { gather_bytes(comm, *rank, in_data, out_data, root) };
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    /// The root deposits its whole input and each rank's element count, then every rank copies its
+    /// own part into `out_data`.
+    unsafe fn scatter_bytes(comm: &ThreadCommunicator, rank: u32, root_input: Option<(&[u8], Vec<u32>)>, out_data: *mut c_void, element_size: usize, root: u32) {
+        if let Some((in_data, counts)) = root_input {
+            comm.with_exchange(|exchange| {
+                exchange.buffers[root as usize] = in_data.to_vec();
+                exchange.counts = counts;
+            });
+        }
+        comm.barrier();
+        comm.with_exchange(|exchange| {
+            let offset: usize = exchange.counts[..rank as usize].iter().map(|&n| n as usize * element_size).sum();
+            let len = exchange.counts[rank as usize] as usize * element_size;
+            let out = unsafe { std::slice::from_raw_parts_mut(out_data as *mut u8, len) };
+            out.copy_from_slice(&exchange.buffers[root as usize][offset..offset + len]);
+        });
+        comm.barrier();
+    }
+
+    pub unsafe extern "C" fn scatter(_: *mut c_void, context: *mut OTF2_CollectiveContext, in_data: *const c_void, out_data: *mut c_void, elements: u32, kind: OTF2_Type, root: u32) -> OTF2_CallbackCode {
+        let RankContext { comm, rank } = as_rank(context);
+        let element_size = element_size!(kind);
+        let root_input = (*rank == root).then(|| {
+            let total = elements as usize * comm.size() as usize * element_size;
+            (unsafe { slice_from_raw!(in_data as *const u8, total) }, vec![elements; comm.size() as usize])
+        });
+        unsafe { scatter_bytes(comm, *rank, root_input, out_data, element_size, root) };
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    pub unsafe extern "C" fn scatterv(_: *mut c_void, context: *mut OTF2_CollectiveContext, in_data: *const c_void, in_elements: *const u32, out_data: *mut c_void, _out_elements: u32, kind: OTF2_Type, root: u32) -> OTF2_CallbackCode {
+        let RankContext { comm, rank } = as_rank(context);
+        let element_size = element_size!(kind);
+        let root_input = (*rank == root).then(|| {
+            let counts = unsafe { slice_from_raw!(in_elements, comm.size()) }.to_vec();
+            let total = counts.iter().map(|&n| n as usize).sum::<usize>() * element_size;
+            (unsafe { slice_from_raw!(in_data as *const u8, total) }, counts)
+        });
+        unsafe { scatter_bytes(comm, *rank, root_input, out_data, element_size, root) };
+        OTF2_CALLBACK_SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    const UINT32: OTF2_Type = OTF2_Type(OTF2_Type_enum::OTF2_TYPE_UINT32 as u8);
+
+    /// Run `f` on `size` threads, each with its own rank context.
+    fn on_ranks<R: Send>(size: u32, f: impl Fn(*mut OTF2_CollectiveContext, u32) -> R + Sync) -> Vec<R> {
+        let comm = ThreadCommunicator::new(size);
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..size)
+                .map(|rank| {
+                    let (comm, f) = (&comm, &f);
+                    scope.spawn(move || {
+                        let mut context = comm.context(rank);
+                        f(context.as_context_ptr(), rank)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        })
+    }
+
+    #[test]
+    fn bcast_copies_root_data() {
+        let results = on_ranks(4, |context, rank| {
+            let mut data = if rank == 2 { [7u32, 8, 9] } else { [0; 3] };
+            let code = unsafe { THREAD_COLLECTIVE_CALLBACKS.otf2_bcast.unwrap()(std::ptr::null_mut(), context, data.as_mut_ptr() as *mut _, 3, UINT32, 2) };
+            assert_eq!(code, OTF2_CALLBACK_SUCCESS);
+            data
+        });
+        assert!(results.iter().all(|data| *data == [7, 8, 9]));
+    }
+
+    #[test]
+    fn gather_then_scatterv_round_trip() {
+        let results = on_ranks(3, |context, rank| {
+            let callbacks = &THREAD_COLLECTIVE_CALLBACKS;
+            let input = [rank * 10, rank * 10 + 1];
+            let mut gathered = [0u32; 6];
+            unsafe { callbacks.otf2_gather.unwrap()(std::ptr::null_mut(), context, input.as_ptr() as *const _, gathered.as_mut_ptr() as *mut _, 2, UINT32, 0) };
+            let counts = [1u32, 2, 3];
+            let mut out = vec![0u32; counts[rank as usize] as usize];
+            unsafe { callbacks.otf2_scatterv.unwrap()(std::ptr::null_mut(), context, gathered.as_ptr() as *const _, counts.as_ptr(), out.as_mut_ptr() as *mut _, out.len() as u32, UINT32, 0) };
+            (gathered, out)
+        });
+        assert_eq!(results[0].0, [0, 1, 10, 11, 20, 21]);
+        assert_eq!(results[0].1, vec![0]);
+        assert_eq!(results[1].1, vec![1, 10]);
+        assert_eq!(results[2].1, vec![11, 20, 21]);
+    }
+
+    #[test]
+    fn split_locations_covers_all() {
+        let comm = ThreadCommunicator::new(3);
+        let locations: Vec<u64> = (0..8).collect();
+        let split: Vec<_> = (0..3).map(|rank| comm.split_locations(rank, &locations)).collect();
+        assert_eq!(split, vec![vec![0, 3, 6], vec![1, 4, 7], vec![2, 5]]);
+    }
+}
@@ -8,7 +8,7 @@ pub type AttributeRegistry = std::collections::BTreeMap<OTF2_AttributeRef, Attri
 
 impl DefinitionVisitor for AttributeRegistry {
     fn visit_attribute(&mut self, defn: OTF2_AttributeRef, name: OTF2_StringRef, description: OTF2_StringRef, type_: OTF2_Type) -> OTF2_CallbackCode {
-        self.insert(defn, AttributeDef { name, description, kind: type_ });
+        self.insert(defn, AttributeDef { name, description, kind: type_.into() });
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
 }
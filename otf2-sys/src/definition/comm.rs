@@ -0,0 +1,196 @@
+use crate::internal::*;
+use crate::types::GroupType;
+use std::collections::BTreeMap;
+
+use super::set::DefinitionSet;
+
+#[derive(Debug, Clone, PartialEq)]
+enum CommRanks {
+    Intra(Vec<OTF2_LocationRef>),
+    // MPI_COMM_SELF, whose only rank is the location using it
+    SelfOnly,
+    // An inter-communicator, whose ranks refer to the group the caller isn't in
+    Inter(Vec<OTF2_LocationRef>, Vec<OTF2_LocationRef>),
+}
+
+/// Resolves the ranks of communicators, as found in events like `MpiSend` or `MpiCollectiveEnd`,
+/// into the locations they ran on.
+///
+/// The members of a `CommGroup` group are indices into the `CommLocations` group of the same
+/// paradigm, whose members are locations. If the paradigm has no such group, the members are
+/// taken to be ranks of the parent communicator instead, or of the common communicator for the
+/// groups of an inter-communicator.
+#[derive(Debug, Default, Clone)]
+pub struct CommResolver {
+    comms: BTreeMap<OTF2_CommRef, CommRanks>,
+}
+
+impl CommResolver {
+    pub fn new(definitions: &DefinitionSet) -> Self {
+        let mut resolver = Self::default();
+        let limit = definitions.comms.len() + definitions.inter_comms.len();
+        for &comm in definitions.comms.keys().chain(definitions.inter_comms.keys()) {
+            resolver.resolve(definitions, comm, limit);
+        }
+        resolver
+    }
+
+    /// Resolve `comm` and its ancestors, giving up after `limit` levels of nesting in case the
+    /// parents form a cycle.
+    fn resolve(&mut self, definitions: &DefinitionSet, comm: OTF2_CommRef, limit: usize) -> Option<&CommRanks> {
+        if !self.comms.contains_key(&comm) {
+            let ranks = if let Some(def) = definitions.comms.get(&comm) {
+                let group = definitions.groups.get(&def.group)?;
+                match group.group_type {
+                    GroupType::CommSelf => CommRanks::SelfOnly,
+                    _ => CommRanks::Intra(self.group_locations(definitions, def.group, def.parent, limit)?),
+                }
+            } else {
+                let def = definitions.inter_comms.get(&comm)?;
+                CommRanks::Inter(
+                    self.group_locations(definitions, def.group_a, def.common_communicator, limit)?,
+                    self.group_locations(definitions, def.group_b, def.common_communicator, limit)?,
+                )
+            };
+            self.comms.insert(comm, ranks);
+        }
+        self.comms.get(&comm)
+    }
+
+    fn group_locations(&mut self, definitions: &DefinitionSet, group: OTF2_GroupRef, parent: Option<OTF2_CommRef>, limit: usize) -> Option<Vec<OTF2_LocationRef>> {
+        let group = definitions.groups.get(&group)?;
+        match group.group_type {
+            GroupType::CommLocations => Some(group.members.iter().copied().map(OTF2_LocationRef).collect()),
+            GroupType::CommGroup => {
+                let locations = definitions.groups.values()
+                    .find(|other| other.group_type == GroupType::CommLocations && other.paradigm == group.paradigm);
+                let ranks: Vec<_> = match locations {
+                    Some(locations) => locations.members.iter().copied().map(OTF2_LocationRef).collect(),
+                    None => match self.resolve(definitions, parent?, limit.checked_sub(1)?)? {
+                        CommRanks::Intra(ranks) => ranks.clone(),
+                        _ => return None,
+                    },
+                };
+                group.members.iter()
+                    .map(|&member| ranks.get(usize::try_from(member).ok()?).copied())
+                    .collect()
+            }
+            _ => None,
+        }
+    }
+
+    /// The location of `rank` in `comm`, as seen from `location`. For an inter-communicator, the
+    /// rank is in the group which `location` isn't part of.
+    pub fn location(&self, comm: OTF2_CommRef, rank: u32, location: OTF2_LocationRef) -> Option<OTF2_LocationRef> {
+        let rank = rank as usize;
+        match self.comms.get(&comm)? {
+            CommRanks::Intra(ranks) => ranks.get(rank).copied(),
+            CommRanks::SelfOnly => (rank == 0).then_some(location),
+            CommRanks::Inter(a, b) if a.contains(&location) => b.get(rank).copied(),
+            CommRanks::Inter(a, b) if b.contains(&location) => a.get(rank).copied(),
+            CommRanks::Inter(..) => None,
+        }
+    }
+
+    /// The rank of `location` in `comm`, or in its own group of an inter-communicator.
+    pub fn rank(&self, comm: OTF2_CommRef, location: OTF2_LocationRef) -> Option<u32> {
+        let position = |ranks: &[OTF2_LocationRef]| ranks.iter().position(|&member| member == location);
+        let rank = match self.comms.get(&comm)? {
+            CommRanks::Intra(ranks) => position(ranks)?,
+            CommRanks::SelfOnly => 0,
+            CommRanks::Inter(a, b) => position(a).or_else(|| position(b))?,
+        };
+        u32::try_from(rank).ok()
+    }
+
+    /// The locations of the ranks of an intra-communicator, in rank order. Returns `None` for
+    /// inter-communicators and `MPI_COMM_SELF`, whose members depend on the caller.
+    pub fn locations(&self, comm: OTF2_CommRef) -> Option<&[OTF2_LocationRef]> {
+        match self.comms.get(&comm)? {
+            CommRanks::Intra(ranks) => Some(ranks),
+            _ => None,
+        }
+    }
+
+    /// Whether `comm` is `MPI_COMM_SELF`, of which every location is the only member.
+    pub fn is_self(&self, comm: OTF2_CommRef) -> bool {
+        matches!(self.comms.get(&comm), Some(CommRanks::SelfOnly))
+    }
+
+    /// The number of locations taking part in an operation on `comm`: both groups of an
+    /// inter-communicator, and 1 for `MPI_COMM_SELF`.
+    pub fn member_count(&self, comm: OTF2_CommRef) -> Option<usize> {
+        match self.comms.get(&comm)? {
+            CommRanks::Intra(ranks) => Some(ranks.len()),
+            CommRanks::SelfOnly => Some(1),
+            CommRanks::Inter(a, b) => Some(a.len() + b.len()),
+        }
+    }
+
+    /// The number of ranks in `comm`, or in the remote group of an inter-communicator as seen
+    /// from `location`.
+    pub fn size(&self, comm: OTF2_CommRef, location: OTF2_LocationRef) -> Option<usize> {
+        match self.comms.get(&comm)? {
+            CommRanks::Intra(ranks) => Some(ranks.len()),
+            CommRanks::SelfOnly => Some(1),
+            CommRanks::Inter(a, b) if a.contains(&location) => Some(b.len()),
+            CommRanks::Inter(a, b) if b.contains(&location) => Some(a.len()),
+            CommRanks::Inter(..) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fixtures::{comm, group, inter_comm, mpi_world};
+    use crate::types::Paradigm;
+
+    fn locations(refs: &[u64]) -> Vec<OTF2_LocationRef> {
+        refs.iter().copied().map(OTF2_LocationRef).collect()
+    }
+
+    fn resolver() -> CommResolver {
+        let mut definitions = mpi_world(&[10, 20, 30, 40]);
+        definitions.extend([
+            group(2, GroupType::CommGroup, Paradigm::Mpi, vec![3, 1]),
+            group(3, GroupType::CommGroup, Paradigm::Mpi, vec![0, 2]),
+            group(4, GroupType::CommSelf, Paradigm::Mpi, vec![]),
+            // A thread team without a locations group: members are ranks of its parent, comm 1
+            group(5, GroupType::CommGroup, Paradigm::OpenMp, vec![1]),
+            comm(1, 2, Some(0)),
+            comm(2, 4, None),
+            comm(3, 5, Some(1)),
+            inter_comm(4, 2, 3, 0),
+        ]);
+        CommResolver::new(&definitions.into())
+    }
+
+    #[test]
+    fn resolves_intra_communicator_ranks() {
+        let resolver = resolver();
+        let world = OTF2_CommRef(0);
+        assert_eq!(resolver.locations(world), Some(&locations(&[10, 20, 30, 40])[..]));
+        assert_eq!(resolver.location(OTF2_CommRef(1), 0, OTF2_LocationRef(20)), Some(OTF2_LocationRef(40)));
+        assert_eq!(resolver.rank(OTF2_CommRef(1), OTF2_LocationRef(20)), Some(1));
+        assert_eq!(resolver.location(OTF2_CommRef(1), 2, OTF2_LocationRef(20)), None);
+        assert_eq!(resolver.location(OTF2_CommRef(2), 0, OTF2_LocationRef(30)), Some(OTF2_LocationRef(30)));
+        assert!(resolver.is_self(OTF2_CommRef(2)) && !resolver.is_self(world));
+        assert_eq!(resolver.member_count(OTF2_CommRef(2)), Some(1));
+        assert_eq!(resolver.locations(OTF2_CommRef(3)), Some(&locations(&[20])[..]));
+        assert_eq!(resolver.location(OTF2_CommRef(9), 0, OTF2_LocationRef(10)), None);
+    }
+
+    #[test]
+    fn resolves_remote_ranks_of_inter_communicators() {
+        let resolver = resolver();
+        let inter = OTF2_CommRef(4);
+        assert_eq!(resolver.location(inter, 1, OTF2_LocationRef(40)), Some(OTF2_LocationRef(30)));
+        assert_eq!(resolver.location(inter, 1, OTF2_LocationRef(30)), Some(OTF2_LocationRef(20)));
+        assert_eq!(resolver.rank(inter, OTF2_LocationRef(30)), Some(1));
+        assert_eq!(resolver.size(inter, OTF2_LocationRef(10)), Some(2));
+        assert_eq!(resolver.member_count(inter), Some(4));
+        assert!(!resolver.is_self(inter));
+        assert_eq!(resolver.location(inter, 0, OTF2_LocationRef(50)), None);
+    }
+}
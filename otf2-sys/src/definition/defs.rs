@@ -1,47 +1,48 @@
 use crate::internal::*;
+use crate::types::{Type, Base, CartPeriodicity, CommFlags, GroupFlags, GroupType, InterruptGeneratorMode, IoAccessMode, IoHandleFlags, IoParadigmClass, IoParadigmFlags, IoParadigmProperty, IoStatusFlags, LocationGroupType, LocationType, MetricMode, MetricOccurrence, MetricScope, MetricType, Paradigm, ParadigmClass, ParadigmProperty, ParameterType, RecorderKind, RegionFlags, RegionRole, RmaWinFlags, SystemTreeDomain};
 use crate::attribute::AttributeValue;
 
 macro_rules! for_each_definition {
     ($macro:ident) => {
         $macro!(
             name: String, reftype: OTF2_StringRef, type: String;
-            name: Attribute, reftype: OTF2_AttributeRef, struct: AttributeDef { name: OTF2_StringRef, description: OTF2_StringRef, kind: OTF2_Type};
+            name: Attribute, reftype: OTF2_AttributeRef, struct: AttributeDef { name: OTF2_StringRef, description: OTF2_StringRef, kind: Type};
             name: ClockProperties, struct: ClockPropertiesDef { timer_resolution: u64, global_offset: u64, trace_length: u64, realtime_timestamp: u64};
-            name: Paradigm, reftype: OTF2_Paradigm, struct: ParadigmDef { paradigm: OTF2_Paradigm, name: OTF2_StringRef, paradigm_class: OTF2_ParadigmClass};
-            name: ParadigmProperty, reftype: OTF2_Paradigm, struct: ParadigmPropertyDef { paradigm: OTF2_Paradigm, property: OTF2_ParadigmProperty, value: AttributeValue};
-            name: IoParadigm, reftype: OTF2_IoParadigmRef, struct: IoParadigmDef { identification: OTF2_StringRef, name: OTF2_StringRef, io_paradigm_class: OTF2_IoParadigmClass, io_paradigm_flags: OTF2_IoParadigmFlag, properties: Vec<OTF2_IoParadigmProperty>, values: Vec<AttributeValue>};
+            name: Paradigm, reftype: Paradigm, struct: ParadigmDef { paradigm: Paradigm, name: OTF2_StringRef, paradigm_class: ParadigmClass};
+            name: ParadigmProperty, reftype: Paradigm, struct: ParadigmPropertyDef { paradigm: Paradigm, property: ParadigmProperty, value: AttributeValue};
+            name: IoParadigm, reftype: OTF2_IoParadigmRef, struct: IoParadigmDef { identification: OTF2_StringRef, name: OTF2_StringRef, io_paradigm_class: IoParadigmClass, io_paradigm_flags: IoParadigmFlags, properties: Vec<IoParadigmProperty>, values: Vec<AttributeValue>};
             name: SystemTreeNode, reftype: OTF2_SystemTreeNodeRef, struct: SystemTreeNodeDef { name: OTF2_StringRef, class_name: OTF2_StringRef, parent: Option<OTF2_SystemTreeNodeRef>};
             name: SystemTreeNodeProperty, reftype: OTF2_SystemTreeNodeRef, struct: SystemTreeNodePropertyDef { system_tree_node: OTF2_SystemTreeNodeRef, name: OTF2_StringRef, value: AttributeValue};
-            name: SystemTreeNodeDomain, reftype: OTF2_SystemTreeNodeRef, struct: SystemTreeNodeDomainDef { system_tree_node: OTF2_SystemTreeNodeRef, system_tree_domain: OTF2_SystemTreeDomain};
-            name: Location, reftype: OTF2_LocationRef, struct: LocationDef { name: OTF2_StringRef, location_type: OTF2_LocationType, num_events: u64, location_group: OTF2_LocationGroupRef};
-            name: LocationGroup, reftype: OTF2_LocationGroupRef, struct: LocationGroupDef { name: OTF2_StringRef, location_group_type: OTF2_LocationGroupType, system_tree_parent: OTF2_SystemTreeNodeRef, creating_location_group: Option<OTF2_LocationGroupRef>};
+            name: SystemTreeNodeDomain, reftype: OTF2_SystemTreeNodeRef, struct: SystemTreeNodeDomainDef { system_tree_node: OTF2_SystemTreeNodeRef, system_tree_domain: SystemTreeDomain};
+            name: Location, reftype: OTF2_LocationRef, struct: LocationDef { name: OTF2_StringRef, location_type: LocationType, num_events: u64, location_group: OTF2_LocationGroupRef};
+            name: LocationGroup, reftype: OTF2_LocationGroupRef, struct: LocationGroupDef { name: OTF2_StringRef, location_group_type: LocationGroupType, system_tree_parent: OTF2_SystemTreeNodeRef, creating_location_group: Option<OTF2_LocationGroupRef>};
             name: LocationGroupProperty, reftype: OTF2_LocationGroupRef, struct: LocationGroupPropertyDef { location_group: OTF2_LocationGroupRef, name: OTF2_StringRef, value: AttributeValue};
             name: LocationProperty, reftype: OTF2_LocationRef, struct: LocationPropertyDef { location: OTF2_LocationRef, name: OTF2_StringRef, value: AttributeValue};
-            name: Region, reftype: OTF2_RegionRef, struct: RegionDef { name: OTF2_StringRef, canonical_name: OTF2_StringRef, description: OTF2_StringRef, region_role: OTF2_RegionRole, paradigm: OTF2_Paradigm, region_flags: OTF2_RegionFlag, source_file: OTF2_StringRef, begin_line_number: u32, end_line_number: u32};
+            name: Region, reftype: OTF2_RegionRef, struct: RegionDef { name: OTF2_StringRef, canonical_name: OTF2_StringRef, description: OTF2_StringRef, region_role: RegionRole, paradigm: Paradigm, region_flags: RegionFlags, source_file: OTF2_StringRef, begin_line_number: u32, end_line_number: u32};
             name: Callsite, reftype: OTF2_CallsiteRef, struct: CallsiteDef { source_file: OTF2_StringRef, line_number: u32, entered_region: OTF2_RegionRef, left_region: OTF2_RegionRef};
             name: Callpath, reftype: OTF2_CallpathRef, struct: CallpathDef { parent: Option<OTF2_CallpathRef>, region: OTF2_RegionRef};
             name: CallpathParameter, reftype: OTF2_CallpathRef, struct: CallpathParameterDef { callpath: OTF2_CallpathRef, parameter: OTF2_ParameterRef, value: AttributeValue};
             name: SourceCodeLocation, reftype: OTF2_SourceCodeLocationRef, struct: SourceCodeLocationDef { file: OTF2_StringRef, line_number: u32};
             name: CallingContext, reftype: OTF2_CallingContextRef, struct: CallingContextDef { region: OTF2_RegionRef, source_code_location: OTF2_SourceCodeLocationRef, parent: Option<OTF2_CallingContextRef>};
             name: CallingContextProperty, reftype: OTF2_CallingContextRef, struct: CallingContextPropertyDef { calling_context: OTF2_CallingContextRef, name: OTF2_StringRef, value: AttributeValue};
-            name: Group, reftype: OTF2_GroupRef, struct: GroupDef { name: OTF2_StringRef, group_type: OTF2_GroupType, paradigm: OTF2_Paradigm, group_flags: OTF2_GroupFlag, members: Vec<u64>};
-            name: MetricMember, reftype: OTF2_MetricMemberRef, struct: MetricMemberDef { name: OTF2_StringRef, description: OTF2_StringRef, metric_type: OTF2_MetricType, metric_mode: OTF2_MetricMode, value_type: OTF2_Type, base: OTF2_Base, exponent: i64, unit: OTF2_StringRef};
-            name: MetricClass, reftype: OTF2_MetricRef, struct: MetricClassDef { metric_members: Vec<OTF2_MetricMemberRef>, metric_occurrence: OTF2_MetricOccurrence, recorder_kind: OTF2_RecorderKind};
-            name: MetricInstance, reftype: OTF2_MetricRef, struct: MetricInstanceDef { metric_class: OTF2_MetricRef, recorder: OTF2_LocationRef, metric_scope: OTF2_MetricScope, scope: u64};
+            name: Group, reftype: OTF2_GroupRef, struct: GroupDef { name: OTF2_StringRef, group_type: GroupType, paradigm: Paradigm, group_flags: GroupFlags, members: Vec<u64>};
+            name: MetricMember, reftype: OTF2_MetricMemberRef, struct: MetricMemberDef { name: OTF2_StringRef, description: OTF2_StringRef, metric_type: MetricType, metric_mode: MetricMode, value_type: Type, base: Base, exponent: i64, unit: OTF2_StringRef};
+            name: MetricClass, reftype: OTF2_MetricRef, struct: MetricClassDef { metric_members: Vec<OTF2_MetricMemberRef>, metric_occurrence: MetricOccurrence, recorder_kind: RecorderKind};
+            name: MetricInstance, reftype: OTF2_MetricRef, struct: MetricInstanceDef { metric_class: OTF2_MetricRef, recorder: OTF2_LocationRef, metric_scope: MetricScope, scope: u64};
             name: MetricClassRecorder, reftype: OTF2_MetricRef, struct: MetricClassRecorderDef { metric_class: OTF2_MetricRef, recorder: OTF2_LocationRef};
-            name: Comm, reftype: OTF2_CommRef, struct: CommDef { name: OTF2_StringRef, group: OTF2_GroupRef, parent: Option<OTF2_CommRef>, flags: OTF2_CommFlag};
-            name: InterComm, reftype: OTF2_CommRef, struct: InterCommDef { name: OTF2_StringRef, group_a: OTF2_GroupRef, group_b: OTF2_GroupRef, common_communicator: Option<OTF2_CommRef>, flags: OTF2_CommFlag};
-            name: Parameter, reftype: OTF2_ParameterRef, struct: ParameterDef { name: OTF2_StringRef, parameter_type: OTF2_ParameterType};
-            name: RmaWin, reftype: OTF2_RmaWinRef, struct: RmaWinDef { name: OTF2_StringRef, comm: OTF2_CommRef, flags: OTF2_RmaWinFlag};
-            name: CartDimension, reftype: OTF2_CartDimensionRef, struct: CartDimensionDef { name: OTF2_StringRef, size: u32, periodic: OTF2_CartPeriodicity};
+            name: Comm, reftype: OTF2_CommRef, struct: CommDef { name: OTF2_StringRef, group: OTF2_GroupRef, parent: Option<OTF2_CommRef>, flags: CommFlags};
+            name: InterComm, reftype: OTF2_CommRef, struct: InterCommDef { name: OTF2_StringRef, group_a: OTF2_GroupRef, group_b: OTF2_GroupRef, common_communicator: Option<OTF2_CommRef>, flags: CommFlags};
+            name: Parameter, reftype: OTF2_ParameterRef, struct: ParameterDef { name: OTF2_StringRef, parameter_type: ParameterType};
+            name: RmaWin, reftype: OTF2_RmaWinRef, struct: RmaWinDef { name: OTF2_StringRef, comm: OTF2_CommRef, flags: RmaWinFlags};
+            name: CartDimension, reftype: OTF2_CartDimensionRef, struct: CartDimensionDef { name: OTF2_StringRef, size: u32, periodic: CartPeriodicity};
             name: CartTopology, reftype: OTF2_CartTopologyRef, struct: CartTopologyDef { name: OTF2_StringRef, communicator: OTF2_CommRef, dimensions: Vec<OTF2_CartDimensionRef>};
             name: CartCoordinate, reftype: OTF2_CartTopologyRef, struct: CartCoordinateDef { topology: OTF2_CartTopologyRef, rank: u32, coordinates: Vec<u32>};
-            name: InterruptGenerator, reftype: OTF2_InterruptGeneratorRef, struct: InterruptGeneratorDef { name: OTF2_StringRef, interrupt_generator_mode: OTF2_InterruptGeneratorMode, base: OTF2_Base, exponent: i64, period: u64};
+            name: InterruptGenerator, reftype: OTF2_InterruptGeneratorRef, struct: InterruptGeneratorDef { name: OTF2_StringRef, interrupt_generator_mode: InterruptGeneratorMode, base: Base, exponent: i64, period: u64};
             name: IoFileProperty, reftype: OTF2_IoFileRef, struct: IoFilePropertyDef { io_file: OTF2_IoFileRef, name: OTF2_StringRef, value: AttributeValue};
             name: IoRegularFile, reftype: OTF2_IoFileRef, struct: IoRegularFileDef { name: OTF2_StringRef, scope: OTF2_SystemTreeNodeRef};
             name: IoDirectory, reftype: OTF2_IoFileRef, struct: IoDirectoryDef { name: OTF2_StringRef, scope: OTF2_SystemTreeNodeRef};
-            name: IoHandle, reftype: OTF2_IoHandleRef, struct: IoHandleDef { name: OTF2_StringRef, file: OTF2_IoFileRef, io_paradigm: OTF2_IoParadigmRef, io_handle_flags: OTF2_IoHandleFlag, comm: Option<OTF2_CommRef>, parent: Option<OTF2_IoHandleRef>};
-            name: IoPreCreatedHandleState, reftype: OTF2_IoHandleRef, struct: IoPreCreatedHandleStateDef { io_handle: OTF2_IoHandleRef, mode: OTF2_IoAccessMode, status_flags: OTF2_IoStatusFlag};
+            name: IoHandle, reftype: OTF2_IoHandleRef, struct: IoHandleDef { name: OTF2_StringRef, file: OTF2_IoFileRef, io_paradigm: OTF2_IoParadigmRef, io_handle_flags: IoHandleFlags, comm: Option<OTF2_CommRef>, parent: Option<OTF2_IoHandleRef>};
+            name: IoPreCreatedHandleState, reftype: OTF2_IoHandleRef, struct: IoPreCreatedHandleStateDef { io_handle: OTF2_IoHandleRef, mode: IoAccessMode, status_flags: IoStatusFlags};
         );
     }
 }
@@ -65,7 +66,7 @@ for_each_definition!(define_value_struct);
 
 /// Stores definitions from a trace file. Each variant stores the data provided by the corresponding
 /// callback function which reports the particular definition.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Definition {
     String {
@@ -80,11 +81,11 @@ pub enum Definition {
         value: ClockPropertiesDef,
     },
     Paradigm {
-        defn: OTF2_Paradigm,
+        defn: Paradigm,
         value: ParadigmDef,
     },
     ParadigmProperty {
-        defn: OTF2_Paradigm,
+        defn: Paradigm,
         value: ParadigmPropertyDef,
     },
     IoParadigm {
@@ -220,3 +221,68 @@ pub enum Definition {
         value: IoPreCreatedHandleStateDef,
     },
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    fn round_trip(definition: &Definition) -> String {
+        let json = serde_json::to_string(definition).expect("failed to serialize definition");
+        let back: Definition = serde_json::from_str(&json).expect("failed to deserialize definition");
+        assert_eq!(&back, definition);
+        json
+    }
+
+    #[test]
+    fn definitions_round_trip_through_json() {
+        let region = Definition::Region {
+            defn: OTF2_RegionRef(4),
+            value: RegionDef {
+                name: OTF2_StringRef(0),
+                canonical_name: OTF2_StringRef(0),
+                description: OTF2_StringRef(1),
+                region_role: RegionRole::Function,
+                paradigm: Paradigm::Mpi,
+                region_flags: RegionFlags::PHASE,
+                source_file: OTF2_StringRef(2),
+                begin_line_number: 10,
+                end_line_number: 20,
+            },
+        };
+        let json = round_trip(&region);
+        assert!(json.contains(r#""region_role":"Function""#), "{json}");
+        assert!(json.contains(r#""paradigm":"Mpi""#), "{json}");
+        assert!(json.contains(r#""region_flags":"PHASE""#), "{json}");
+
+        let attribute = Definition::Attribute {
+            defn: OTF2_AttributeRef(0),
+            value: AttributeDef { name: OTF2_StringRef(3), description: OTF2_StringRef(4), kind: Type::Uint64 },
+        };
+        assert!(round_trip(&attribute).contains(r#""kind":"Uint64""#));
+
+        let metric_member = Definition::MetricMember {
+            defn: OTF2_MetricMemberRef(2),
+            value: MetricMemberDef {
+                name: OTF2_StringRef(5),
+                description: OTF2_StringRef(6),
+                metric_type: MetricType::Papi,
+                metric_mode: MetricMode::AccumulatedStart,
+                value_type: Type::Double,
+                base: Base::Decimal,
+                exponent: -3,
+                unit: OTF2_StringRef(7),
+            },
+        };
+        assert!(round_trip(&metric_member).contains(r#""value_type":"Double""#));
+
+        round_trip(&Definition::String { defn: OTF2_StringRef(0), value: "main".to_string() });
+        round_trip(&Definition::ParadigmProperty {
+            defn: Paradigm::OpenMp,
+            value: ParadigmPropertyDef { paradigm: Paradigm::OpenMp, property: ParadigmProperty::RmaOnly, value: AttributeValue::Uint8(1) },
+        });
+        round_trip(&Definition::Comm {
+            defn: OTF2_CommRef(1),
+            value: CommDef { name: OTF2_StringRef(5), group: OTF2_GroupRef(0), parent: None, flags: CommFlags::empty() },
+        });
+    }
+}
@@ -183,7 +183,7 @@ mod visitor_callbacks {
             defn,
             value: LocationDef {
                 name,
-                location_type,
+                location_type: location_type.into(),
                 num_events,
                 location_group,
             },
@@ -222,11 +222,11 @@ mod visitor_callbacks {
         paradigm_class: OTF2_ParadigmClass,
     ) -> OTF2_CallbackCode_enum {
         unsafe {push_def!(user_data, Definition::Paradigm {
-            defn: paradigm,
+            defn: paradigm.into(),
             value: ParadigmDef {
-                paradigm,
+                paradigm: paradigm.into(),
                 name,
-                paradigm_class,
+                paradigm_class: paradigm_class.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
@@ -240,10 +240,10 @@ mod visitor_callbacks {
         value: OTF2_AttributeValue,
     ) -> OTF2_CallbackCode_enum {
         unsafe {push_def!(user_data, Definition::ParadigmProperty {
-            defn: paradigm,
+            defn: paradigm.into(),
             value: ParadigmPropertyDef {
-                paradigm,
-                property,
+                paradigm: paradigm.into(),
+                property: property.into(),
                 value: AttributeValue::new(type_, value),
             },
         })};
@@ -270,9 +270,9 @@ mod visitor_callbacks {
             value: IoParadigmDef {
                 identification,
                 name,
-                io_paradigm_class,
-                io_paradigm_flags,
-                properties: properties_slice.to_vec(),
+                io_paradigm_class: io_paradigm_class.into(),
+                io_paradigm_flags: io_paradigm_flags.into(),
+                properties: properties_slice.iter().map(|&property| property.into()).collect(),
                 values: zipmap!(types_slice.iter(), values_slice.iter(), |(&k, &v)| AttributeValue::new(k, v))
             },
         })};
@@ -291,7 +291,7 @@ mod visitor_callbacks {
             value: AttributeDef {
                 name,
                 description,
-                kind,
+                kind: kind.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
@@ -347,7 +347,7 @@ mod visitor_callbacks {
             defn: system_tree_node,
             value: SystemTreeNodeDomainDef {
                 system_tree_node,
-                system_tree_domain,
+                system_tree_domain: system_tree_domain.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
@@ -371,7 +371,7 @@ mod visitor_callbacks {
             defn,
             value: LocationGroupDef {
                 name,
-                location_group_type,
+                location_group_type: location_group_type.into(),
                 system_tree_parent,
                 creating_location_group: creating_location_group_opt,
             },
@@ -434,9 +434,9 @@ mod visitor_callbacks {
                 name,
                 canonical_name,
                 description,
-                region_role,
-                paradigm,
-                region_flags,
+                region_role: region_role.into(),
+                paradigm: paradigm.into(),
+                region_flags: region_flags.into(),
                 source_file,
                 begin_line_number,
                 end_line_number,
@@ -573,9 +573,9 @@ mod visitor_callbacks {
             defn,
             value: GroupDef {
                 name,
-                group_type,
-                paradigm,
-                group_flags,
+                group_type: group_type.into(),
+                paradigm: paradigm.into(),
+                group_flags: group_flags.into(),
                 members: members_slice.to_vec(),
             },
         })};
@@ -599,10 +599,10 @@ mod visitor_callbacks {
             value: MetricMemberDef {
                 name,
                 description,
-                metric_type,
-                metric_mode,
-                value_type,
-                base,
+                metric_type: metric_type.into(),
+                metric_mode: metric_mode.into(),
+                value_type: value_type.into(),
+                base: base.into(),
                 exponent,
                 unit,
             },
@@ -623,8 +623,8 @@ mod visitor_callbacks {
             defn,
             value: MetricClassDef {
                 metric_members: metric_members_slice.to_vec(),
-                metric_occurrence,
-                recorder_kind,
+                metric_occurrence: metric_occurrence.into(),
+                recorder_kind: recorder_kind.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
@@ -643,7 +643,7 @@ mod visitor_callbacks {
             value: MetricInstanceDef {
                 metric_class,
                 recorder,
-                metric_scope,
+                metric_scope: metric_scope.into(),
                 scope,
             },
         })};
@@ -684,7 +684,7 @@ mod visitor_callbacks {
                 name,
                 group,
                 parent: parent_opt,
-                flags,
+                flags: flags.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
@@ -711,7 +711,7 @@ mod visitor_callbacks {
                 group_a,
                 group_b,
                 common_communicator: common_communicator_opt,
-                flags,
+                flags: flags.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
@@ -727,7 +727,7 @@ mod visitor_callbacks {
             defn,
             value: ParameterDef {
                 name,
-                parameter_type,
+                parameter_type: parameter_type.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
@@ -742,7 +742,7 @@ mod visitor_callbacks {
     ) -> OTF2_CallbackCode_enum {
         unsafe {push_def!(user_data, Definition::RmaWin {
             defn,
-            value: RmaWinDef { name, comm, flags },
+            value: RmaWinDef { name, comm, flags: flags.into() },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
@@ -759,7 +759,7 @@ mod visitor_callbacks {
             value: CartDimensionDef {
                 name,
                 size,
-                periodic,
+                periodic: periodic.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
@@ -818,8 +818,8 @@ mod visitor_callbacks {
             defn,
             value: InterruptGeneratorDef {
                 name,
-                interrupt_generator_mode,
-                base,
+                interrupt_generator_mode: interrupt_generator_mode.into(),
+                base: base.into(),
                 exponent,
                 period,
             },
@@ -897,7 +897,7 @@ mod visitor_callbacks {
                 name,
                 file,
                 io_paradigm,
-                io_handle_flags,
+                io_handle_flags: io_handle_flags.into(),
                 comm: comm_opt,
                 parent: parent_opt,
             },
@@ -915,8 +915,8 @@ mod visitor_callbacks {
             defn: io_handle,
             value: IoPreCreatedHandleStateDef {
                 io_handle,
-                mode,
-                status_flags,
+                mode: mode.into(),
+                status_flags: status_flags.into(),
             },
         })};
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
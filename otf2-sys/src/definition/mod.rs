@@ -1,9 +1,14 @@
 mod attribute;
+mod comm;
 mod location;
 mod global_reader_callbacks;
 mod print_defs;
+mod region;
+mod resolver;
+mod set;
 mod string;
 mod visitor;
+mod writer;
 mod defs;
 
 pub use global_reader_callbacks::GlobalDefReaderCallbacks;
@@ -13,3 +18,8 @@ pub use self::defs::*;
 pub use string::StringRegistry;
 pub use attribute::AttributeRegistry;
 pub use location::LocationRegistry;
+pub use comm::CommResolver;
+pub use region::RegionRegistry;
+pub use resolver::{AttributeResolver, ResolvedAttribute, ResolvedValue, ResolveError};
+pub use set::{DefinitionRef, DefinitionSet};
+pub(crate) use writer::write_definition;
@@ -2,6 +2,8 @@
 
 use crate::error::Status;
 use crate::internal::*;
+use crate::attribute::AttributeValue;
+use crate::types::Type;
 use std::ffi::CStr;
 
 use crate::definition::DefinitionVisitor;
@@ -80,7 +82,7 @@ impl DefinitionVisitor for PrintingDefinitionVisitor {
             paradigm,
             property,
             type_,
-            value.display(type_)
+            AttributeValue::new(type_, value)
         );
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
@@ -148,7 +150,7 @@ impl DefinitionVisitor for PrintingDefinitionVisitor {
             system_tree_node,
             name,
             type_,
-            value.display(type_)
+            AttributeValue::new(type_, value)
         );
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
@@ -192,7 +194,7 @@ impl DefinitionVisitor for PrintingDefinitionVisitor {
             location_group,
             name,
             type_,
-            value.display(type_)
+            AttributeValue::new(type_, value)
         );
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
@@ -209,7 +211,7 @@ impl DefinitionVisitor for PrintingDefinitionVisitor {
             location,
             name,
             type_,
-            value.display(type_)
+            AttributeValue::new(type_, value)
         );
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
@@ -283,7 +285,7 @@ impl DefinitionVisitor for PrintingDefinitionVisitor {
             callpath,
             parameter,
             type_,
-            value.display(type_)
+            AttributeValue::new(type_, value)
         );
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
@@ -327,7 +329,7 @@ impl DefinitionVisitor for PrintingDefinitionVisitor {
             calling_context,
             name,
             type_,
-            value.display(type_)
+            AttributeValue::new(type_, value)
         );
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
@@ -366,8 +368,8 @@ impl DefinitionVisitor for PrintingDefinitionVisitor {
         unit: OTF2_StringRef,
     ) -> OTF2_CallbackCode {
         eprintln!(
-            "visit_metric_member: defn: {}, name: {}, description: {}, metric_type: {}, metric_mode: {}, value_type: {}, base: {}, exponent: {}, unit: {}",
-            defn, name, description, metric_type, metric_mode, value_type, base, exponent, unit
+            "visit_metric_member: defn: {}, name: {}, description: {}, metric_type: {}, metric_mode: {}, value_type: {:?}, base: {}, exponent: {}, unit: {}",
+            defn, name, description, metric_type, metric_mode, Type::from(value_type), base, exponent, unit
         );
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
@@ -546,7 +548,7 @@ impl DefinitionVisitor for PrintingDefinitionVisitor {
             io_file,
             name,
             type_,
-            value.display(type_)
+            AttributeValue::new(type_, value)
         );
         OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
     }
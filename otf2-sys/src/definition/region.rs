@@ -0,0 +1,14 @@
+use crate::internal::*;
+use std::ffi::{CStr, CString};
+
+use super::DefinitionVisitor;
+use super::defs::RegionDef;
+
+pub type RegionRegistry = std::collections::BTreeMap<OTF2_RegionRef, RegionDef>;
+
+impl DefinitionVisitor for RegionRegistry {
+    fn visit_region(&mut self, defn: OTF2_RegionRef, name: OTF2_StringRef, canonical_name: OTF2_StringRef, description: OTF2_StringRef, region_role: OTF2_RegionRole, paradigm: OTF2_Paradigm, region_flags: OTF2_RegionFlag, source_file: OTF2_StringRef, begin_line_number: u32, end_line_number: u32) -> OTF2_CallbackCode {
+        self.insert(defn, RegionDef { name, canonical_name, description, region_role: region_role.into(), paradigm: paradigm.into(), region_flags: region_flags.into(), source_file, begin_line_number, end_line_number });
+        OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
+    }
+}
@@ -0,0 +1,153 @@
+use crate::internal::*;
+use crate::attribute::{AttributeValue, Attributes};
+
+use super::defs::Definition;
+use super::{AttributeRegistry, LocationRegistry, RegionRegistry, StringRegistry};
+
+/// A failure to resolve an attribute against the definitions of a trace.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ResolveError {
+    #[error("attribute {0} is not defined")]
+    UnknownAttribute(OTF2_AttributeRef),
+    #[error("attribute {attribute} is declared as {declared:?} but has a value of type {found:?}")]
+    TypeMismatch { attribute: OTF2_AttributeRef, declared: OTF2_Type_enum, found: OTF2_Type_enum },
+    #[error("string {0} is not defined")]
+    UnknownString(OTF2_StringRef),
+    #[error("region {0} is not defined")]
+    UnknownRegion(OTF2_RegionRef),
+    #[error("location {0} is not defined")]
+    UnknownLocation(OTF2_LocationRef),
+}
+
+/// An attribute value with string, attribute, region and location references replaced by their
+/// names. Values of any other type are passed through unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResolvedValue<'a> {
+    String(&'a str),
+    Attribute(&'a str),
+    Region(&'a str),
+    Location(&'a str),
+    Value(AttributeValue),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedAttribute<'a> {
+    pub name: &'a str,
+    pub description: &'a str,
+    pub value: ResolvedValue<'a>,
+}
+
+/// Resolves the raw attributes of events into names, using the definitions of a trace.
+#[derive(Debug, Default)]
+pub struct AttributeResolver {
+    attributes: AttributeRegistry,
+    strings: StringRegistry,
+    regions: RegionRegistry,
+    locations: LocationRegistry,
+}
+
+impl AttributeResolver {
+    pub fn new(attributes: AttributeRegistry, strings: StringRegistry, regions: RegionRegistry, locations: LocationRegistry) -> Self {
+        Self { attributes, strings, regions, locations }
+    }
+
+    /// Build a resolver from the global definitions of a trace.
+    pub fn from_definitions<'d>(definitions: impl IntoIterator<Item = &'d Definition>) -> Self {
+        let mut resolver = Self::default();
+        for definition in definitions {
+            match definition {
+                Definition::Attribute { defn, value } => { resolver.attributes.insert(*defn, value.clone()); },
+                Definition::String { defn, value } => { resolver.strings.insert(*defn, value.clone()); },
+                Definition::Region { defn, value } => { resolver.regions.insert(*defn, value.clone()); },
+                Definition::Location { defn, value } => { resolver.locations.insert(*defn, value.clone()); },
+                _ => {},
+            }
+        }
+        resolver
+    }
+
+    /// Resolve one attribute, checking its value against the type the attribute was declared with.
+    pub fn resolve(&self, attribute: OTF2_AttributeRef, value: AttributeValue) -> Result<ResolvedAttribute<'_>, ResolveError> {
+        let def = self.attributes.get(&attribute).ok_or(ResolveError::UnknownAttribute(attribute))?;
+        let (declared, found) = (OTF2_Type::from(def.kind).to_enum(), value.kind());
+        if declared != found {
+            return Err(ResolveError::TypeMismatch { attribute, declared, found });
+        }
+        let value = match value {
+            AttributeValue::String(string) => ResolvedValue::String(self.string(string)?),
+            AttributeValue::Attribute(other) => {
+                let other = self.attributes.get(&other).ok_or(ResolveError::UnknownAttribute(other))?;
+                ResolvedValue::Attribute(self.string(other.name)?)
+            },
+            AttributeValue::Region(region) => {
+                let region = self.regions.get(&region).ok_or(ResolveError::UnknownRegion(region))?;
+                ResolvedValue::Region(self.string(region.name)?)
+            },
+            AttributeValue::Location(location) => {
+                let location = self.locations.get(&location).ok_or(ResolveError::UnknownLocation(location))?;
+                ResolvedValue::Location(self.string(location.name)?)
+            },
+            value => ResolvedValue::Value(value),
+        };
+        Ok(ResolvedAttribute { name: self.string(def.name)?, description: self.string(def.description)?, value })
+    }
+
+    /// Resolve each of `attributes` in order.
+    pub fn resolve_all<'s>(&'s self, attributes: &'s Attributes) -> impl Iterator<Item = Result<ResolvedAttribute<'s>, ResolveError>> + 's {
+        attributes.iter().map(|(attribute, value)| self.resolve(*attribute, *value))
+    }
+
+    fn string(&self, string: OTF2_StringRef) -> Result<&str, ResolveError> {
+        self.strings.get(&string).map(String::as_str).ok_or(ResolveError::UnknownString(string))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definition::{AttributeDef, RegionDef};
+    use crate::types::{Paradigm, RegionFlags, RegionRole, Type};
+
+    fn resolver() -> AttributeResolver {
+        let strings = [(0, "stream"), (1, "CUDA stream id"), (2, "task"), (3, "task name"), (4, "compute")]
+            .map(|(defn, value)| Definition::String { defn: OTF2_StringRef(defn), value: value.to_string() });
+        let attributes = [
+            Definition::Attribute { defn: OTF2_AttributeRef(0), value: AttributeDef { name: OTF2_StringRef(0), description: OTF2_StringRef(1), kind: Type::Uint32 } },
+            Definition::Attribute { defn: OTF2_AttributeRef(1), value: AttributeDef { name: OTF2_StringRef(2), description: OTF2_StringRef(3), kind: Type::Region } },
+        ];
+        let region = Definition::Region {
+            defn: OTF2_RegionRef(7),
+            value: RegionDef {
+                name: OTF2_StringRef(4),
+                canonical_name: OTF2_StringRef(4),
+                description: OTF2_StringRef(4),
+                region_role: RegionRole::Function,
+                paradigm: Paradigm::User,
+                region_flags: RegionFlags::empty(),
+                source_file: OTF2_StringRef(4),
+                begin_line_number: 0,
+                end_line_number: 0,
+            },
+        };
+        AttributeResolver::from_definitions(strings.iter().chain(&attributes).chain([&region]))
+    }
+
+    #[test]
+    fn resolves_names_and_references() {
+        let resolver = resolver();
+        let stream = resolver.resolve(OTF2_AttributeRef(0), AttributeValue::Uint32(3)).unwrap();
+        assert_eq!(stream, ResolvedAttribute { name: "stream", description: "CUDA stream id", value: ResolvedValue::Value(AttributeValue::Uint32(3)) });
+        let task = resolver.resolve(OTF2_AttributeRef(1), AttributeValue::Region(OTF2_RegionRef(7))).unwrap();
+        assert_eq!(task.value, ResolvedValue::Region("compute"));
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        let resolver = resolver();
+        assert_eq!(
+            resolver.resolve(OTF2_AttributeRef(0), AttributeValue::Uint64(3)),
+            Err(ResolveError::TypeMismatch { attribute: OTF2_AttributeRef(0), declared: OTF2_Type_enum::OTF2_TYPE_UINT32, found: OTF2_Type_enum::OTF2_TYPE_UINT64 })
+        );
+        assert_eq!(resolver.resolve(OTF2_AttributeRef(9), AttributeValue::Uint32(3)), Err(ResolveError::UnknownAttribute(OTF2_AttributeRef(9))));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::internal::*;
+use crate::attribute::AttributeValue;
+use std::collections::BTreeMap;
+
+use super::defs::*;
+
+/// The global definitions of a trace, indexed by reference. Only those definitions which can be
+/// the target of a reference are kept.
+#[derive(Debug, Default, Clone)]
+pub struct DefinitionSet {
+    pub clock_properties: Option<ClockPropertiesDef>,
+    pub strings: BTreeMap<OTF2_StringRef, String>,
+    pub attributes: BTreeMap<OTF2_AttributeRef, AttributeDef>,
+    pub system_tree_nodes: BTreeMap<OTF2_SystemTreeNodeRef, SystemTreeNodeDef>,
+    pub location_groups: BTreeMap<OTF2_LocationGroupRef, LocationGroupDef>,
+    pub locations: BTreeMap<OTF2_LocationRef, LocationDef>,
+    pub regions: BTreeMap<OTF2_RegionRef, RegionDef>,
+    pub callsites: BTreeMap<OTF2_CallsiteRef, CallsiteDef>,
+    pub callpaths: BTreeMap<OTF2_CallpathRef, CallpathDef>,
+    pub groups: BTreeMap<OTF2_GroupRef, GroupDef>,
+    pub metric_members: BTreeMap<OTF2_MetricMemberRef, MetricMemberDef>,
+    pub metric_classes: BTreeMap<OTF2_MetricRef, MetricClassDef>,
+    pub metric_instances: BTreeMap<OTF2_MetricRef, MetricInstanceDef>,
+    pub comms: BTreeMap<OTF2_CommRef, CommDef>,
+    pub inter_comms: BTreeMap<OTF2_CommRef, InterCommDef>,
+    pub parameters: BTreeMap<OTF2_ParameterRef, ParameterDef>,
+    pub rma_wins: BTreeMap<OTF2_RmaWinRef, RmaWinDef>,
+    pub source_code_locations: BTreeMap<OTF2_SourceCodeLocationRef, SourceCodeLocationDef>,
+    pub calling_contexts: BTreeMap<OTF2_CallingContextRef, CallingContextDef>,
+    pub interrupt_generators: BTreeMap<OTF2_InterruptGeneratorRef, InterruptGeneratorDef>,
+    pub io_regular_files: BTreeMap<OTF2_IoFileRef, IoRegularFileDef>,
+    pub io_directories: BTreeMap<OTF2_IoFileRef, IoDirectoryDef>,
+    pub io_handles: BTreeMap<OTF2_IoHandleRef, IoHandleDef>,
+}
+
+/// The definition which an [`AttributeValue`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DefinitionRef<'d> {
+    String(&'d str),
+    Attribute(&'d AttributeDef),
+    Location(&'d LocationDef),
+    LocationGroup(&'d LocationGroupDef),
+    Region(&'d RegionDef),
+    Group(&'d GroupDef),
+    MetricClass(&'d MetricClassDef),
+    MetricInstance(&'d MetricInstanceDef),
+    Comm(&'d CommDef),
+    InterComm(&'d InterCommDef),
+    Parameter(&'d ParameterDef),
+    RmaWin(&'d RmaWinDef),
+    SourceCodeLocation(&'d SourceCodeLocationDef),
+    CallingContext(&'d CallingContextDef),
+    InterruptGenerator(&'d InterruptGeneratorDef),
+    IoRegularFile(&'d IoRegularFileDef),
+    IoDirectory(&'d IoDirectoryDef),
+    IoHandle(&'d IoHandleDef),
+}
+
+impl DefinitionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, definition: Definition) {
+        match definition {
+            Definition::ClockProperties { value } => { self.clock_properties = Some(value); },
+            Definition::String { defn, value } => { self.strings.insert(defn, value); },
+            Definition::Attribute { defn, value } => { self.attributes.insert(defn, value); },
+            Definition::SystemTreeNode { defn, value } => { self.system_tree_nodes.insert(defn, value); },
+            Definition::LocationGroup { defn, value } => { self.location_groups.insert(defn, value); },
+            Definition::Location { defn, value } => { self.locations.insert(defn, value); },
+            Definition::Region { defn, value } => { self.regions.insert(defn, value); },
+            Definition::Callsite { defn, value } => { self.callsites.insert(defn, value); },
+            Definition::Callpath { defn, value } => { self.callpaths.insert(defn, value); },
+            Definition::Group { defn, value } => { self.groups.insert(defn, value); },
+            Definition::MetricMember { defn, value } => { self.metric_members.insert(defn, value); },
+            Definition::MetricClass { defn, value } => { self.metric_classes.insert(defn, value); },
+            Definition::MetricInstance { defn, value } => { self.metric_instances.insert(defn, value); },
+            Definition::Comm { defn, value } => { self.comms.insert(defn, value); },
+            Definition::InterComm { defn, value } => { self.inter_comms.insert(defn, value); },
+            Definition::Parameter { defn, value } => { self.parameters.insert(defn, value); },
+            Definition::RmaWin { defn, value } => { self.rma_wins.insert(defn, value); },
+            Definition::SourceCodeLocation { defn, value } => { self.source_code_locations.insert(defn, value); },
+            Definition::CallingContext { defn, value } => { self.calling_contexts.insert(defn, value); },
+            Definition::InterruptGenerator { defn, value } => { self.interrupt_generators.insert(defn, value); },
+            Definition::IoRegularFile { defn, value } => { self.io_regular_files.insert(defn, value); },
+            Definition::IoDirectory { defn, value } => { self.io_directories.insert(defn, value); },
+            Definition::IoHandle { defn, value } => { self.io_handles.insert(defn, value); },
+            _ => {},
+        }
+    }
+
+    pub fn string(&self, string: OTF2_StringRef) -> Option<&str> {
+        self.strings.get(&string).map(String::as_str)
+    }
+
+    /// The name of `region`, or `None` if the region or its name is missing.
+    pub fn region_name(&self, region: OTF2_RegionRef) -> Option<&str> {
+        self.regions.get(&region).and_then(|def| self.string(def.name))
+    }
+
+    /// The name of `region`, falling back to e.g. "region 3" if the region or its name is missing.
+    pub fn region_label(&self, region: OTF2_RegionRef) -> String {
+        self.region_name(region).map_or_else(|| format!("region {}", region.0), str::to_string)
+    }
+
+    /// Ticks per second of the trace's clock, or `None` if it has no clock properties.
+    pub fn timer_resolution(&self) -> Option<u64> {
+        self.clock_properties.as_ref().map(|clock| clock.timer_resolution)
+    }
+
+    /// The definition referred to by `value`, or `None` if `value` isn't a reference or the
+    /// referenced definition is missing. Metric and I/O file references may refer to either of
+    /// two kinds of definition, which are tried in turn.
+    pub fn referenced(&self, value: &AttributeValue) -> Option<DefinitionRef<'_>> {
+        use AttributeValue as V;
+        use DefinitionRef as D;
+        match *value {
+            V::String(defn) => self.string(defn).map(D::String),
+            V::Attribute(defn) => self.attributes.get(&defn).map(D::Attribute),
+            V::Location(defn) => self.locations.get(&defn).map(D::Location),
+            V::LocationGroup(defn) => self.location_groups.get(&defn).map(D::LocationGroup),
+            V::Region(defn) => self.regions.get(&defn).map(D::Region),
+            V::Group(defn) => self.groups.get(&defn).map(D::Group),
+            V::Metric(defn) => self.metric_classes.get(&defn).map(D::MetricClass)
+                .or_else(|| self.metric_instances.get(&defn).map(D::MetricInstance)),
+            V::Comm(defn) => self.comms.get(&defn).map(D::Comm)
+                .or_else(|| self.inter_comms.get(&defn).map(D::InterComm)),
+            V::Parameter(defn) => self.parameters.get(&defn).map(D::Parameter),
+            V::RmaWin(defn) => self.rma_wins.get(&defn).map(D::RmaWin),
+            V::SourceCodeLocation(defn) => self.source_code_locations.get(&defn).map(D::SourceCodeLocation),
+            V::CallingContext(defn) => self.calling_contexts.get(&defn).map(D::CallingContext),
+            V::InterruptGenerator(defn) => self.interrupt_generators.get(&defn).map(D::InterruptGenerator),
+            V::IoFile(defn) => self.io_regular_files.get(&defn).map(D::IoRegularFile)
+                .or_else(|| self.io_directories.get(&defn).map(D::IoDirectory)),
+            V::IoHandle(defn) => self.io_handles.get(&defn).map(D::IoHandle),
+            _ => None,
+        }
+    }
+}
+
+impl FromIterator<Definition> for DefinitionSet {
+    fn from_iter<I: IntoIterator<Item = Definition>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|definition| set.insert(definition));
+        set
+    }
+}
+
+impl From<Vec<Definition>> for DefinitionSet {
+    fn from(definitions: Vec<Definition>) -> Self {
+        definitions.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ParameterType, Paradigm, RegionFlags, RegionRole};
+
+    #[test]
+    fn references_resolve_to_definitions() {
+        let set: DefinitionSet = vec![
+            Definition::String { defn: OTF2_StringRef(0), value: "main".to_string() },
+            Definition::Parameter { defn: OTF2_ParameterRef(2), value: ParameterDef { name: OTF2_StringRef(0), parameter_type: ParameterType::Int64 } },
+        ].into();
+        assert_eq!(set.referenced(&AttributeValue::String(OTF2_StringRef(0))), Some(DefinitionRef::String("main")));
+        assert_eq!(
+            set.referenced(&AttributeValue::Parameter(OTF2_ParameterRef(2))),
+            Some(DefinitionRef::Parameter(&ParameterDef { name: OTF2_StringRef(0), parameter_type: ParameterType::Int64 }))
+        );
+        assert_eq!(set.referenced(&AttributeValue::Region(OTF2_RegionRef(0))), None);
+        assert_eq!(set.referenced(&AttributeValue::Uint32(0)), None);
+    }
+
+    #[test]
+    fn labels_regions_and_reads_the_clock() {
+        let region = RegionDef {
+            name: OTF2_StringRef(0),
+            canonical_name: OTF2_StringRef(0),
+            description: OTF2_StringRef(0),
+            region_role: RegionRole::Function,
+            paradigm: Paradigm::User,
+            region_flags: RegionFlags::empty(),
+            source_file: OTF2_StringRef(0),
+            begin_line_number: 0,
+            end_line_number: 0,
+        };
+        let mut set: DefinitionSet = vec![
+            Definition::String { defn: OTF2_StringRef(0), value: "main".to_string() },
+            Definition::Region { defn: OTF2_RegionRef(1), value: region },
+        ].into();
+        assert_eq!(set.region_label(OTF2_RegionRef(1)), "main");
+        assert_eq!(set.region_label(OTF2_RegionRef(3)), "region 3");
+        assert_eq!(set.timer_resolution(), None);
+
+        set.clock_properties = Some(ClockPropertiesDef { timer_resolution: 1000, global_offset: 0, trace_length: 0, realtime_timestamp: 0 });
+        assert_eq!(set.timer_resolution(), Some(1000));
+    }
+}
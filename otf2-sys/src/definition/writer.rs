@@ -0,0 +1,89 @@
+use crate::archive::array_length;
+use crate::error::{Status, StatusCode};
+use crate::internal::*;
+use std::ffi::CString;
+
+use super::defs::Definition;
+
+use OTF2_ErrorCode::*;
+
+/// Write `definition` with `writer`. Optional references which are `None` are written as OTF2's
+/// undefined reference.
+pub(crate) fn write_definition(writer: &mut Handle<OTF2_GlobalDefWriter>, definition: &Definition) -> Status<()> {
+    use Definition::*;
+    let writer = writer.as_mut_ptr();
+    unsafe {
+        match definition {
+            String { defn, value } => {
+                let value = CString::new(value.as_str()).map_err(|_| StatusCode::from_raw(OTF2_ERROR_INVALID_ARGUMENT))?;
+                OTF2_GlobalDefWriter_WriteString(writer, *defn, value.as_ptr())
+            },
+            Attribute { defn, value } => OTF2_GlobalDefWriter_WriteAttribute(writer, *defn, value.name, value.description, value.kind.into()),
+            ClockProperties { value } => OTF2_GlobalDefWriter_WriteClockProperties(writer, value.timer_resolution, value.global_offset, value.trace_length, value.realtime_timestamp),
+            Paradigm { value, .. } => OTF2_GlobalDefWriter_WriteParadigm(writer, value.paradigm.into(), value.name, value.paradigm_class.into()),
+            ParadigmProperty { value, .. } => {
+                let (value_type, raw_value) = value.value.to_raw();
+                OTF2_GlobalDefWriter_WriteParadigmProperty(writer, value.paradigm.into(), value.property.into(), value_type, raw_value)
+            },
+            IoParadigm { defn, value } => {
+                if value.properties.len() != value.values.len() {
+                    return Err(StatusCode::from_raw(OTF2_ERROR_INVALID_ARGUMENT));
+                }
+                let properties: Vec<OTF2_IoParadigmProperty> = value.properties.iter().map(|&property| property.into()).collect();
+                let (types, values): (Vec<_>, Vec<_>) = value.values.iter().map(|value| value.to_raw()).unzip();
+                OTF2_GlobalDefWriter_WriteIoParadigm(writer, *defn, value.identification, value.name, value.io_paradigm_class.into(), value.io_paradigm_flags.into(), array_length(&properties)?, properties.as_ptr(), types.as_ptr(), values.as_ptr())
+            },
+            SystemTreeNode { defn, value } => OTF2_GlobalDefWriter_WriteSystemTreeNode(writer, *defn, value.name, value.class_name, value.parent.unwrap_or(OTF2_UNDEFINED_SYSTEM_TREE_NODE)),
+            SystemTreeNodeProperty { value, .. } => {
+                let (value_type, raw_value) = value.value.to_raw();
+                OTF2_GlobalDefWriter_WriteSystemTreeNodeProperty(writer, value.system_tree_node, value.name, value_type, raw_value)
+            },
+            SystemTreeNodeDomain { value, .. } => OTF2_GlobalDefWriter_WriteSystemTreeNodeDomain(writer, value.system_tree_node, value.system_tree_domain.into()),
+            Location { defn, value } => OTF2_GlobalDefWriter_WriteLocation(writer, *defn, value.name, value.location_type.into(), value.num_events, value.location_group),
+            LocationGroup { defn, value } => OTF2_GlobalDefWriter_WriteLocationGroup(writer, *defn, value.name, value.location_group_type.into(), value.system_tree_parent, value.creating_location_group.unwrap_or(OTF2_UNDEFINED_LOCATION_GROUP)),
+            LocationGroupProperty { value, .. } => {
+                let (value_type, raw_value) = value.value.to_raw();
+                OTF2_GlobalDefWriter_WriteLocationGroupProperty(writer, value.location_group, value.name, value_type, raw_value)
+            },
+            LocationProperty { value, .. } => {
+                let (value_type, raw_value) = value.value.to_raw();
+                OTF2_GlobalDefWriter_WriteLocationProperty(writer, value.location, value.name, value_type, raw_value)
+            },
+            Region { defn, value } => OTF2_GlobalDefWriter_WriteRegion(writer, *defn, value.name, value.canonical_name, value.description, value.region_role.into(), value.paradigm.into(), value.region_flags.into(), value.source_file, value.begin_line_number, value.end_line_number),
+            Callsite { defn, value } => OTF2_GlobalDefWriter_WriteCallsite(writer, *defn, value.source_file, value.line_number, value.entered_region, value.left_region),
+            Callpath { defn, value } => OTF2_GlobalDefWriter_WriteCallpath(writer, *defn, value.parent.unwrap_or(OTF2_UNDEFINED_CALLPATH), value.region),
+            CallpathParameter { value, .. } => {
+                let (value_type, raw_value) = value.value.to_raw();
+                OTF2_GlobalDefWriter_WriteCallpathParameter(writer, value.callpath, value.parameter, value_type, raw_value)
+            },
+            SourceCodeLocation { defn, value } => OTF2_GlobalDefWriter_WriteSourceCodeLocation(writer, *defn, value.file, value.line_number),
+            CallingContext { defn, value } => OTF2_GlobalDefWriter_WriteCallingContext(writer, *defn, value.region, value.source_code_location, value.parent.unwrap_or(OTF2_UNDEFINED_CALLING_CONTEXT)),
+            CallingContextProperty { value, .. } => {
+                let (value_type, raw_value) = value.value.to_raw();
+                OTF2_GlobalDefWriter_WriteCallingContextProperty(writer, value.calling_context, value.name, value_type, raw_value)
+            },
+            Group { defn, value } => OTF2_GlobalDefWriter_WriteGroup(writer, *defn, value.name, value.group_type.into(), value.paradigm.into(), value.group_flags.into(), array_length(&value.members)?, value.members.as_ptr()),
+            MetricMember { defn, value } => OTF2_GlobalDefWriter_WriteMetricMember(writer, *defn, value.name, value.description, value.metric_type.into(), value.metric_mode.into(), value.value_type.into(), value.base.into(), value.exponent, value.unit),
+            MetricClass { defn, value } => OTF2_GlobalDefWriter_WriteMetricClass(writer, *defn, array_length(&value.metric_members)?, value.metric_members.as_ptr(), value.metric_occurrence.into(), value.recorder_kind.into()),
+            MetricInstance { defn, value } => OTF2_GlobalDefWriter_WriteMetricInstance(writer, *defn, value.metric_class, value.recorder, value.metric_scope.into(), value.scope),
+            MetricClassRecorder { value, .. } => OTF2_GlobalDefWriter_WriteMetricClassRecorder(writer, value.metric_class, value.recorder),
+            Comm { defn, value } => OTF2_GlobalDefWriter_WriteComm(writer, *defn, value.name, value.group, value.parent.unwrap_or(OTF2_UNDEFINED_COMM), value.flags.into()),
+            InterComm { defn, value } => OTF2_GlobalDefWriter_WriteInterComm(writer, *defn, value.name, value.group_a, value.group_b, value.common_communicator.unwrap_or(OTF2_UNDEFINED_COMM), value.flags.into()),
+            Parameter { defn, value } => OTF2_GlobalDefWriter_WriteParameter(writer, *defn, value.name, value.parameter_type.into()),
+            RmaWin { defn, value } => OTF2_GlobalDefWriter_WriteRmaWin(writer, *defn, value.name, value.comm, value.flags.into()),
+            CartDimension { defn, value } => OTF2_GlobalDefWriter_WriteCartDimension(writer, *defn, value.name, value.size, value.periodic.into()),
+            CartTopology { defn, value } => OTF2_GlobalDefWriter_WriteCartTopology(writer, *defn, value.name, value.communicator, array_length(&value.dimensions)?, value.dimensions.as_ptr()),
+            CartCoordinate { value, .. } => OTF2_GlobalDefWriter_WriteCartCoordinate(writer, value.topology, value.rank, array_length(&value.coordinates)?, value.coordinates.as_ptr()),
+            InterruptGenerator { defn, value } => OTF2_GlobalDefWriter_WriteInterruptGenerator(writer, *defn, value.name, value.interrupt_generator_mode.into(), value.base.into(), value.exponent, value.period),
+            IoFileProperty { value, .. } => {
+                let (value_type, raw_value) = value.value.to_raw();
+                OTF2_GlobalDefWriter_WriteIoFileProperty(writer, value.io_file, value.name, value_type, raw_value)
+            },
+            IoRegularFile { defn, value } => OTF2_GlobalDefWriter_WriteIoRegularFile(writer, *defn, value.name, value.scope),
+            IoDirectory { defn, value } => OTF2_GlobalDefWriter_WriteIoDirectory(writer, *defn, value.name, value.scope),
+            IoHandle { defn, value } => OTF2_GlobalDefWriter_WriteIoHandle(writer, *defn, value.name, value.file, value.io_paradigm, value.io_handle_flags.into(), value.comm.unwrap_or(OTF2_UNDEFINED_COMM), value.parent.unwrap_or(OTF2_UNDEFINED_IO_HANDLE)),
+            IoPreCreatedHandleState { value, .. } => OTF2_GlobalDefWriter_WriteIoPreCreatedHandleState(writer, value.io_handle, value.mode.into(), value.status_flags.into()),
+        }
+    }?;
+    Ok(())
+}
@@ -41,6 +41,16 @@ impl StatusCode {
         }
     }
 
+    /// Whether a callback stopped the read, e.g. because it was cancelled with a
+    /// [`CancellationToken`](crate::progress::CancellationToken).
+    pub fn is_interrupted(&self) -> bool {
+        self.0 == c::OTF2_ErrorCode::OTF2_ERROR_INTERRUPTED_BY_CALLBACK
+    }
+
+    pub(crate) fn interrupted() -> Self {
+        StatusCode(c::OTF2_ErrorCode::OTF2_ERROR_INTERRUPTED_BY_CALLBACK)
+    }
+
     /// This is `pub(crate)` so that consumers of this crate can't create invalid values by passing
     /// OTF2_SUCCESS.
     pub(crate) fn from_raw(code: c::OTF2_ErrorCode) -> Self {
@@ -55,6 +65,8 @@ impl std::fmt::Display for StatusCode {
     }
 }
 
+impl std::error::Error for StatusCode {}
+
 impl FromResidual<StatusCode> for StatusCode {
     fn from_residual(residual: StatusCode) -> Self {
         residual
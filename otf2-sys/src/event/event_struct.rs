@@ -2,10 +2,11 @@
 
 use crate::error::Status;
 use crate::internal::*;
-use crate::attribute::AttributeValue;
+use crate::types::{CollectiveOp, IoAccessMode, IoCreationFlags, IoOperationFlags, IoOperationMode, IoSeekOption, IoStatusFlags, LockType, MeasurementMode, Paradigm, RmaAtomicType, RmaSyncLevel, RmaSyncType};
+use crate::attribute::Attributes;
 use std::ffi::CStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Event {
     pub kind: EventKind,
@@ -13,7 +14,7 @@ pub struct Event {
 }
 
 impl Event {
-    pub fn new(location: OTF2_LocationRef, time: OTF2_TimeStamp, attributes: Vec<(OTF2_AttributeRef, AttributeValue)>, kind: EventKind) -> Self {
+    pub fn new(location: OTF2_LocationRef, time: OTF2_TimeStamp, attributes: Attributes, kind: EventKind) -> Self {
         Self {
             kind,
             data: EventData {
@@ -31,52 +32,21 @@ impl std::fmt::Display for Event {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventData {
     pub location: OTF2_LocationRef,
     pub time: OTF2_TimeStamp,
-    pub attributes: Vec<(OTF2_AttributeRef, AttributeValue)>,
-}
-
-macro_rules! declare_named_enum {
-    (
-        $(#[$enum_attr:meta])*
-        $vis:vis enum $enum_name:ident {
-            $(
-                $(#[$variant_attr:meta])*
-                $variant_name:ident { $($field:ident: $ty:ty),* }
-            ),* $(,)?
-        }
-    ) => {
-        $(#[$enum_attr])*
-        $vis enum $enum_name {
-            $(
-                $(#[$variant_attr])*
-                $variant_name { $($field: $ty),* },
-            )*
-        }
-
-        impl $enum_name {
-            pub fn name(&self) -> &'static str {
-                use $enum_name::*;
-                match self {
-                    $(
-                        $variant_name { .. } => stringify!($variant_name),
-                    )*
-                }
-            }
-        }
-    };
+    pub attributes: Attributes,
 }
 
 declare_named_enum!(
-    #[derive(Debug)]
+    #[derive(Debug, Clone, PartialEq)]
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-    pub enum EventKind {
+    pub enum EventKind: EventKindId {
         Unknown{},
         BufferFlush {stop_time: OTF2_TimeStamp},
-        MeasurementOnOff {measurement_mode: OTF2_MeasurementMode},
+        MeasurementOnOff {measurement_mode: MeasurementMode},
         Enter {region: OTF2_RegionRef},
         Leave {region: OTF2_RegionRef},
         MpiSend {receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64},
@@ -88,7 +58,7 @@ declare_named_enum!(
         MpiRequestTest {request_id: u64},
         MpiRequestCancelled {request_id: u64},
         MpiCollectiveBegin{},
-        MpiCollectiveEnd {collective_op: OTF2_CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64},
+        MpiCollectiveEnd {collective_op: CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64},
         OmpFork {number_of_requested_threads: u32},
         OmpJoin{},
         OmpAcquireLock {lock_id: u32, acquisition_order: u32},
@@ -103,27 +73,27 @@ declare_named_enum!(
         RmaWinCreate {win: OTF2_RmaWinRef},
         RmaWinDestroy {win: OTF2_RmaWinRef},
         RmaCollectiveBegin{},
-        RmaCollectiveEnd {collective_op: OTF2_CollectiveOp, sync_level: OTF2_RmaSyncLevel, win: OTF2_RmaWinRef, root: u32, bytes_sent: u64, bytes_received: u64},
-        RmaGroupSync {sync_level: OTF2_RmaSyncLevel, win: OTF2_RmaWinRef, group: OTF2_GroupRef},
-        RmaRequestLock {win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType},
-        RmaAcquireLock {win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType},
-        RmaTryLock {win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType},
+        RmaCollectiveEnd {collective_op: CollectiveOp, sync_level: RmaSyncLevel, win: OTF2_RmaWinRef, root: u32, bytes_sent: u64, bytes_received: u64},
+        RmaGroupSync {sync_level: RmaSyncLevel, win: OTF2_RmaWinRef, group: OTF2_GroupRef},
+        RmaRequestLock {win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: LockType},
+        RmaAcquireLock {win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: LockType},
+        RmaTryLock {win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: LockType},
         RmaReleaseLock {win: OTF2_RmaWinRef, remote: u32, lock_id: u64},
-        RmaSync {win: OTF2_RmaWinRef, remote: u32, sync_type: OTF2_RmaSyncType},
+        RmaSync {win: OTF2_RmaWinRef, remote: u32, sync_type: RmaSyncType},
         RmaWaitChange {win: OTF2_RmaWinRef},
         RmaPut {win: OTF2_RmaWinRef, remote: u32, bytes: u64, matching_id: u64},
         RmaGet {win: OTF2_RmaWinRef, remote: u32, bytes: u64, matching_id: u64},
-        RmaAtomic {win: OTF2_RmaWinRef, remote: u32, type_: OTF2_RmaAtomicType, bytes_sent: u64, bytes_received: u64, matching_id: u64},
+        RmaAtomic {win: OTF2_RmaWinRef, remote: u32, type_: RmaAtomicType, bytes_sent: u64, bytes_received: u64, matching_id: u64},
         RmaOpCompleteBlocking {win: OTF2_RmaWinRef, matching_id: u64},
         RmaOpCompleteNonBlocking {win: OTF2_RmaWinRef, matching_id: u64},
         RmaOpTest {win: OTF2_RmaWinRef, matching_id: u64},
         RmaOpCompleteRemote {win: OTF2_RmaWinRef, matching_id: u64},
-        ThreadFork {model: OTF2_Paradigm, number_of_requested_threads: u32},
-        ThreadJoin {model: OTF2_Paradigm},
+        ThreadFork {model: Paradigm, number_of_requested_threads: u32},
+        ThreadJoin {model: Paradigm},
         ThreadTeamBegin {thread_team: OTF2_CommRef},
         ThreadTeamEnd {thread_team: OTF2_CommRef},
-        ThreadAcquireLock {model: OTF2_Paradigm, lock_id: u32, acquisition_order: u32},
-        ThreadReleaseLock {model: OTF2_Paradigm, lock_id: u32, acquisition_order: u32},
+        ThreadAcquireLock {model: Paradigm, lock_id: u32, acquisition_order: u32},
+        ThreadReleaseLock {model: Paradigm, lock_id: u32, acquisition_order: u32},
         ThreadTaskCreate {thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32},
         ThreadTaskSwitch {thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32},
         ThreadTaskComplete {thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32},
@@ -134,25 +104,68 @@ declare_named_enum!(
         CallingContextEnter {calling_context: OTF2_CallingContextRef, unwind_distance: u32},
         CallingContextLeave {calling_context: OTF2_CallingContextRef},
         CallingContextSample {calling_context: OTF2_CallingContextRef, unwind_distance: u32, interrupt_generator: OTF2_InterruptGeneratorRef},
-        IoCreateHandle {handle: OTF2_IoHandleRef, mode: OTF2_IoAccessMode, creation_flags: OTF2_IoCreationFlag, status_flags: OTF2_IoStatusFlag},
+        IoCreateHandle {handle: OTF2_IoHandleRef, mode: IoAccessMode, creation_flags: IoCreationFlags, status_flags: IoStatusFlags},
         IoDestroyHandle {handle: OTF2_IoHandleRef},
-        IoDuplicateHandle {old_handle: OTF2_IoHandleRef, new_handle: OTF2_IoHandleRef, status_flags: OTF2_IoStatusFlag},
-        IoSeek {handle: OTF2_IoHandleRef, offset_request: i64, whence: OTF2_IoSeekOption, offset_result: u64},
-        IoChangeStatusFlags {handle: OTF2_IoHandleRef, status_flags: OTF2_IoStatusFlag},
+        IoDuplicateHandle {old_handle: OTF2_IoHandleRef, new_handle: OTF2_IoHandleRef, status_flags: IoStatusFlags},
+        IoSeek {handle: OTF2_IoHandleRef, offset_request: i64, whence: IoSeekOption, offset_result: u64},
+        IoChangeStatusFlags {handle: OTF2_IoHandleRef, status_flags: IoStatusFlags},
         IoDeleteFile {io_paradigm: OTF2_IoParadigmRef, file: OTF2_IoFileRef},
-        IoOperationBegin {handle: OTF2_IoHandleRef, mode: OTF2_IoOperationMode, operation_flags: OTF2_IoOperationFlag, bytes_request: u64, matching_id: u64},
+        IoOperationBegin {handle: OTF2_IoHandleRef, mode: IoOperationMode, operation_flags: IoOperationFlags, bytes_request: u64, matching_id: u64},
         IoOperationTest {handle: OTF2_IoHandleRef, matching_id: u64},
         IoOperationIssued {handle: OTF2_IoHandleRef, matching_id: u64},
         IoOperationComplete {handle: OTF2_IoHandleRef, bytes_result: u64, matching_id: u64},
         IoOperationCancelled {handle: OTF2_IoHandleRef, matching_id: u64},
-        IoAcquireLock {handle: OTF2_IoHandleRef, lock_type: OTF2_LockType},
-        IoReleaseLock {handle: OTF2_IoHandleRef, lock_type: OTF2_LockType},
-        IoTryLock {handle: OTF2_IoHandleRef, lock_type: OTF2_LockType},
+        IoAcquireLock {handle: OTF2_IoHandleRef, lock_type: LockType},
+        IoReleaseLock {handle: OTF2_IoHandleRef, lock_type: LockType},
+        IoTryLock {handle: OTF2_IoHandleRef, lock_type: LockType},
         ProgramBegin {program_name: OTF2_StringRef, program_arguments: Vec<OTF2_StringRef>},
         ProgramEnd {exit_status: i64},
         NonBlockingCollectiveRequest {request_id: u64},
-        NonBlockingCollectiveComplete {collective_op: OTF2_CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64, request_id: u64},
+        NonBlockingCollectiveComplete {collective_op: CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64, request_id: u64},
         CommCreate {communicator: OTF2_CommRef},
         CommDestroy {communicator: OTF2_CommRef},
     }
-);
\ No newline at end of file
+);
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::attribute::AttributeValue;
+    use crate::types::{CollectiveOp, IoOperationFlags, IoOperationMode};
+
+    fn round_trip(event: &Event) -> String {
+        let json = serde_json::to_string(event).expect("failed to serialize event");
+        let back: Event = serde_json::from_str(&json).expect("failed to deserialize event");
+        assert_eq!(&back, event);
+        json
+    }
+
+    #[test]
+    fn events_round_trip_through_json() {
+        let attributes = vec![(OTF2_AttributeRef(1), AttributeValue::Uint32(4))].into();
+        let collective = Event::new(OTF2_LocationRef(3), 100, attributes, EventKind::MpiCollectiveEnd {
+            collective_op: CollectiveOp::Allreduce,
+            communicator: OTF2_CommRef(0),
+            root: u32::MAX,
+            size_sent: 8,
+            size_received: 8,
+        });
+        let json = round_trip(&collective);
+        assert!(json.contains(r#""collective_op":"Allreduce""#), "{json}");
+
+        let io = Event::new(OTF2_LocationRef(0), 5, Attributes::default(), EventKind::IoOperationBegin {
+            handle: OTF2_IoHandleRef(2),
+            mode: IoOperationMode::Write,
+            operation_flags: IoOperationFlags::NON_BLOCKING | IoOperationFlags::COLLECTIVE,
+            bytes_request: 64,
+            matching_id: 9,
+        });
+        let json = round_trip(&io);
+        assert!(json.contains(r#""operation_flags":"NON_BLOCKING | COLLECTIVE""#), "{json}");
+
+        round_trip(&Event::new(OTF2_LocationRef(1), 7, Attributes::default(), EventKind::Metric {
+            metric: OTF2_MetricRef(0),
+            values: vec![MetricValue::Uint64(3), MetricValue::Float64(0.5)],
+        }));
+    }
+}
@@ -2,15 +2,47 @@
 
 use crate::error::Status;
 use crate::internal::*;
-use crate::attribute::AttributeIterator;
+use crate::attribute::AttributeListRef;
 use std::ffi::CStr;
 
-use super::event_struct::{Event, EventKind};
+use super::event_struct::{Event, EventKind, EventKindId};
+use super::kind_set::EventKindSet;
+use super::visitor::EventVisitor;
 
 use crate::macros::{zipmap, slice_from_raw};
 
+use crate::progress::CancellationToken;
+
 use std::collections::VecDeque;
 
+/// The user data of the event callbacks.
+#[derive(Debug, Default)]
+pub struct EventQueue {
+    pub events: VecDeque<Event>,
+    /// When cancelled, callbacks interrupt the read instead of queueing more events.
+    pub cancel: Option<CancellationToken>,
+}
+
+impl EventQueue {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { events: VecDeque::with_capacity(capacity), cancel: None }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+}
+
+impl EventVisitor for EventQueue {
+    fn visit_event(&mut self, location: OTF2_LocationRef, time: OTF2_TimeStamp, attributes: AttributeListRef<'_>, kind: EventKind) -> OTF2_CallbackCode {
+        if self.is_cancelled() {
+            return OTF2_CallbackCode::OTF2_CALLBACK_INTERRUPT;
+        }
+        self.events.push_back(Event::new(location, time, attributes.to_attributes(), kind));
+        OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
+    }
+}
+
 /// Safe wrapper around OTF2_GlobalEvtReaderCallbacks
 /// 
 /// Registers callbacks for reading events in OTF2 traces.
@@ -25,457 +57,466 @@ impl core::ops::Drop for GlobalEvtReaderCallbacks {
     }
 }
 
+/// Register the callback for each kind in `$kinds`. Kinds left out of the set have no callback, so
+/// OTF2 skips those records without calling back into Rust.
 macro_rules! set_callbacks {
-    ($handle:ident, $($setter:ident => $callback:ident),* $(,)?) => {
-        $( $setter(
-            $handle.as_mut_ptr(),
-            Some($callback),
-        )?;)*
+    ($handle:ident, $kinds:ident, $($kind:ident: $setter:ident => $callback:ident),* $(,)?) => {
+        $( if $kinds.contains(EventKindId::$kind) {
+            $setter(
+                $handle.as_mut_ptr(),
+                Some($callback::<V>),
+            )?;
+        })*
     }
 }
 
 impl GlobalEvtReaderCallbacks {
     pub fn new() -> Status<Self> {
+        Self::with_kinds(EventKindSet::all())
+    }
+
+    /// Create callbacks which only decode the event kinds in `kinds`. The user data must be an
+    /// [`EventQueue`].
+    pub fn with_kinds(kinds: EventKindSet) -> Status<Self> {
+        Self::for_visitor::<EventQueue>(kinds)
+    }
+
+    /// Create callbacks which hand each event in `kinds` to a `V`. The user data must be a `V`.
+    pub fn for_visitor<V: EventVisitor>(kinds: EventKindSet) -> Status<Self> {
         let mut this = Self(Handle::from_raw(unsafe { OTF2_GlobalEvtReaderCallbacks_New() })
             .expect("Failed to create GlobalEvtReaderCallbacks: null pointer"));
-        this.set_callbacks()?;
+        this.set_callbacks::<V>(kinds)?;
         Ok(this)
     }
 
-    fn set_callbacks(&mut self) -> Status<()> {
-        use event_queue_callbacks::*;
+    fn set_callbacks<V: EventVisitor>(&mut self, kinds: EventKindSet) -> Status<()> {
+        use event_visitor_callbacks::*;
         unsafe {
-            set_callbacks!(self,
-                OTF2_GlobalEvtReaderCallbacks_SetUnknownCallback => unknown,
-                OTF2_GlobalEvtReaderCallbacks_SetBufferFlushCallback => buffer_flush,
-                OTF2_GlobalEvtReaderCallbacks_SetMeasurementOnOffCallback => measurement_on_off,
-                OTF2_GlobalEvtReaderCallbacks_SetEnterCallback => enter,
-                OTF2_GlobalEvtReaderCallbacks_SetLeaveCallback => leave,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiSendCallback => mpi_send,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiIsendCallback => mpi_isend,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiIsendCompleteCallback => mpi_isend_complete,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiIrecvRequestCallback => mpi_irecv_request,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiRecvCallback => mpi_recv,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiIrecvCallback => mpi_irecv,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiRequestTestCallback => mpi_request_test,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiRequestCancelledCallback => mpi_request_cancelled,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiCollectiveBeginCallback => mpi_collective_begin,
-                OTF2_GlobalEvtReaderCallbacks_SetMpiCollectiveEndCallback => mpi_collective_end,
-                OTF2_GlobalEvtReaderCallbacks_SetOmpForkCallback => omp_fork,
-                OTF2_GlobalEvtReaderCallbacks_SetOmpJoinCallback => omp_join,
-                OTF2_GlobalEvtReaderCallbacks_SetOmpAcquireLockCallback => omp_acquire_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetOmpReleaseLockCallback => omp_release_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetOmpTaskCreateCallback => omp_task_create,
-                OTF2_GlobalEvtReaderCallbacks_SetOmpTaskSwitchCallback => omp_task_switch,
-                OTF2_GlobalEvtReaderCallbacks_SetOmpTaskCompleteCallback => omp_task_complete,
-                OTF2_GlobalEvtReaderCallbacks_SetMetricCallback => metric,
-                OTF2_GlobalEvtReaderCallbacks_SetParameterStringCallback => parameter_string,
-                OTF2_GlobalEvtReaderCallbacks_SetParameterIntCallback => parameter_int,
-                OTF2_GlobalEvtReaderCallbacks_SetParameterUnsignedIntCallback => parameter_unsigned_int,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaWinCreateCallback => rma_win_create,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaWinDestroyCallback => rma_win_destroy,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaCollectiveBeginCallback => rma_collective_begin,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaCollectiveEndCallback => rma_collective_end,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaGroupSyncCallback => rma_group_sync,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaRequestLockCallback => rma_request_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaAcquireLockCallback => rma_acquire_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaTryLockCallback => rma_try_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaReleaseLockCallback => rma_release_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaSyncCallback => rma_sync,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaWaitChangeCallback => rma_wait_change,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaPutCallback => rma_put,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaGetCallback => rma_get,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaAtomicCallback => rma_atomic,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaOpCompleteBlockingCallback => rma_op_complete_blocking,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaOpCompleteNonBlockingCallback => rma_op_complete_non_blocking,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaOpTestCallback => rma_op_test,
-                OTF2_GlobalEvtReaderCallbacks_SetRmaOpCompleteRemoteCallback => rma_op_complete_remote,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadForkCallback => thread_fork,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadJoinCallback => thread_join,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadTeamBeginCallback => thread_team_begin,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadTeamEndCallback => thread_team_end,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadAcquireLockCallback => thread_acquire_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadReleaseLockCallback => thread_release_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadTaskCreateCallback => thread_task_create,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadTaskSwitchCallback => thread_task_switch,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadTaskCompleteCallback => thread_task_complete,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadCreateCallback => thread_create,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadBeginCallback => thread_begin,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadWaitCallback => thread_wait,
-                OTF2_GlobalEvtReaderCallbacks_SetThreadEndCallback => thread_end,
-                OTF2_GlobalEvtReaderCallbacks_SetCallingContextEnterCallback => calling_context_enter,
-                OTF2_GlobalEvtReaderCallbacks_SetCallingContextLeaveCallback => calling_context_leave,
-                OTF2_GlobalEvtReaderCallbacks_SetCallingContextSampleCallback => calling_context_sample,
-                OTF2_GlobalEvtReaderCallbacks_SetIoCreateHandleCallback => io_create_handle,
-                OTF2_GlobalEvtReaderCallbacks_SetIoDestroyHandleCallback => io_destroy_handle,
-                OTF2_GlobalEvtReaderCallbacks_SetIoDuplicateHandleCallback => io_duplicate_handle,
-                OTF2_GlobalEvtReaderCallbacks_SetIoSeekCallback => io_seek,
-                OTF2_GlobalEvtReaderCallbacks_SetIoChangeStatusFlagsCallback => io_change_status_flags,
-                OTF2_GlobalEvtReaderCallbacks_SetIoDeleteFileCallback => io_delete_file,
-                OTF2_GlobalEvtReaderCallbacks_SetIoOperationBeginCallback => io_operation_begin,
-                OTF2_GlobalEvtReaderCallbacks_SetIoOperationTestCallback => io_operation_test,
-                OTF2_GlobalEvtReaderCallbacks_SetIoOperationIssuedCallback => io_operation_issued,
-                OTF2_GlobalEvtReaderCallbacks_SetIoOperationCompleteCallback => io_operation_complete,
-                OTF2_GlobalEvtReaderCallbacks_SetIoOperationCancelledCallback => io_operation_cancelled,
-                OTF2_GlobalEvtReaderCallbacks_SetIoAcquireLockCallback => io_acquire_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetIoReleaseLockCallback => io_release_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetIoTryLockCallback => io_try_lock,
-                OTF2_GlobalEvtReaderCallbacks_SetProgramBeginCallback => program_begin,
-                OTF2_GlobalEvtReaderCallbacks_SetProgramEndCallback => program_end,
-                OTF2_GlobalEvtReaderCallbacks_SetNonBlockingCollectiveRequestCallback => non_blocking_collective_request,
-                OTF2_GlobalEvtReaderCallbacks_SetNonBlockingCollectiveCompleteCallback => non_blocking_collective_complete,
-                OTF2_GlobalEvtReaderCallbacks_SetCommCreateCallback => comm_create,
-                OTF2_GlobalEvtReaderCallbacks_SetCommDestroyCallback => comm_destroy,
+            set_callbacks!(self, kinds,
+                Unknown: OTF2_GlobalEvtReaderCallbacks_SetUnknownCallback => unknown,
+                BufferFlush: OTF2_GlobalEvtReaderCallbacks_SetBufferFlushCallback => buffer_flush,
+                MeasurementOnOff: OTF2_GlobalEvtReaderCallbacks_SetMeasurementOnOffCallback => measurement_on_off,
+                Enter: OTF2_GlobalEvtReaderCallbacks_SetEnterCallback => enter,
+                Leave: OTF2_GlobalEvtReaderCallbacks_SetLeaveCallback => leave,
+                MpiSend: OTF2_GlobalEvtReaderCallbacks_SetMpiSendCallback => mpi_send,
+                MpiIsend: OTF2_GlobalEvtReaderCallbacks_SetMpiIsendCallback => mpi_isend,
+                MpiIsendComplete: OTF2_GlobalEvtReaderCallbacks_SetMpiIsendCompleteCallback => mpi_isend_complete,
+                MpiIrecvRequest: OTF2_GlobalEvtReaderCallbacks_SetMpiIrecvRequestCallback => mpi_irecv_request,
+                MpiRecv: OTF2_GlobalEvtReaderCallbacks_SetMpiRecvCallback => mpi_recv,
+                MpiIrecv: OTF2_GlobalEvtReaderCallbacks_SetMpiIrecvCallback => mpi_irecv,
+                MpiRequestTest: OTF2_GlobalEvtReaderCallbacks_SetMpiRequestTestCallback => mpi_request_test,
+                MpiRequestCancelled: OTF2_GlobalEvtReaderCallbacks_SetMpiRequestCancelledCallback => mpi_request_cancelled,
+                MpiCollectiveBegin: OTF2_GlobalEvtReaderCallbacks_SetMpiCollectiveBeginCallback => mpi_collective_begin,
+                MpiCollectiveEnd: OTF2_GlobalEvtReaderCallbacks_SetMpiCollectiveEndCallback => mpi_collective_end,
+                OmpFork: OTF2_GlobalEvtReaderCallbacks_SetOmpForkCallback => omp_fork,
+                OmpJoin: OTF2_GlobalEvtReaderCallbacks_SetOmpJoinCallback => omp_join,
+                OmpAcquireLock: OTF2_GlobalEvtReaderCallbacks_SetOmpAcquireLockCallback => omp_acquire_lock,
+                OmpReleaseLock: OTF2_GlobalEvtReaderCallbacks_SetOmpReleaseLockCallback => omp_release_lock,
+                OmpTaskCreate: OTF2_GlobalEvtReaderCallbacks_SetOmpTaskCreateCallback => omp_task_create,
+                OmpTaskSwitch: OTF2_GlobalEvtReaderCallbacks_SetOmpTaskSwitchCallback => omp_task_switch,
+                OmpTaskComplete: OTF2_GlobalEvtReaderCallbacks_SetOmpTaskCompleteCallback => omp_task_complete,
+                Metric: OTF2_GlobalEvtReaderCallbacks_SetMetricCallback => metric,
+                ParameterString: OTF2_GlobalEvtReaderCallbacks_SetParameterStringCallback => parameter_string,
+                ParameterInt: OTF2_GlobalEvtReaderCallbacks_SetParameterIntCallback => parameter_int,
+                ParameterUnsignedInt: OTF2_GlobalEvtReaderCallbacks_SetParameterUnsignedIntCallback => parameter_unsigned_int,
+                RmaWinCreate: OTF2_GlobalEvtReaderCallbacks_SetRmaWinCreateCallback => rma_win_create,
+                RmaWinDestroy: OTF2_GlobalEvtReaderCallbacks_SetRmaWinDestroyCallback => rma_win_destroy,
+                RmaCollectiveBegin: OTF2_GlobalEvtReaderCallbacks_SetRmaCollectiveBeginCallback => rma_collective_begin,
+                RmaCollectiveEnd: OTF2_GlobalEvtReaderCallbacks_SetRmaCollectiveEndCallback => rma_collective_end,
+                RmaGroupSync: OTF2_GlobalEvtReaderCallbacks_SetRmaGroupSyncCallback => rma_group_sync,
+                RmaRequestLock: OTF2_GlobalEvtReaderCallbacks_SetRmaRequestLockCallback => rma_request_lock,
+                RmaAcquireLock: OTF2_GlobalEvtReaderCallbacks_SetRmaAcquireLockCallback => rma_acquire_lock,
+                RmaTryLock: OTF2_GlobalEvtReaderCallbacks_SetRmaTryLockCallback => rma_try_lock,
+                RmaReleaseLock: OTF2_GlobalEvtReaderCallbacks_SetRmaReleaseLockCallback => rma_release_lock,
+                RmaSync: OTF2_GlobalEvtReaderCallbacks_SetRmaSyncCallback => rma_sync,
+                RmaWaitChange: OTF2_GlobalEvtReaderCallbacks_SetRmaWaitChangeCallback => rma_wait_change,
+                RmaPut: OTF2_GlobalEvtReaderCallbacks_SetRmaPutCallback => rma_put,
+                RmaGet: OTF2_GlobalEvtReaderCallbacks_SetRmaGetCallback => rma_get,
+                RmaAtomic: OTF2_GlobalEvtReaderCallbacks_SetRmaAtomicCallback => rma_atomic,
+                RmaOpCompleteBlocking: OTF2_GlobalEvtReaderCallbacks_SetRmaOpCompleteBlockingCallback => rma_op_complete_blocking,
+                RmaOpCompleteNonBlocking: OTF2_GlobalEvtReaderCallbacks_SetRmaOpCompleteNonBlockingCallback => rma_op_complete_non_blocking,
+                RmaOpTest: OTF2_GlobalEvtReaderCallbacks_SetRmaOpTestCallback => rma_op_test,
+                RmaOpCompleteRemote: OTF2_GlobalEvtReaderCallbacks_SetRmaOpCompleteRemoteCallback => rma_op_complete_remote,
+                ThreadFork: OTF2_GlobalEvtReaderCallbacks_SetThreadForkCallback => thread_fork,
+                ThreadJoin: OTF2_GlobalEvtReaderCallbacks_SetThreadJoinCallback => thread_join,
+                ThreadTeamBegin: OTF2_GlobalEvtReaderCallbacks_SetThreadTeamBeginCallback => thread_team_begin,
+                ThreadTeamEnd: OTF2_GlobalEvtReaderCallbacks_SetThreadTeamEndCallback => thread_team_end,
+                ThreadAcquireLock: OTF2_GlobalEvtReaderCallbacks_SetThreadAcquireLockCallback => thread_acquire_lock,
+                ThreadReleaseLock: OTF2_GlobalEvtReaderCallbacks_SetThreadReleaseLockCallback => thread_release_lock,
+                ThreadTaskCreate: OTF2_GlobalEvtReaderCallbacks_SetThreadTaskCreateCallback => thread_task_create,
+                ThreadTaskSwitch: OTF2_GlobalEvtReaderCallbacks_SetThreadTaskSwitchCallback => thread_task_switch,
+                ThreadTaskComplete: OTF2_GlobalEvtReaderCallbacks_SetThreadTaskCompleteCallback => thread_task_complete,
+                ThreadCreate: OTF2_GlobalEvtReaderCallbacks_SetThreadCreateCallback => thread_create,
+                ThreadBegin: OTF2_GlobalEvtReaderCallbacks_SetThreadBeginCallback => thread_begin,
+                ThreadWait: OTF2_GlobalEvtReaderCallbacks_SetThreadWaitCallback => thread_wait,
+                ThreadEnd: OTF2_GlobalEvtReaderCallbacks_SetThreadEndCallback => thread_end,
+                CallingContextEnter: OTF2_GlobalEvtReaderCallbacks_SetCallingContextEnterCallback => calling_context_enter,
+                CallingContextLeave: OTF2_GlobalEvtReaderCallbacks_SetCallingContextLeaveCallback => calling_context_leave,
+                CallingContextSample: OTF2_GlobalEvtReaderCallbacks_SetCallingContextSampleCallback => calling_context_sample,
+                IoCreateHandle: OTF2_GlobalEvtReaderCallbacks_SetIoCreateHandleCallback => io_create_handle,
+                IoDestroyHandle: OTF2_GlobalEvtReaderCallbacks_SetIoDestroyHandleCallback => io_destroy_handle,
+                IoDuplicateHandle: OTF2_GlobalEvtReaderCallbacks_SetIoDuplicateHandleCallback => io_duplicate_handle,
+                IoSeek: OTF2_GlobalEvtReaderCallbacks_SetIoSeekCallback => io_seek,
+                IoChangeStatusFlags: OTF2_GlobalEvtReaderCallbacks_SetIoChangeStatusFlagsCallback => io_change_status_flags,
+                IoDeleteFile: OTF2_GlobalEvtReaderCallbacks_SetIoDeleteFileCallback => io_delete_file,
+                IoOperationBegin: OTF2_GlobalEvtReaderCallbacks_SetIoOperationBeginCallback => io_operation_begin,
+                IoOperationTest: OTF2_GlobalEvtReaderCallbacks_SetIoOperationTestCallback => io_operation_test,
+                IoOperationIssued: OTF2_GlobalEvtReaderCallbacks_SetIoOperationIssuedCallback => io_operation_issued,
+                IoOperationComplete: OTF2_GlobalEvtReaderCallbacks_SetIoOperationCompleteCallback => io_operation_complete,
+                IoOperationCancelled: OTF2_GlobalEvtReaderCallbacks_SetIoOperationCancelledCallback => io_operation_cancelled,
+                IoAcquireLock: OTF2_GlobalEvtReaderCallbacks_SetIoAcquireLockCallback => io_acquire_lock,
+                IoReleaseLock: OTF2_GlobalEvtReaderCallbacks_SetIoReleaseLockCallback => io_release_lock,
+                IoTryLock: OTF2_GlobalEvtReaderCallbacks_SetIoTryLockCallback => io_try_lock,
+                ProgramBegin: OTF2_GlobalEvtReaderCallbacks_SetProgramBeginCallback => program_begin,
+                ProgramEnd: OTF2_GlobalEvtReaderCallbacks_SetProgramEndCallback => program_end,
+                NonBlockingCollectiveRequest: OTF2_GlobalEvtReaderCallbacks_SetNonBlockingCollectiveRequestCallback => non_blocking_collective_request,
+                NonBlockingCollectiveComplete: OTF2_GlobalEvtReaderCallbacks_SetNonBlockingCollectiveCompleteCallback => non_blocking_collective_complete,
+                CommCreate: OTF2_GlobalEvtReaderCallbacks_SetCommCreateCallback => comm_create,
+                CommDestroy: OTF2_GlobalEvtReaderCallbacks_SetCommDestroyCallback => comm_destroy,
             );
         }
         Ok(())
     }
 }
 
-mod event_queue_callbacks {
+mod event_visitor_callbacks {
     use super::*;
     use std::os::raw::c_void;
 
     #[inline]
-    fn as_event_queue<'a>(data: *mut c_void) -> &'a mut VecDeque<Event> {
+    fn as_visitor<'a, V: EventVisitor>(data: *mut c_void) -> &'a mut V {
         assert!(!data.is_null(), "callback user data must not be null pointer");
         unsafe { &mut *(data as *mut _) }
     }
 
-    macro_rules! into_attributes {
-        ($list:ident) => {
-            Handle::from_raw_unchecked($list).into_iter().collect::<Vec<_>>()
+    macro_rules! visit_event {
+        ($visitor:ident, $location:ident, $time:ident, $attr:ident, $kind:expr) => {
+            // SAFETY: OTF2 keeps the attribute list alive until the callback returns
+            as_visitor::<V>($visitor).visit_event($location, $time, unsafe { AttributeListRef::from_raw($attr) }, $kind)
         }
     }
 
-    macro_rules! push_event {
-        ($queue:ident, $location:ident, $time:ident, $attr:ident, $kind:expr) => {{
-            as_event_queue($queue).push_back(Event::new($location, $time, into_attributes!($attr), $kind));
-            OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
-        }}
-    }
-
-    pub unsafe extern "C" fn unknown(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::Unknown{})
+    pub unsafe extern "C" fn unknown<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::Unknown{})
     }
 
-    pub unsafe extern "C" fn buffer_flush(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, stop_time: OTF2_TimeStamp ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::BufferFlush { stop_time })
+    pub unsafe extern "C" fn buffer_flush<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, stop_time: OTF2_TimeStamp ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::BufferFlush { stop_time })
     }
 
-    pub unsafe extern "C" fn measurement_on_off(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, measurement_mode: OTF2_MeasurementMode ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MeasurementOnOff { measurement_mode })
+    pub unsafe extern "C" fn measurement_on_off<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, measurement_mode: OTF2_MeasurementMode ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MeasurementOnOff { measurement_mode: measurement_mode.into() })
     }
 
-    pub unsafe extern "C" fn enter(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, region: OTF2_RegionRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::Enter { region })
+    pub unsafe extern "C" fn enter<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, region: OTF2_RegionRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::Enter { region })
     }
 
-    pub unsafe extern "C" fn leave(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, region: OTF2_RegionRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::Leave { region })
+    pub unsafe extern "C" fn leave<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, region: OTF2_RegionRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::Leave { region })
     }
 
-    pub unsafe extern "C" fn mpi_send(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiSend { receiver, communicator, msg_tag, msg_length })
+    pub unsafe extern "C" fn mpi_send<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiSend { receiver, communicator, msg_tag, msg_length })
     }
 
-    pub unsafe extern "C" fn mpi_isend(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64, request_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiIsend { receiver, communicator, msg_tag, msg_length, request_id })
+    pub unsafe extern "C" fn mpi_isend<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64, request_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiIsend { receiver, communicator, msg_tag, msg_length, request_id })
     }
 
-    pub unsafe extern "C" fn mpi_isend_complete(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiIsendComplete { request_id })
+    pub unsafe extern "C" fn mpi_isend_complete<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiIsendComplete { request_id })
     }
 
-    pub unsafe extern "C" fn mpi_irecv_request(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiIrecvRequest { request_id })
+    pub unsafe extern "C" fn mpi_irecv_request<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiIrecvRequest { request_id })
     }
 
-    pub unsafe extern "C" fn mpi_recv(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, sender: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiRecv { sender, communicator, msg_tag, msg_length })
+    pub unsafe extern "C" fn mpi_recv<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, sender: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiRecv { sender, communicator, msg_tag, msg_length })
     }
 
-    pub unsafe extern "C" fn mpi_irecv(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, sender: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64, request_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiIrecv { sender, communicator, msg_tag, msg_length, request_id })
+    pub unsafe extern "C" fn mpi_irecv<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, sender: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64, request_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiIrecv { sender, communicator, msg_tag, msg_length, request_id })
     }
 
-    pub unsafe extern "C" fn mpi_request_test(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiRequestTest { request_id })
+    pub unsafe extern "C" fn mpi_request_test<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiRequestTest { request_id })
     }
 
-    pub unsafe extern "C" fn mpi_request_cancelled(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiRequestCancelled { request_id })
+    pub unsafe extern "C" fn mpi_request_cancelled<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiRequestCancelled { request_id })
     }
 
-    pub unsafe extern "C" fn mpi_collective_begin(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiCollectiveBegin{})
+    pub unsafe extern "C" fn mpi_collective_begin<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiCollectiveBegin{})
     }
 
-    pub unsafe extern "C" fn mpi_collective_end(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, collective_op: OTF2_CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::MpiCollectiveEnd { collective_op, communicator, root, size_sent, size_received })
+    pub unsafe extern "C" fn mpi_collective_end<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, collective_op: OTF2_CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::MpiCollectiveEnd { collective_op: collective_op.into(), communicator, root, size_sent, size_received })
     }
 
-    pub unsafe extern "C" fn omp_fork(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, number_of_requested_threads: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::OmpFork { number_of_requested_threads })
+    pub unsafe extern "C" fn omp_fork<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, number_of_requested_threads: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::OmpFork { number_of_requested_threads })
     }
 
-    pub unsafe extern "C" fn omp_join(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::OmpJoin{})
+    pub unsafe extern "C" fn omp_join<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::OmpJoin{})
     }
 
-    pub unsafe extern "C" fn omp_acquire_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::OmpAcquireLock { lock_id, acquisition_order })
+    pub unsafe extern "C" fn omp_acquire_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::OmpAcquireLock { lock_id, acquisition_order })
     }
 
-    pub unsafe extern "C" fn omp_release_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::OmpReleaseLock { lock_id, acquisition_order })
+    pub unsafe extern "C" fn omp_release_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::OmpReleaseLock { lock_id, acquisition_order })
     }
 
-    pub unsafe extern "C" fn omp_task_create(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, task_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::OmpTaskCreate { task_id })
+    pub unsafe extern "C" fn omp_task_create<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, task_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::OmpTaskCreate { task_id })
     }
 
-    pub unsafe extern "C" fn omp_task_switch(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, task_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::OmpTaskSwitch { task_id })
+    pub unsafe extern "C" fn omp_task_switch<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, task_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::OmpTaskSwitch { task_id })
     }
 
-    pub unsafe extern "C" fn omp_task_complete(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, task_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::OmpTaskComplete { task_id })
+    pub unsafe extern "C" fn omp_task_complete<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, task_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::OmpTaskComplete { task_id })
     }
 
-    pub unsafe extern "C" fn metric(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, metric: OTF2_MetricRef, number_of_metrics: u8, type_ids: *const OTF2_Type, metric_values: *const OTF2_MetricValue ) -> OTF2_CallbackCode {
+    pub unsafe extern "C" fn metric<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, metric: OTF2_MetricRef, number_of_metrics: u8, type_ids: *const OTF2_Type, metric_values: *const OTF2_MetricValue ) -> OTF2_CallbackCode {
         let types = unsafe { slice_from_raw!(type_ids, number_of_metrics) }.to_vec();
         let raw_values = unsafe { slice_from_raw!(metric_values, number_of_metrics) }.to_vec();
         let values = types.into_iter().zip(raw_values).map(|(t, v)| MetricValue::new(t, v)).collect();
-        push_event!(queue, location, time, attributes, EventKind::Metric { metric, values })
+        visit_event!(visitor, location, time, attributes, EventKind::Metric { metric, values })
     }
 
-    pub unsafe extern "C" fn parameter_string(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, parameter: OTF2_ParameterRef, string: OTF2_StringRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ParameterString { parameter, string })
+    pub unsafe extern "C" fn parameter_string<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, parameter: OTF2_ParameterRef, string: OTF2_StringRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ParameterString { parameter, string })
     }
 
-    pub unsafe extern "C" fn parameter_int(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, parameter: OTF2_ParameterRef, value: i64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ParameterInt { parameter, value })
+    pub unsafe extern "C" fn parameter_int<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, parameter: OTF2_ParameterRef, value: i64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ParameterInt { parameter, value })
     }
 
-    pub unsafe extern "C" fn parameter_unsigned_int(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, parameter: OTF2_ParameterRef, value: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ParameterUnsignedInt { parameter, value })
+    pub unsafe extern "C" fn parameter_unsigned_int<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, parameter: OTF2_ParameterRef, value: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ParameterUnsignedInt { parameter, value })
     }
 
-    pub unsafe extern "C" fn rma_win_create(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaWinCreate { win })
+    pub unsafe extern "C" fn rma_win_create<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaWinCreate { win })
     }
 
-    pub unsafe extern "C" fn rma_win_destroy(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaWinDestroy { win })
+    pub unsafe extern "C" fn rma_win_destroy<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaWinDestroy { win })
     }
 
-    pub unsafe extern "C" fn rma_collective_begin(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaCollectiveBegin{})
+    pub unsafe extern "C" fn rma_collective_begin<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaCollectiveBegin{})
     }
 
-    pub unsafe extern "C" fn rma_collective_end(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, collective_op: OTF2_CollectiveOp, sync_level: OTF2_RmaSyncLevel, win: OTF2_RmaWinRef, root: u32, bytes_sent: u64, bytes_received: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaCollectiveEnd { collective_op, sync_level, win, root, bytes_sent, bytes_received })
+    pub unsafe extern "C" fn rma_collective_end<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, collective_op: OTF2_CollectiveOp, sync_level: OTF2_RmaSyncLevel, win: OTF2_RmaWinRef, root: u32, bytes_sent: u64, bytes_received: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaCollectiveEnd { collective_op: collective_op.into(), sync_level: sync_level.into(), win, root, bytes_sent, bytes_received })
     }
 
-    pub unsafe extern "C" fn rma_group_sync(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, sync_level: OTF2_RmaSyncLevel, win: OTF2_RmaWinRef, group: OTF2_GroupRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaGroupSync { sync_level, win, group })
+    pub unsafe extern "C" fn rma_group_sync<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, sync_level: OTF2_RmaSyncLevel, win: OTF2_RmaWinRef, group: OTF2_GroupRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaGroupSync { sync_level: sync_level.into(), win, group })
     }
 
-    pub unsafe extern "C" fn rma_request_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaRequestLock { win, remote, lock_id, lock_type })
+    pub unsafe extern "C" fn rma_request_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaRequestLock { win, remote, lock_id, lock_type: lock_type.into() })
     }
 
-    pub unsafe extern "C" fn rma_acquire_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaAcquireLock { win, remote, lock_id, lock_type })
+    pub unsafe extern "C" fn rma_acquire_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaAcquireLock { win, remote, lock_id, lock_type: lock_type.into() })
     }
 
-    pub unsafe extern "C" fn rma_try_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaTryLock { win, remote, lock_id, lock_type })
+    pub unsafe extern "C" fn rma_try_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, lock_id: u64, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaTryLock { win, remote, lock_id, lock_type: lock_type.into() })
     }
 
-    pub unsafe extern "C" fn rma_release_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, lock_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaReleaseLock { win, remote, lock_id })
+    pub unsafe extern "C" fn rma_release_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, lock_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaReleaseLock { win, remote, lock_id })
     }
 
-    pub unsafe extern "C" fn rma_sync(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, sync_type: OTF2_RmaSyncType ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaSync { win, remote, sync_type })
+    pub unsafe extern "C" fn rma_sync<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, sync_type: OTF2_RmaSyncType ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaSync { win, remote, sync_type: sync_type.into() })
     }
 
-    pub unsafe extern "C" fn rma_wait_change(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaWaitChange { win })
+    pub unsafe extern "C" fn rma_wait_change<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaWaitChange { win })
     }
 
-    pub unsafe extern "C" fn rma_put(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, bytes: u64, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaPut { win, remote, bytes, matching_id })
+    pub unsafe extern "C" fn rma_put<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, bytes: u64, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaPut { win, remote, bytes, matching_id })
     }
 
-    pub unsafe extern "C" fn rma_get(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, bytes: u64, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaGet { win, remote, bytes, matching_id })
+    pub unsafe extern "C" fn rma_get<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, bytes: u64, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaGet { win, remote, bytes, matching_id })
     }
 
-    pub unsafe extern "C" fn rma_atomic(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, type_: OTF2_RmaAtomicType, bytes_sent: u64, bytes_received: u64, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaAtomic { win, remote, type_, bytes_sent, bytes_received, matching_id })
+    pub unsafe extern "C" fn rma_atomic<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, remote: u32, type_: OTF2_RmaAtomicType, bytes_sent: u64, bytes_received: u64, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaAtomic { win, remote, type_: type_.into(), bytes_sent, bytes_received, matching_id })
     }
 
-    pub unsafe extern "C" fn rma_op_complete_blocking(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaOpCompleteBlocking { win, matching_id })
+    pub unsafe extern "C" fn rma_op_complete_blocking<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaOpCompleteBlocking { win, matching_id })
     }
 
-    pub unsafe extern "C" fn rma_op_complete_non_blocking(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaOpCompleteNonBlocking { win, matching_id })
+    pub unsafe extern "C" fn rma_op_complete_non_blocking<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaOpCompleteNonBlocking { win, matching_id })
     }
 
-    pub unsafe extern "C" fn rma_op_test(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaOpTest { win, matching_id })
+    pub unsafe extern "C" fn rma_op_test<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaOpTest { win, matching_id })
     }
 
-    pub unsafe extern "C" fn rma_op_complete_remote(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::RmaOpCompleteRemote { win, matching_id })
+    pub unsafe extern "C" fn rma_op_complete_remote<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, win: OTF2_RmaWinRef, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::RmaOpCompleteRemote { win, matching_id })
     }
 
-    pub unsafe extern "C" fn thread_fork(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, model: OTF2_Paradigm, number_of_requested_threads: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadFork { model, number_of_requested_threads })
+    pub unsafe extern "C" fn thread_fork<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, model: OTF2_Paradigm, number_of_requested_threads: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadFork { model: model.into(), number_of_requested_threads })
     }
 
-    pub unsafe extern "C" fn thread_join(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, model: OTF2_Paradigm ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadJoin { model })
+    pub unsafe extern "C" fn thread_join<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, model: OTF2_Paradigm ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadJoin { model: model.into() })
     }
 
-    pub unsafe extern "C" fn thread_team_begin(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadTeamBegin { thread_team })
+    pub unsafe extern "C" fn thread_team_begin<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadTeamBegin { thread_team })
     }
 
-    pub unsafe extern "C" fn thread_team_end(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadTeamEnd { thread_team })
+    pub unsafe extern "C" fn thread_team_end<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadTeamEnd { thread_team })
     }
 
-    pub unsafe extern "C" fn thread_acquire_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, model: OTF2_Paradigm, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadAcquireLock { model, lock_id, acquisition_order })
+    pub unsafe extern "C" fn thread_acquire_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, model: OTF2_Paradigm, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadAcquireLock { model: model.into(), lock_id, acquisition_order })
     }
 
-    pub unsafe extern "C" fn thread_release_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, model: OTF2_Paradigm, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadReleaseLock { model, lock_id, acquisition_order })
+    pub unsafe extern "C" fn thread_release_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, model: OTF2_Paradigm, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadReleaseLock { model: model.into(), lock_id, acquisition_order })
     }
 
-    pub unsafe extern "C" fn thread_task_create(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadTaskCreate { thread_team, creating_thread, generation_number })
+    pub unsafe extern "C" fn thread_task_create<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadTaskCreate { thread_team, creating_thread, generation_number })
     }
 
-    pub unsafe extern "C" fn thread_task_switch(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadTaskSwitch { thread_team, creating_thread, generation_number })
+    pub unsafe extern "C" fn thread_task_switch<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadTaskSwitch { thread_team, creating_thread, generation_number })
     }
 
-    pub unsafe extern "C" fn thread_task_complete(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadTaskComplete { thread_team, creating_thread, generation_number })
+    pub unsafe extern "C" fn thread_task_complete<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_team: OTF2_CommRef, creating_thread: u32, generation_number: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadTaskComplete { thread_team, creating_thread, generation_number })
     }
 
-    pub unsafe extern "C" fn thread_create(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_contingent: OTF2_CommRef, sequence_count: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadCreate { thread_contingent, sequence_count })
+    pub unsafe extern "C" fn thread_create<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_contingent: OTF2_CommRef, sequence_count: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadCreate { thread_contingent, sequence_count })
     }
 
-    pub unsafe extern "C" fn thread_begin(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_contingent: OTF2_CommRef, sequence_count: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadBegin { thread_contingent, sequence_count })
+    pub unsafe extern "C" fn thread_begin<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_contingent: OTF2_CommRef, sequence_count: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadBegin { thread_contingent, sequence_count })
     }
 
-    pub unsafe extern "C" fn thread_wait(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_contingent: OTF2_CommRef, sequence_count: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadWait { thread_contingent, sequence_count })
+    pub unsafe extern "C" fn thread_wait<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_contingent: OTF2_CommRef, sequence_count: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadWait { thread_contingent, sequence_count })
     }
 
-    pub unsafe extern "C" fn thread_end(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, thread_contingent: OTF2_CommRef, sequence_count: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ThreadEnd { thread_contingent, sequence_count })
+    pub unsafe extern "C" fn thread_end<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, thread_contingent: OTF2_CommRef, sequence_count: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ThreadEnd { thread_contingent, sequence_count })
     }
 
-    pub unsafe extern "C" fn calling_context_enter(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, calling_context: OTF2_CallingContextRef, unwind_distance: u32 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::CallingContextEnter { calling_context, unwind_distance })
+    pub unsafe extern "C" fn calling_context_enter<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, calling_context: OTF2_CallingContextRef, unwind_distance: u32 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::CallingContextEnter { calling_context, unwind_distance })
     }
 
-    pub unsafe extern "C" fn calling_context_leave(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, calling_context: OTF2_CallingContextRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::CallingContextLeave { calling_context })
+    pub unsafe extern "C" fn calling_context_leave<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, calling_context: OTF2_CallingContextRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::CallingContextLeave { calling_context })
     }
 
-    pub unsafe extern "C" fn calling_context_sample(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, calling_context: OTF2_CallingContextRef, unwind_distance: u32, interrupt_generator: OTF2_InterruptGeneratorRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::CallingContextSample { calling_context, unwind_distance, interrupt_generator })
+    pub unsafe extern "C" fn calling_context_sample<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, calling_context: OTF2_CallingContextRef, unwind_distance: u32, interrupt_generator: OTF2_InterruptGeneratorRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::CallingContextSample { calling_context, unwind_distance, interrupt_generator })
     }
 
-    pub unsafe extern "C" fn io_create_handle(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, mode: OTF2_IoAccessMode, creation_flags: OTF2_IoCreationFlag, status_flags: OTF2_IoStatusFlag ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoCreateHandle { handle, mode, creation_flags, status_flags })
+    pub unsafe extern "C" fn io_create_handle<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, mode: OTF2_IoAccessMode, creation_flags: OTF2_IoCreationFlag, status_flags: OTF2_IoStatusFlag ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoCreateHandle { handle, mode: mode.into(), creation_flags: creation_flags.into(), status_flags: status_flags.into() })
     }
 
-    pub unsafe extern "C" fn io_destroy_handle(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoDestroyHandle { handle })
+    pub unsafe extern "C" fn io_destroy_handle<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoDestroyHandle { handle })
     }
 
-    pub unsafe extern "C" fn io_duplicate_handle(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, old_handle: OTF2_IoHandleRef, new_handle: OTF2_IoHandleRef, status_flags: OTF2_IoStatusFlag ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoDuplicateHandle { old_handle, new_handle, status_flags })
+    pub unsafe extern "C" fn io_duplicate_handle<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, old_handle: OTF2_IoHandleRef, new_handle: OTF2_IoHandleRef, status_flags: OTF2_IoStatusFlag ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoDuplicateHandle { old_handle, new_handle, status_flags: status_flags.into() })
     }
 
-    pub unsafe extern "C" fn io_seek(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, offset_request: i64, whence: OTF2_IoSeekOption, offset_result: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoSeek { handle, offset_request, whence, offset_result })
+    pub unsafe extern "C" fn io_seek<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, offset_request: i64, whence: OTF2_IoSeekOption, offset_result: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoSeek { handle, offset_request, whence: whence.into(), offset_result })
     }
 
-    pub unsafe extern "C" fn io_change_status_flags(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, status_flags: OTF2_IoStatusFlag ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoChangeStatusFlags { handle, status_flags })
+    pub unsafe extern "C" fn io_change_status_flags<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, status_flags: OTF2_IoStatusFlag ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoChangeStatusFlags { handle, status_flags: status_flags.into() })
     }
 
-    pub unsafe extern "C" fn io_delete_file(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, io_paradigm: OTF2_IoParadigmRef, file: OTF2_IoFileRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoDeleteFile { io_paradigm, file })
+    pub unsafe extern "C" fn io_delete_file<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, io_paradigm: OTF2_IoParadigmRef, file: OTF2_IoFileRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoDeleteFile { io_paradigm, file })
     }
 
-    pub unsafe extern "C" fn io_operation_begin(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, mode: OTF2_IoOperationMode, operation_flags: OTF2_IoOperationFlag, bytes_request: u64, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoOperationBegin { handle, mode, operation_flags, bytes_request, matching_id })
+    pub unsafe extern "C" fn io_operation_begin<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, mode: OTF2_IoOperationMode, operation_flags: OTF2_IoOperationFlag, bytes_request: u64, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoOperationBegin { handle, mode: mode.into(), operation_flags: operation_flags.into(), bytes_request, matching_id })
     }
 
-    pub unsafe extern "C" fn io_operation_test(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoOperationTest { handle, matching_id })
+    pub unsafe extern "C" fn io_operation_test<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoOperationTest { handle, matching_id })
     }
 
-    pub unsafe extern "C" fn io_operation_issued(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoOperationIssued { handle, matching_id })
+    pub unsafe extern "C" fn io_operation_issued<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoOperationIssued { handle, matching_id })
     }
 
-    pub unsafe extern "C" fn io_operation_complete(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, bytes_result: u64, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoOperationComplete { handle, bytes_result, matching_id })
+    pub unsafe extern "C" fn io_operation_complete<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, bytes_result: u64, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoOperationComplete { handle, bytes_result, matching_id })
     }
 
-    pub unsafe extern "C" fn io_operation_cancelled(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, matching_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoOperationCancelled { handle, matching_id })
+    pub unsafe extern "C" fn io_operation_cancelled<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, matching_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoOperationCancelled { handle, matching_id })
     }
 
-    pub unsafe extern "C" fn io_acquire_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoAcquireLock { handle, lock_type })
+    pub unsafe extern "C" fn io_acquire_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoAcquireLock { handle, lock_type: lock_type.into() })
     }
 
-    pub unsafe extern "C" fn io_release_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoReleaseLock { handle, lock_type })
+    pub unsafe extern "C" fn io_release_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoReleaseLock { handle, lock_type: lock_type.into() })
     }
 
-    pub unsafe extern "C" fn io_try_lock(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::IoTryLock { handle, lock_type })
+    pub unsafe extern "C" fn io_try_lock<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, handle: OTF2_IoHandleRef, lock_type: OTF2_LockType ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::IoTryLock { handle, lock_type: lock_type.into() })
     }
 
-    pub unsafe extern "C" fn program_begin(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, program_name: OTF2_StringRef, number_of_arguments: u32, program_arguments: *const OTF2_StringRef ) -> OTF2_CallbackCode {
+    pub unsafe extern "C" fn program_begin<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, program_name: OTF2_StringRef, number_of_arguments: u32, program_arguments: *const OTF2_StringRef ) -> OTF2_CallbackCode {
         let program_arguments = unsafe { slice_from_raw!(program_arguments, number_of_arguments) }.to_vec();
-        push_event!(queue, location, time, attributes, EventKind::ProgramBegin { program_name, program_arguments })
+        visit_event!(visitor, location, time, attributes, EventKind::ProgramBegin { program_name, program_arguments })
     }
 
-    pub unsafe extern "C" fn program_end(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, exit_status: i64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::ProgramEnd { exit_status })
+    pub unsafe extern "C" fn program_end<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, exit_status: i64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::ProgramEnd { exit_status })
     }
 
-    pub unsafe extern "C" fn non_blocking_collective_request(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::NonBlockingCollectiveRequest { request_id })
+    pub unsafe extern "C" fn non_blocking_collective_request<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, request_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::NonBlockingCollectiveRequest { request_id })
     }
 
-    pub unsafe extern "C" fn non_blocking_collective_complete(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, collective_op: OTF2_CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64, request_id: u64 ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::NonBlockingCollectiveComplete { collective_op, communicator, root, size_sent, size_received, request_id })
+    pub unsafe extern "C" fn non_blocking_collective_complete<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, collective_op: OTF2_CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64, request_id: u64 ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::NonBlockingCollectiveComplete { collective_op: collective_op.into(), communicator, root, size_sent, size_received, request_id })
     }
 
-    pub unsafe extern "C" fn comm_create(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, communicator: OTF2_CommRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::CommCreate { communicator })
+    pub unsafe extern "C" fn comm_create<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, communicator: OTF2_CommRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::CommCreate { communicator })
     }
 
-    pub unsafe extern "C" fn comm_destroy(location: OTF2_LocationRef, time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, communicator: OTF2_CommRef ) -> OTF2_CallbackCode {
-        push_event!(queue, location, time, attributes, EventKind::CommDestroy { communicator })
+    pub unsafe extern "C" fn comm_destroy<V: EventVisitor>(location: OTF2_LocationRef, time: OTF2_TimeStamp, visitor: *mut c_void, attributes: *mut OTF2_AttributeList, communicator: OTF2_CommRef ) -> OTF2_CallbackCode {
+        visit_event!(visitor, location, time, attributes, EventKind::CommDestroy { communicator })
     }
 }
@@ -0,0 +1,171 @@
+//! A set of event kinds, used to register only the event callbacks a reader actually needs.
+
+use super::event_struct::EventKindId;
+
+/// Bitset over [`EventKindId`].
+///
+/// When passed to an event reader, only the kinds in the set have a callback registered with OTF2.
+/// Records of any other kind are skipped inside OTF2 and never decoded into an `Event`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Vec<EventKindId>", into = "Vec<EventKindId>"))]
+pub struct EventKindSet(u128);
+
+const _: () = assert!(EventKindId::ALL.len() <= u128::BITS as usize, "too many event kinds for EventKindSet");
+
+impl EventKindSet {
+    /// `Enter` and `Leave` events only.
+    pub const REGIONS: Self = Self::empty()
+        .with(EventKindId::Enter)
+        .with(EventKindId::Leave);
+
+    /// Blocking and non-blocking MPI point-to-point events.
+    pub const MPI_POINT_TO_POINT: Self = Self::empty()
+        .with(EventKindId::MpiSend)
+        .with(EventKindId::MpiIsend)
+        .with(EventKindId::MpiIsendComplete)
+        .with(EventKindId::MpiIrecvRequest)
+        .with(EventKindId::MpiRecv)
+        .with(EventKindId::MpiIrecv)
+        .with(EventKindId::MpiRequestTest)
+        .with(EventKindId::MpiRequestCancelled);
+
+    /// Blocking and non-blocking MPI collective events.
+    pub const MPI_COLLECTIVE: Self = Self::empty()
+        .with(EventKindId::MpiCollectiveBegin)
+        .with(EventKindId::MpiCollectiveEnd)
+        .with(EventKindId::NonBlockingCollectiveRequest)
+        .with(EventKindId::NonBlockingCollectiveComplete);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        let mut bits = 0;
+        let mut index = 0;
+        while index < EventKindId::ALL.len() {
+            bits |= Self::bit(EventKindId::ALL[index]);
+            index += 1;
+        }
+        Self(bits)
+    }
+
+    const fn bit(kind: EventKindId) -> u128 {
+        1 << (kind as u8)
+    }
+
+    /// Return a copy of this set which also contains `kind`.
+    pub const fn with(self, kind: EventKindId) -> Self {
+        Self(self.0 | Self::bit(kind))
+    }
+
+    /// Return a copy of this set which doesn't contain `kind`.
+    pub const fn without(self, kind: EventKindId) -> Self {
+        Self(self.0 & !Self::bit(kind))
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn contains(&self, kind: EventKindId) -> bool {
+        self.0 & Self::bit(kind) != 0
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn insert(&mut self, kind: EventKindId) {
+        *self = self.with(kind);
+    }
+
+    pub fn remove(&mut self, kind: EventKindId) {
+        *self = self.without(kind);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = EventKindId> + '_ {
+        EventKindId::ALL.iter().copied().filter(|&kind| self.contains(kind))
+    }
+}
+
+impl Default for EventKindSet {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl From<EventKindId> for EventKindSet {
+    fn from(kind: EventKindId) -> Self {
+        Self::empty().with(kind)
+    }
+}
+
+impl FromIterator<EventKindId> for EventKindSet {
+    fn from_iter<I: IntoIterator<Item = EventKindId>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::empty(), Self::with)
+    }
+}
+
+impl From<Vec<EventKindId>> for EventKindSet {
+    fn from(kinds: Vec<EventKindId>) -> Self {
+        kinds.into_iter().collect()
+    }
+}
+
+impl From<EventKindSet> for Vec<EventKindId> {
+    fn from(kinds: EventKindSet) -> Self {
+        kinds.iter().collect()
+    }
+}
+
+impl std::ops::BitOr for EventKindSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl std::ops::BitOr<EventKindId> for EventKindSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: EventKindId) -> Self::Output {
+        self.with(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_contains_every_kind() {
+        let all = EventKindSet::all();
+        assert_eq!(all.len(), EventKindId::ALL.len());
+        assert!(EventKindId::ALL.iter().all(|&kind| all.contains(kind)));
+    }
+
+    #[test]
+    fn presets_contain_only_their_kinds() {
+        let regions: Vec<_> = EventKindSet::REGIONS.iter().collect();
+        assert_eq!(regions, vec![EventKindId::Enter, EventKindId::Leave]);
+        assert!(EventKindSet::MPI_POINT_TO_POINT.contains(EventKindId::MpiIrecv));
+        assert!(!EventKindSet::MPI_POINT_TO_POINT.contains(EventKindId::MpiCollectiveEnd));
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut kinds: EventKindSet = [EventKindId::Enter, EventKindId::CommDestroy].into_iter().collect();
+        assert!(kinds.contains(EventKindId::CommDestroy));
+        kinds.remove(EventKindId::CommDestroy);
+        kinds.insert(EventKindId::Unknown);
+        assert_eq!(kinds, EventKindSet::from(EventKindId::Enter) | EventKindId::Unknown);
+        assert!(EventKindSet::empty().is_empty());
+    }
+}
@@ -1,5 +1,11 @@
 mod global_reader_callbacks;
 mod event_struct;
+mod kind_set;
+mod visitor;
+mod writer;
 
-pub use event_struct::{Event, EventKind};
-pub use global_reader_callbacks::GlobalEvtReaderCallbacks;
+pub use event_struct::{Event, EventKind, EventKindId};
+pub use global_reader_callbacks::{EventQueue, GlobalEvtReaderCallbacks};
+pub use kind_set::EventKindSet;
+pub use visitor::EventVisitor;
+pub(crate) use writer::write_event;
@@ -0,0 +1,21 @@
+use crate::internal::*;
+use crate::attribute::AttributeListRef;
+
+use super::event_struct::EventKind;
+
+/// Receives events directly from the OTF2 callbacks, without queueing them.
+///
+/// Attributes are passed as a borrowed [`AttributeListRef`] which is only decoded if the visitor
+/// asks for it. Return `OTF2_CALLBACK_INTERRUPT` to stop reading.
+pub trait EventVisitor {
+    fn visit_event(&mut self, location: OTF2_LocationRef, time: OTF2_TimeStamp, attributes: AttributeListRef<'_>, kind: EventKind) -> OTF2_CallbackCode;
+}
+
+impl<F> EventVisitor for F
+where
+    F: FnMut(OTF2_LocationRef, OTF2_TimeStamp, AttributeListRef<'_>, EventKind) -> OTF2_CallbackCode,
+{
+    fn visit_event(&mut self, location: OTF2_LocationRef, time: OTF2_TimeStamp, attributes: AttributeListRef<'_>, kind: EventKind) -> OTF2_CallbackCode {
+        self(location, time, attributes, kind)
+    }
+}
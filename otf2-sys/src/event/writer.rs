@@ -0,0 +1,107 @@
+use crate::archive::array_length;
+use crate::error::Status;
+use crate::internal::*;
+
+use super::event_struct::{Event, EventKind};
+
+/// Write `event` with `writer`, passing its attributes through `attributes`. OTF2 can't write
+/// records of unknown kind, so those are skipped.
+pub(crate) fn write_event(writer: &mut Handle<OTF2_EvtWriter>, attributes: &mut Handle<OTF2_AttributeList>, event: &Event) -> Status<()> {
+    use EventKind::*;
+    if let Unknown {} = event.kind {
+        return Ok(());
+    }
+    attributes.add_attributes(&event.data.attributes)?;
+    let (writer, attributes, time) = (writer.as_mut_ptr(), attributes.as_mut_ptr(), event.data.time);
+    unsafe {
+        match event.kind {
+            Unknown {} => unreachable!("skipped above"),
+            BufferFlush { stop_time } => OTF2_EvtWriter_BufferFlush(writer, attributes, time, stop_time),
+            MeasurementOnOff { measurement_mode } => OTF2_EvtWriter_MeasurementOnOff(writer, attributes, time, measurement_mode.into()),
+            Enter { region } => OTF2_EvtWriter_Enter(writer, attributes, time, region),
+            Leave { region } => OTF2_EvtWriter_Leave(writer, attributes, time, region),
+            MpiSend { receiver, communicator, msg_tag, msg_length } => OTF2_EvtWriter_MpiSend(writer, attributes, time, receiver, communicator, msg_tag, msg_length),
+            MpiIsend { receiver, communicator, msg_tag, msg_length, request_id } => OTF2_EvtWriter_MpiIsend(writer, attributes, time, receiver, communicator, msg_tag, msg_length, request_id),
+            MpiIsendComplete { request_id } => OTF2_EvtWriter_MpiIsendComplete(writer, attributes, time, request_id),
+            MpiIrecvRequest { request_id } => OTF2_EvtWriter_MpiIrecvRequest(writer, attributes, time, request_id),
+            MpiRecv { sender, communicator, msg_tag, msg_length } => OTF2_EvtWriter_MpiRecv(writer, attributes, time, sender, communicator, msg_tag, msg_length),
+            MpiIrecv { sender, communicator, msg_tag, msg_length, request_id } => OTF2_EvtWriter_MpiIrecv(writer, attributes, time, sender, communicator, msg_tag, msg_length, request_id),
+            MpiRequestTest { request_id } => OTF2_EvtWriter_MpiRequestTest(writer, attributes, time, request_id),
+            MpiRequestCancelled { request_id } => OTF2_EvtWriter_MpiRequestCancelled(writer, attributes, time, request_id),
+            MpiCollectiveBegin {} => OTF2_EvtWriter_MpiCollectiveBegin(writer, attributes, time),
+            MpiCollectiveEnd { collective_op, communicator, root, size_sent, size_received } => OTF2_EvtWriter_MpiCollectiveEnd(writer, attributes, time, collective_op.into(), communicator, root, size_sent, size_received),
+            OmpFork { number_of_requested_threads } => OTF2_EvtWriter_OmpFork(writer, attributes, time, number_of_requested_threads),
+            OmpJoin {} => OTF2_EvtWriter_OmpJoin(writer, attributes, time),
+            OmpAcquireLock { lock_id, acquisition_order } => OTF2_EvtWriter_OmpAcquireLock(writer, attributes, time, lock_id, acquisition_order),
+            OmpReleaseLock { lock_id, acquisition_order } => OTF2_EvtWriter_OmpReleaseLock(writer, attributes, time, lock_id, acquisition_order),
+            OmpTaskCreate { task_id } => OTF2_EvtWriter_OmpTaskCreate(writer, attributes, time, task_id),
+            OmpTaskSwitch { task_id } => OTF2_EvtWriter_OmpTaskSwitch(writer, attributes, time, task_id),
+            OmpTaskComplete { task_id } => OTF2_EvtWriter_OmpTaskComplete(writer, attributes, time, task_id),
+            Metric { metric, ref values } => {
+                let (types, values): (Vec<_>, Vec<_>) = values.iter().map(|value| value.to_raw()).unzip();
+                OTF2_EvtWriter_Metric(writer, attributes, time, metric, array_length(&types)?, types.as_ptr(), values.as_ptr())
+            },
+            ParameterString { parameter, string } => OTF2_EvtWriter_ParameterString(writer, attributes, time, parameter, string),
+            ParameterInt { parameter, value } => OTF2_EvtWriter_ParameterInt(writer, attributes, time, parameter, value),
+            ParameterUnsignedInt { parameter, value } => OTF2_EvtWriter_ParameterUnsignedInt(writer, attributes, time, parameter, value),
+            RmaWinCreate { win } => OTF2_EvtWriter_RmaWinCreate(writer, attributes, time, win),
+            RmaWinDestroy { win } => OTF2_EvtWriter_RmaWinDestroy(writer, attributes, time, win),
+            RmaCollectiveBegin {} => OTF2_EvtWriter_RmaCollectiveBegin(writer, attributes, time),
+            RmaCollectiveEnd { collective_op, sync_level, win, root, bytes_sent, bytes_received } => OTF2_EvtWriter_RmaCollectiveEnd(writer, attributes, time, collective_op.into(), sync_level.into(), win, root, bytes_sent, bytes_received),
+            RmaGroupSync { sync_level, win, group } => OTF2_EvtWriter_RmaGroupSync(writer, attributes, time, sync_level.into(), win, group),
+            RmaRequestLock { win, remote, lock_id, lock_type } => OTF2_EvtWriter_RmaRequestLock(writer, attributes, time, win, remote, lock_id, lock_type.into()),
+            RmaAcquireLock { win, remote, lock_id, lock_type } => OTF2_EvtWriter_RmaAcquireLock(writer, attributes, time, win, remote, lock_id, lock_type.into()),
+            RmaTryLock { win, remote, lock_id, lock_type } => OTF2_EvtWriter_RmaTryLock(writer, attributes, time, win, remote, lock_id, lock_type.into()),
+            RmaReleaseLock { win, remote, lock_id } => OTF2_EvtWriter_RmaReleaseLock(writer, attributes, time, win, remote, lock_id),
+            RmaSync { win, remote, sync_type } => OTF2_EvtWriter_RmaSync(writer, attributes, time, win, remote, sync_type.into()),
+            RmaWaitChange { win } => OTF2_EvtWriter_RmaWaitChange(writer, attributes, time, win),
+            RmaPut { win, remote, bytes, matching_id } => OTF2_EvtWriter_RmaPut(writer, attributes, time, win, remote, bytes, matching_id),
+            RmaGet { win, remote, bytes, matching_id } => OTF2_EvtWriter_RmaGet(writer, attributes, time, win, remote, bytes, matching_id),
+            RmaAtomic { win, remote, type_, bytes_sent, bytes_received, matching_id } => OTF2_EvtWriter_RmaAtomic(writer, attributes, time, win, remote, type_.into(), bytes_sent, bytes_received, matching_id),
+            RmaOpCompleteBlocking { win, matching_id } => OTF2_EvtWriter_RmaOpCompleteBlocking(writer, attributes, time, win, matching_id),
+            RmaOpCompleteNonBlocking { win, matching_id } => OTF2_EvtWriter_RmaOpCompleteNonBlocking(writer, attributes, time, win, matching_id),
+            RmaOpTest { win, matching_id } => OTF2_EvtWriter_RmaOpTest(writer, attributes, time, win, matching_id),
+            RmaOpCompleteRemote { win, matching_id } => OTF2_EvtWriter_RmaOpCompleteRemote(writer, attributes, time, win, matching_id),
+            ThreadFork { model, number_of_requested_threads } => OTF2_EvtWriter_ThreadFork(writer, attributes, time, model.into(), number_of_requested_threads),
+            ThreadJoin { model } => OTF2_EvtWriter_ThreadJoin(writer, attributes, time, model.into()),
+            ThreadTeamBegin { thread_team } => OTF2_EvtWriter_ThreadTeamBegin(writer, attributes, time, thread_team),
+            ThreadTeamEnd { thread_team } => OTF2_EvtWriter_ThreadTeamEnd(writer, attributes, time, thread_team),
+            ThreadAcquireLock { model, lock_id, acquisition_order } => OTF2_EvtWriter_ThreadAcquireLock(writer, attributes, time, model.into(), lock_id, acquisition_order),
+            ThreadReleaseLock { model, lock_id, acquisition_order } => OTF2_EvtWriter_ThreadReleaseLock(writer, attributes, time, model.into(), lock_id, acquisition_order),
+            ThreadTaskCreate { thread_team, creating_thread, generation_number } => OTF2_EvtWriter_ThreadTaskCreate(writer, attributes, time, thread_team, creating_thread, generation_number),
+            ThreadTaskSwitch { thread_team, creating_thread, generation_number } => OTF2_EvtWriter_ThreadTaskSwitch(writer, attributes, time, thread_team, creating_thread, generation_number),
+            ThreadTaskComplete { thread_team, creating_thread, generation_number } => OTF2_EvtWriter_ThreadTaskComplete(writer, attributes, time, thread_team, creating_thread, generation_number),
+            ThreadCreate { thread_contingent, sequence_count } => OTF2_EvtWriter_ThreadCreate(writer, attributes, time, thread_contingent, sequence_count),
+            ThreadBegin { thread_contingent, sequence_count } => OTF2_EvtWriter_ThreadBegin(writer, attributes, time, thread_contingent, sequence_count),
+            ThreadWait { thread_contingent, sequence_count } => OTF2_EvtWriter_ThreadWait(writer, attributes, time, thread_contingent, sequence_count),
+            ThreadEnd { thread_contingent, sequence_count } => OTF2_EvtWriter_ThreadEnd(writer, attributes, time, thread_contingent, sequence_count),
+            CallingContextEnter { calling_context, unwind_distance } => OTF2_EvtWriter_CallingContextEnter(writer, attributes, time, calling_context, unwind_distance),
+            CallingContextLeave { calling_context } => OTF2_EvtWriter_CallingContextLeave(writer, attributes, time, calling_context),
+            CallingContextSample { calling_context, unwind_distance, interrupt_generator } => OTF2_EvtWriter_CallingContextSample(writer, attributes, time, calling_context, unwind_distance, interrupt_generator),
+            IoCreateHandle { handle, mode, creation_flags, status_flags } => OTF2_EvtWriter_IoCreateHandle(writer, attributes, time, handle, mode.into(), creation_flags.into(), status_flags.into()),
+            IoDestroyHandle { handle } => OTF2_EvtWriter_IoDestroyHandle(writer, attributes, time, handle),
+            IoDuplicateHandle { old_handle, new_handle, status_flags } => OTF2_EvtWriter_IoDuplicateHandle(writer, attributes, time, old_handle, new_handle, status_flags.into()),
+            IoSeek { handle, offset_request, whence, offset_result } => OTF2_EvtWriter_IoSeek(writer, attributes, time, handle, offset_request, whence.into(), offset_result),
+            IoChangeStatusFlags { handle, status_flags } => OTF2_EvtWriter_IoChangeStatusFlags(writer, attributes, time, handle, status_flags.into()),
+            IoDeleteFile { io_paradigm, file } => OTF2_EvtWriter_IoDeleteFile(writer, attributes, time, io_paradigm, file),
+            IoOperationBegin { handle, mode, operation_flags, bytes_request, matching_id } => OTF2_EvtWriter_IoOperationBegin(writer, attributes, time, handle, mode.into(), operation_flags.into(), bytes_request, matching_id),
+            IoOperationTest { handle, matching_id } => OTF2_EvtWriter_IoOperationTest(writer, attributes, time, handle, matching_id),
+            IoOperationIssued { handle, matching_id } => OTF2_EvtWriter_IoOperationIssued(writer, attributes, time, handle, matching_id),
+            IoOperationComplete { handle, bytes_result, matching_id } => OTF2_EvtWriter_IoOperationComplete(writer, attributes, time, handle, bytes_result, matching_id),
+            IoOperationCancelled { handle, matching_id } => OTF2_EvtWriter_IoOperationCancelled(writer, attributes, time, handle, matching_id),
+            IoAcquireLock { handle, lock_type } => OTF2_EvtWriter_IoAcquireLock(writer, attributes, time, handle, lock_type.into()),
+            IoReleaseLock { handle, lock_type } => OTF2_EvtWriter_IoReleaseLock(writer, attributes, time, handle, lock_type.into()),
+            IoTryLock { handle, lock_type } => OTF2_EvtWriter_IoTryLock(writer, attributes, time, handle, lock_type.into()),
+            ProgramBegin { program_name, ref program_arguments } => {
+                OTF2_EvtWriter_ProgramBegin(writer, attributes, time, program_name, array_length(program_arguments)?, program_arguments.as_ptr())
+            },
+            ProgramEnd { exit_status } => OTF2_EvtWriter_ProgramEnd(writer, attributes, time, exit_status),
+            NonBlockingCollectiveRequest { request_id } => OTF2_EvtWriter_NonBlockingCollectiveRequest(writer, attributes, time, request_id),
+            NonBlockingCollectiveComplete { collective_op, communicator, root, size_sent, size_received, request_id } => OTF2_EvtWriter_NonBlockingCollectiveComplete(writer, attributes, time, collective_op.into(), communicator, root, size_sent, size_received, request_id),
+            CommCreate { communicator } => OTF2_EvtWriter_CommCreate(writer, attributes, time, communicator),
+            CommDestroy { communicator } => OTF2_EvtWriter_CommDestroy(writer, attributes, time, communicator),
+        }
+    }?;
+    Ok(())
+}
+
@@ -0,0 +1,15 @@
+use std::collections::BTreeMap;
+
+/// The properties of a trace which are recorded in its anchor file.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraceInfo {
+    /// The OTF2 version which wrote the trace, as (major, minor, bugfix).
+    pub version: (u8, u8, u8),
+    pub trace_id: u64,
+    pub creator: String,
+    pub description: String,
+    pub machine_name: String,
+    pub number_of_locations: u64,
+    pub properties: BTreeMap<String, String>,
+}
@@ -0,0 +1,169 @@
+//! A newline-delimited JSON form of whole traces: one [`Record`] per line, first the trace info,
+//! then every global definition, then every event.
+
+use crate::archive::Archive;
+use crate::definition::Definition;
+use crate::error::StatusCode;
+use crate::event::Event;
+use crate::info::TraceInfo;
+use crate::reader::Trace;
+use crate::traits::AsJson;
+use std::ffi::CString;
+use std::io::{BufRead, Write};
+
+/// One line of a JSON lines trace.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Record {
+    Info(TraceInfo),
+    Definition(Definition),
+    Event(Event),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JsonLinesError {
+    #[error(transparent)]
+    Otf2(#[from] StatusCode),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("line {line}: {source}")]
+    Json { line: u64, source: serde_json::Error },
+}
+
+impl Trace {
+    /// Write the whole trace to `out` as JSON lines, returning the number of lines written.
+    pub fn dump_json_lines(&mut self, mut out: impl Write) -> Result<u64, JsonLinesError> {
+        let mut lines = 0;
+        let mut write = |record: Record| -> Result<(), JsonLinesError> {
+            lines += 1;
+            let json = record.as_json().map_err(|source| JsonLinesError::Json { line: lines, source })?;
+            writeln!(out, "{json}")?;
+            Ok(())
+        };
+        write(Record::Info(self.info()?))?;
+        let (_, definitions) = self.read_definitions()?;
+        for definition in definitions {
+            write(Record::Definition(definition))?;
+        }
+        let batch_size = self.options().batch_size;
+        for event in self.iter_events(batch_size)? {
+            write(Record::Event(event?))?;
+        }
+        out.flush()?;
+        Ok(lines)
+    }
+}
+
+/// Create the archive `name` in the directory `path` from the JSON lines in `input`, returning
+/// the number of records written. Blank lines are skipped.
+pub fn load_json_lines(input: impl BufRead, path: CString, name: CString) -> Result<u64, JsonLinesError> {
+    let mut archive = Archive::create(path, name)?;
+    let mut records = 0;
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let Some(record) = parse_record(&line, index as u64 + 1)? else {
+            continue;
+        };
+        match record {
+            Record::Info(info) => archive.set_info(&info)?,
+            Record::Definition(definition) => archive.write_definition(&definition)?,
+            Record::Event(event) => archive.write_event(&event)?,
+        }
+        records += 1;
+    }
+    archive.finish()?;
+    Ok(records)
+}
+
+/// Parse line number `line` of a JSON lines trace, or `None` if it is blank.
+fn parse_record(text: &str, line: u64) -> Result<Option<Record>, JsonLinesError> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(text).map(Some).map_err(|source| JsonLinesError::Json { line, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::fixtures::{definitions, TempDir};
+    use crate::attribute::{AttributeValue, Attributes};
+    use crate::definition::AttributeDef;
+    use crate::event::EventKind;
+    use crate::internal::*;
+    use crate::reader;
+    use crate::types::Type;
+
+    #[test]
+    fn records_round_trip_through_a_line() {
+        let records = [
+            Record::Info(TraceInfo { version: (3, 0, 0), creator: "test".to_string(), ..Default::default() }),
+            Record::Definition(Definition::String { defn: OTF2_StringRef(0), value: "main".to_string() }),
+            Record::Event(Event::new(
+                OTF2_LocationRef(2),
+                10,
+                vec![(OTF2_AttributeRef(0), AttributeValue::Int64(-1))].into(),
+                EventKind::Enter { region: OTF2_RegionRef(1) },
+            )),
+        ];
+        for (index, record) in records.iter().enumerate() {
+            let line = record.as_json().unwrap();
+            assert!(!line.contains('\n'), "{line}");
+            assert_eq!(parse_record(&line, index as u64 + 1).unwrap().as_ref(), Some(record));
+        }
+        assert!(records[2].as_json().unwrap().starts_with(r#"{"event":"#));
+    }
+
+    #[test]
+    fn reports_the_line_of_malformed_records() {
+        assert!(parse_record("  ", 1).unwrap().is_none());
+        let error = parse_record(r#"{"event": 1}"#, 7).unwrap_err();
+        assert!(matches!(error, JsonLinesError::Json { line: 7, .. }), "{error}");
+        assert!(error.to_string().starts_with("line 7: "), "{error}");
+    }
+
+    #[test]
+    fn traces_round_trip_through_an_archive() {
+        let mut definitions = definitions(&[2]);
+        definitions.push(Definition::Attribute {
+            defn: OTF2_AttributeRef(0),
+            value: AttributeDef { name: OTF2_StringRef(0), description: OTF2_StringRef(0), kind: Type::Uint32 },
+        });
+        let events = vec![
+            Event::new(OTF2_LocationRef(0), 0, vec![(OTF2_AttributeRef(0), AttributeValue::Uint32(7))].into(), EventKind::Enter { region: OTF2_RegionRef(0) }),
+            Event::new(OTF2_LocationRef(0), 20, Attributes::default(), EventKind::Leave { region: OTF2_RegionRef(0) }),
+        ];
+        let info = TraceInfo { creator: "jsonl test".to_string(), ..Default::default() };
+        let records: Vec<_> = std::iter::once(Record::Info(info))
+            .chain(definitions.iter().cloned().map(Record::Definition))
+            .chain(events.iter().cloned().map(Record::Event))
+            .collect();
+        let input: String = records.iter().map(|record| record.as_json().unwrap() + "\n").collect();
+
+        let dir = TempDir::new("jsonl");
+        let written = load_json_lines(input.as_bytes(), dir.path(), CString::new("trace").unwrap()).expect("Failed to load JSON lines");
+        assert_eq!(written, records.len() as u64);
+
+        let mut output = Vec::new();
+        reader::open(dir.anchor_file("trace")).unwrap().dump_json_lines(&mut output).expect("Failed to dump JSON lines");
+
+        let dumped: Vec<_> = output.split(|&byte| byte == b'\n')
+            .enumerate()
+            .filter_map(|(index, line)| parse_record(std::str::from_utf8(line).unwrap(), index as u64 + 1).unwrap())
+            .collect();
+        let Some(Record::Info(info)) = dumped.first() else {
+            panic!("the first record should be the trace info");
+        };
+        assert_eq!(info.creator, "jsonl test");
+        for definition in &definitions {
+            assert!(dumped.contains(&Record::Definition(definition.clone())), "missing {definition:?}");
+        }
+        let dumped_events: Vec<_> = dumped.into_iter()
+            .filter_map(|record| match record {
+                Record::Event(event) => Some(event),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(dumped_events, events);
+    }
+}
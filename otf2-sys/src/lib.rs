@@ -2,15 +2,30 @@
 #![allow(unused_imports)]
 #![allow(dead_code)]
 
+mod analysis;
+mod archive;
 mod attribute;
 mod c;
+mod collective;
 mod definition;
 mod error;
 mod event;
 mod handle;
+mod info;
+#[cfg(feature = "serde")]
+mod jsonl;
+mod locking;
 mod reader;
+mod snapshot;
+#[cfg(feature = "tokio")]
+mod stream;
+mod thumbnail;
 mod macros;
+mod marker;
+mod options;
+mod progress;
 mod traits;
+mod types;
 
 mod internal {
     pub use super::c::*;
@@ -0,0 +1,127 @@
+//! OTF2 locking callbacks implemented with `std::sync`, installed on every reader and archive.
+//!
+//! With these installed, OTF2 guards its internal state, so a `Trace` (and the readers and
+//! writers borrowed from it) may be moved to another thread. Every operation goes through
+//! `&mut self`, so sharing a `&Trace` between threads gives no access to OTF2 at all. Different
+//! locations are read concurrently through `Trace::local_event_readers`, whose readers share the
+//! one `OTF2_Reader` under these locks.
+
+use crate::internal::*;
+
+use std::os::raw::c_void;
+use std::sync::{Condvar, Mutex, PoisonError};
+
+use OTF2_CallbackCode::*;
+
+/// OTF2 keeps a pointer to the callbacks rather than a copy, so they live in a static.
+pub(crate) static MUTEX_LOCKING_CALLBACKS: OTF2_LockingCallbacks = OTF2_LockingCallbacks {
+    otf2_release: None,
+    otf2_create: Some(callbacks::create),
+    otf2_destroy: Some(callbacks::destroy),
+    otf2_lock: Some(callbacks::lock),
+    otf2_unlock: Some(callbacks::unlock),
+};
+
+/// A lock which, unlike a `MutexGuard`, can be released by a different call than the one which
+/// acquired it, as OTF2 requires.
+#[derive(Debug, Default)]
+struct Lock {
+    locked: Mutex<bool>,
+    released: Condvar,
+}
+
+impl Lock {
+    fn lock(&self) {
+        let mut locked = self.locked.lock().unwrap_or_else(PoisonError::into_inner);
+        while *locked {
+            locked = self.released.wait(locked).unwrap_or_else(PoisonError::into_inner);
+        }
+        *locked = true;
+    }
+
+    fn unlock(&self) {
+        *self.locked.lock().unwrap_or_else(PoisonError::into_inner) = false;
+        self.released.notify_one();
+    }
+}
+
+mod callbacks {
+    use super::*;
+
+    #[inline]
+    fn as_lock<'a>(lock: OTF2_Lock) -> Option<&'a Lock> {
+        unsafe { (lock as *const Lock).as_ref() }
+    }
+
+    pub unsafe extern "C" fn create(_: *mut c_void, lock: *mut OTF2_Lock) -> OTF2_CallbackCode {
+        if lock.is_null() {
+            return OTF2_CALLBACK_ERROR;
+        }
+        unsafe { *lock = Box::into_raw(Box::<Lock>::default()) as OTF2_Lock };
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    pub unsafe extern "C" fn destroy(_: *mut c_void, lock: OTF2_Lock) -> OTF2_CallbackCode {
+        if lock.is_null() {
+            return OTF2_CALLBACK_ERROR;
+        }
+        drop(unsafe { Box::from_raw(lock as *mut Lock) });
+        OTF2_CALLBACK_SUCCESS
+    }
+
+    pub unsafe extern "C" fn lock(_: *mut c_void, lock: OTF2_Lock) -> OTF2_CallbackCode {
+        match as_lock(lock) {
+            Some(lock) => { lock.lock(); OTF2_CALLBACK_SUCCESS },
+            None => OTF2_CALLBACK_ERROR,
+        }
+    }
+
+    pub unsafe extern "C" fn unlock(_: *mut c_void, lock: OTF2_Lock) -> OTF2_CallbackCode {
+        match as_lock(lock) {
+            Some(lock) => { lock.unlock(); OTF2_CALLBACK_SUCCESS },
+            None => OTF2_CALLBACK_ERROR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn lock_excludes_other_threads() {
+        let callbacks = &MUTEX_LOCKING_CALLBACKS;
+        let mut lock: OTF2_Lock = std::ptr::null_mut();
+        unsafe { assert_eq!(callbacks.otf2_create.unwrap()(std::ptr::null_mut(), &mut lock), OTF2_CALLBACK_SUCCESS) };
+        let lock_addr = lock as usize;
+        // Not atomic: only correct if the lock serialises the increments
+        let counter = std::cell::UnsafeCell::new(0u32);
+        let counter_addr = counter.get() as usize;
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(move || {
+                    for _ in 0..1000 {
+                        unsafe {
+                            callbacks.otf2_lock.unwrap()(std::ptr::null_mut(), lock_addr as OTF2_Lock);
+                            let counter = counter_addr as *mut u32;
+                            counter.write(counter.read() + 1);
+                            callbacks.otf2_unlock.unwrap()(std::ptr::null_mut(), lock_addr as OTF2_Lock);
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.into_inner(), 4000);
+        unsafe { assert_eq!(callbacks.otf2_destroy.unwrap()(std::ptr::null_mut(), lock), OTF2_CALLBACK_SUCCESS) };
+    }
+
+    #[test]
+    fn null_lock_is_an_error() {
+        let callbacks = &MUTEX_LOCKING_CALLBACKS;
+        unsafe {
+            assert_eq!(callbacks.otf2_lock.unwrap()(std::ptr::null_mut(), std::ptr::null_mut()), OTF2_CALLBACK_ERROR);
+            assert_eq!(callbacks.otf2_create.unwrap()(std::ptr::null_mut(), std::ptr::null_mut()), OTF2_CALLBACK_ERROR);
+        }
+    }
+}
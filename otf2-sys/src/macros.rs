@@ -22,6 +22,16 @@ macro_rules! parse_ident_or_underscore {
     };
 }
 
+/// The OTF2 type named by a `declare_enum_union_wrapper!` arm. The catch-all arm has no type.
+macro_rules! type_or_none {
+    ( _ ) => {
+        OTF2_TYPE_NONE
+    };
+    ( $ident:ident ) => {
+        $ident
+    };
+}
+
 macro_rules! map_optional_union_access {
     ($variant_name:ident from $union_field:expr ) => {
         $variant_name(unsafe { $union_field })
@@ -60,6 +70,22 @@ macro_rules! declare_enum_union_wrapper {
                 }
             }
 
+            /// The OTF2 type and raw value which OTF2's writers take.
+            pub fn to_raw(self) -> (OTF2_Type, $mapped_union_newtype) {
+                use OTF2_Type_enum::*;
+                // SAFETY: every field of the union is plain data, so all-zero is a valid value
+                let mut raw: $mapped_union_newtype = unsafe { std::mem::zeroed() };
+                let kind = match self {
+                    $(
+                        Self::$variant_name(_value) => {
+                            $( raw.0.$union_field = _value; )?
+                            crate::internal::type_or_none!($type_variant)
+                        }
+                    )*
+                };
+                (OTF2_Type(kind as u8), raw)
+            }
+
             pub fn type_name(&self) -> &'static str {
                 match self {
                     $(
@@ -86,6 +112,338 @@ macro_rules! declare_enum_union_wrapper {
     };
 }
 
+/// Declare an enum with named variants along with a field-less `$id_name` enum which has one
+/// variant per variant of `$enum_name`. The discriminants of `$id_name` are contiguous from 0.
+macro_rules! declare_named_enum {
+    (
+        $(#[$enum_attr:meta])*
+        $vis:vis enum $enum_name:ident : $id_name:ident {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant_name:ident { $($field:ident: $ty:ty),* }
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$enum_attr])*
+        $vis enum $enum_name {
+            $(
+                $(#[$variant_attr])*
+                $variant_name { $($field: $ty),* },
+            )*
+        }
+
+        #[doc = concat!("The variants of [`", stringify!($enum_name), "`] without their data.")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[repr(u8)]
+        $vis enum $id_name {
+            $( $variant_name, )*
+        }
+
+        impl $enum_name {
+            pub fn name(&self) -> &'static str {
+                self.id().name()
+            }
+
+            pub fn id(&self) -> $id_name {
+                match self {
+                    $(
+                        Self::$variant_name { .. } => $id_name::$variant_name,
+                    )*
+                }
+            }
+        }
+
+        impl $id_name {
+            /// Every variant, in declaration order.
+            pub const ALL: &'static [$id_name] = &[ $( $id_name::$variant_name, )* ];
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(
+                        Self::$variant_name => stringify!($variant_name),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+/// Declare an enum wrapping an OTF2 enum typedef. Each variant is given by the name of a constant of
+/// the bindgen enum `$c_enum`; raw values without a variant are preserved in `Other`. With
+/// `wraps $newtype` the typedef is a bindgen newtype rather than a plain integer.
+macro_rules! declare_otf2_enum {
+    (
+        $(#[$enum_attr:meta])*
+        $vis:vis enum $name:ident: $c_enum:ident as $repr:ty $(, wraps $newtype:ident)? {
+            $( $variant:ident = $constant:ident ),* $(,)?
+        }
+    ) => {
+        $(#[$enum_attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        $vis enum $name {
+            $( $variant, )*
+            /// A value which has no variant, kept as-is.
+            Other($repr),
+        }
+
+        impl $name {
+            pub fn from_raw(raw: $repr) -> Self {
+                $( if raw == $c_enum::$constant as $repr { return Self::$variant; } )*
+                Self::Other(raw)
+            }
+
+            pub fn to_raw(self) -> $repr {
+                match self {
+                    $( Self::$variant => $c_enum::$constant as $repr, )*
+                    Self::Other(raw) => raw,
+                }
+            }
+
+            /// The name of the variant, or `None` for [`Self::Other`].
+            pub fn name(self) -> Option<&'static str> {
+                match self {
+                    $( Self::$variant => Some(stringify!($variant)), )*
+                    Self::Other(_) => None,
+                }
+            }
+        }
+
+        impl From<$repr> for $name {
+            fn from(raw: $repr) -> Self {
+                Self::from_raw(raw)
+            }
+        }
+
+        impl From<$name> for $repr {
+            fn from(value: $name) -> Self {
+                value.to_raw()
+            }
+        }
+
+        $(
+            impl From<$newtype> for $name {
+                fn from(raw: $newtype) -> Self {
+                    Self::from_raw(raw.0)
+                }
+            }
+
+            impl From<$name> for $newtype {
+                fn from(value: $name) -> Self {
+                    $newtype(value.to_raw())
+                }
+            }
+        )?
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self.name() {
+                    Some(name) => f.write_str(name),
+                    None => write!(f, "{}", self.to_raw()),
+                }
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = crate::types::ParseError;
+
+            /// Parse a variant name or a raw value.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $( stringify!($variant) => Ok(Self::$variant), )*
+                    _ => s.parse::<$repr>()
+                        .map(Self::from_raw)
+                        .map_err(|_| crate::types::ParseError::new(stringify!($name), s)),
+                }
+            }
+        }
+
+        crate::internal::impl_serde_via_str!($name, $repr);
+    };
+}
+
+/// Declare a set of flags wrapping an OTF2 flag typedef. Each flag is given by the name of a
+/// constant of the bindgen enum `$c_enum`. Bits without a name are preserved.
+macro_rules! declare_otf2_flags {
+    (
+        $(#[$flags_attr:meta])*
+        $vis:vis struct $name:ident: $c_enum:ident as $repr:ty {
+            $( $flag:ident = $constant:ident ),* $(,)?
+        }
+    ) => {
+        $(#[$flags_attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+        $vis struct $name($repr);
+
+        impl $name {
+            $( pub const $flag: Self = Self($c_enum::$constant as $repr); )*
+
+            const NAMED: &'static [(&'static str, Self)] = &[ $( (stringify!($flag), Self::$flag), )* ];
+
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            /// Every named flag.
+            pub const fn all() -> Self {
+                Self(0 $( | Self::$flag.0 )*)
+            }
+
+            /// Keep all of `bits`, including those without a name.
+            pub const fn from_bits_retain(bits: $repr) -> Self {
+                Self(bits)
+            }
+
+            pub const fn bits(self) -> $repr {
+                self.0
+            }
+
+            pub const fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            pub const fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            pub const fn intersects(self, other: Self) -> bool {
+                self.0 & other.0 != 0
+            }
+
+            pub fn insert(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+
+            pub fn remove(&mut self, other: Self) {
+                self.0 &= !other.0;
+            }
+
+            /// The bits which don't belong to any named flag.
+            pub const fn unknown_bits(self) -> $repr {
+                self.0 & !Self::all().0
+            }
+
+            /// The named flags which are set.
+            pub fn iter_names(self) -> impl Iterator<Item = (&'static str, Self)> {
+                Self::NAMED.iter().copied().filter(move |(_, flag)| !flag.is_empty() && self.contains(*flag))
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl std::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl std::ops::BitAndAssign for $name {
+            fn bitand_assign(&mut self, rhs: Self) {
+                self.0 &= rhs.0;
+            }
+        }
+
+        impl From<$repr> for $name {
+            fn from(bits: $repr) -> Self {
+                Self::from_bits_retain(bits)
+            }
+        }
+
+        impl From<$name> for $repr {
+            fn from(value: $name) -> Self {
+                value.bits()
+            }
+        }
+
+        /// Named flags separated by `" | "`, then any unknown bits in hex. No flags is `NONE`.
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                if self.is_empty() {
+                    return f.write_str("NONE");
+                }
+                let mut separator = "";
+                for (name, _) in self.iter_names() {
+                    write!(f, "{separator}{name}")?;
+                    separator = " | ";
+                }
+                if self.unknown_bits() != 0 {
+                    write!(f, "{separator}{:#x}", self.unknown_bits())?;
+                }
+                Ok(())
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = crate::types::ParseError;
+
+            /// Parse the format written by `Display`.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let error = || crate::types::ParseError::new(stringify!($name), s);
+                let mut flags = Self::empty();
+                for part in s.split('|').map(str::trim) {
+                    if part == "NONE" {
+                        continue;
+                    }
+                    if let Some((_, flag)) = Self::NAMED.iter().find(|(name, _)| *name == part) {
+                        flags |= *flag;
+                    } else if let Some(hex) = part.strip_prefix("0x") {
+                        flags |= Self(<$repr>::from_str_radix(hex, 16).map_err(|_| error())?);
+                    } else {
+                        flags |= Self(part.parse::<$repr>().map_err(|_| error())?);
+                    }
+                }
+                Ok(flags)
+            }
+        }
+
+        crate::internal::impl_serde_via_str!($name, $repr);
+    };
+}
+
+/// Serialize `$name` with its `Display` form in human-readable formats and as its raw `$repr`
+/// otherwise.
+macro_rules! impl_serde_via_str {
+    ($name:ident, $repr:ty) => {
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    serializer.collect_str(self)
+                } else {
+                    serde::Serialize::serialize(&<$repr>::from(*self), serializer)
+                }
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+                    value.parse().map_err(serde::de::Error::custom)
+                } else {
+                    <$repr as serde::Deserialize>::deserialize(deserializer).map(Self::from)
+                }
+            }
+        }
+    };
+}
+
 pub(crate) use {
-    zipmap, slice_from_raw, parse_ident_or_underscore, map_optional_union_access, declare_enum_union_wrapper
+    zipmap, slice_from_raw, parse_ident_or_underscore, type_or_none, map_optional_union_access, declare_enum_union_wrapper,
+    declare_named_enum, declare_otf2_enum, declare_otf2_flags, impl_serde_via_str
 };
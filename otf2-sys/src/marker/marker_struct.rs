@@ -0,0 +1,160 @@
+use crate::internal::*;
+
+use OTF2_MarkerScope_enum::*;
+use OTF2_MarkerSeverity_enum::*;
+
+/// How severe a marker is. Vampir uses this to choose how prominently to show it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarkerSeverity {
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl MarkerSeverity {
+    pub fn from_raw(severity: OTF2_MarkerSeverity) -> Option<Self> {
+        match severity {
+            s if s == OTF2_SEVERITY_NONE as OTF2_MarkerSeverity => Some(Self::None),
+            s if s == OTF2_SEVERITY_LOW as OTF2_MarkerSeverity => Some(Self::Low),
+            s if s == OTF2_SEVERITY_MEDIUM as OTF2_MarkerSeverity => Some(Self::Medium),
+            s if s == OTF2_SEVERITY_HIGH as OTF2_MarkerSeverity => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    pub fn to_raw(self) -> OTF2_MarkerSeverity {
+        (match self {
+            Self::None => OTF2_SEVERITY_NONE,
+            Self::Low => OTF2_SEVERITY_LOW,
+            Self::Medium => OTF2_SEVERITY_MEDIUM,
+            Self::High => OTF2_SEVERITY_HIGH,
+        }) as OTF2_MarkerSeverity
+    }
+}
+
+/// The part of the trace a marker applies to, together with the definition it refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarkerScope {
+    Global,
+    Location(OTF2_LocationRef),
+    LocationGroup(OTF2_LocationGroupRef),
+    SystemTreeNode(OTF2_SystemTreeNodeRef),
+    Group(OTF2_GroupRef),
+    Comm(OTF2_CommRef),
+}
+
+impl MarkerScope {
+    /// Combine the `scope` and `scopeRef` fields of a marker record. Returns `None` for an unknown
+    /// scope or a reference which doesn't fit the scope's reference type.
+    pub fn from_raw(scope: OTF2_MarkerScope, scope_ref: u64) -> Option<Self> {
+        let as_u32 = || u32::try_from(scope_ref).ok();
+        match scope {
+            s if s == OTF2_MARKER_SCOPE_GLOBAL as OTF2_MarkerScope => Some(Self::Global),
+            s if s == OTF2_MARKER_SCOPE_LOCATION as OTF2_MarkerScope => Some(Self::Location(OTF2_LocationRef(scope_ref))),
+            s if s == OTF2_MARKER_SCOPE_LOCATION_GROUP as OTF2_MarkerScope => as_u32().map(|r| Self::LocationGroup(OTF2_LocationGroupRef(r))),
+            s if s == OTF2_MARKER_SCOPE_SYSTEM_TREE_NODE as OTF2_MarkerScope => as_u32().map(|r| Self::SystemTreeNode(OTF2_SystemTreeNodeRef(r))),
+            s if s == OTF2_MARKER_SCOPE_GROUP as OTF2_MarkerScope => as_u32().map(|r| Self::Group(OTF2_GroupRef(r))),
+            s if s == OTF2_MARKER_SCOPE_COMM as OTF2_MarkerScope => as_u32().map(|r| Self::Comm(OTF2_CommRef(r))),
+            _ => None,
+        }
+    }
+
+    /// Split into the `scope` and `scopeRef` fields of a marker record. The reference of a global
+    /// marker is ignored by OTF2 and written as 0.
+    pub fn to_raw(self) -> (OTF2_MarkerScope, u64) {
+        let (scope, scope_ref) = match self {
+            Self::Global => (OTF2_MARKER_SCOPE_GLOBAL, 0),
+            Self::Location(r) => (OTF2_MARKER_SCOPE_LOCATION, r.0),
+            Self::LocationGroup(r) => (OTF2_MARKER_SCOPE_LOCATION_GROUP, r.0 as u64),
+            Self::SystemTreeNode(r) => (OTF2_MARKER_SCOPE_SYSTEM_TREE_NODE, r.0 as u64),
+            Self::Group(r) => (OTF2_MARKER_SCOPE_GROUP, r.0 as u64),
+            Self::Comm(r) => (OTF2_MARKER_SCOPE_COMM, r.0 as u64),
+        };
+        (scope as OTF2_MarkerScope, scope_ref)
+    }
+}
+
+/// A `DefMarker` record: a kind of marker, identified by its group and category.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarkerDef {
+    pub defn: OTF2_MarkerRef,
+    pub group: String,
+    pub category: String,
+    pub severity: MarkerSeverity,
+}
+
+/// A `Marker` record: an annotation of an instant (`duration == 0`) or interval of the trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Marker {
+    pub time: OTF2_TimeStamp,
+    pub duration: OTF2_TimeStamp,
+    pub marker: OTF2_MarkerRef,
+    pub scope: MarkerScope,
+    pub text: String,
+}
+
+/// The contents of the marker file of a trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Markers {
+    pub definitions: Vec<MarkerDef>,
+    pub markers: Vec<Marker>,
+}
+
+impl Markers {
+    /// A marker reference not used by any of `definitions`, for defining a new kind of marker.
+    pub fn next_marker_ref(&self) -> OTF2_MarkerRef {
+        self.definitions.iter()
+            .map(|def| def.defn.0 + 1)
+            .max()
+            .map_or(OTF2_MarkerRef(0), OTF2_MarkerRef)
+    }
+
+    pub fn definition(&self, marker: OTF2_MarkerRef) -> Option<&MarkerDef> {
+        self.definitions.iter().find(|def| def.defn == marker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_round_trip() {
+        let scopes = [
+            MarkerScope::Global,
+            MarkerScope::Location(OTF2_LocationRef(u64::MAX - 1)),
+            MarkerScope::LocationGroup(OTF2_LocationGroupRef(3)),
+            MarkerScope::SystemTreeNode(OTF2_SystemTreeNodeRef(4)),
+            MarkerScope::Group(OTF2_GroupRef(5)),
+            MarkerScope::Comm(OTF2_CommRef(6)),
+        ];
+        for scope in scopes {
+            let (raw, scope_ref) = scope.to_raw();
+            assert_eq!(MarkerScope::from_raw(raw, scope_ref), Some(scope));
+        }
+        let (comm, _) = MarkerScope::Comm(OTF2_CommRef(0)).to_raw();
+        assert_eq!(MarkerScope::from_raw(comm, u64::MAX), None);
+    }
+
+    #[test]
+    fn next_marker_ref_is_unused() {
+        let mut markers = Markers::default();
+        assert_eq!(markers.next_marker_ref(), OTF2_MarkerRef(0));
+        for defn in [4, 1] {
+            markers.definitions.push(MarkerDef {
+                defn: OTF2_MarkerRef(defn),
+                group: "analysis".to_string(),
+                category: "late sender".to_string(),
+                severity: MarkerSeverity::High,
+            });
+        }
+        assert_eq!(markers.next_marker_ref(), OTF2_MarkerRef(5));
+        assert!(markers.definition(OTF2_MarkerRef(1)).is_some());
+    }
+}
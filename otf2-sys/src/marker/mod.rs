@@ -0,0 +1,5 @@
+mod marker_struct;
+mod reader_callbacks;
+
+pub use marker_struct::{Marker, MarkerDef, MarkerScope, MarkerSeverity, Markers};
+pub use reader_callbacks::MarkerReaderCallbacks;
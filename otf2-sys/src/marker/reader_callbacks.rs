@@ -0,0 +1,77 @@
+use crate::error::Status;
+use crate::internal::*;
+use std::ffi::CStr;
+
+use super::marker_struct::{Marker, MarkerDef, MarkerScope, MarkerSeverity, Markers};
+
+/// Safe wrapper around OTF2_MarkerReaderCallbacks
+///
+/// Registers callbacks for reading the marker file of an OTF2 trace. These callbacks expect a
+/// mutable `Markers` which will store the definitions and markers read.
+#[derive(Debug, derive_more::Deref, derive_more::DerefMut)]
+pub struct MarkerReaderCallbacks(Handle<OTF2_MarkerReaderCallbacks_struct>);
+
+impl core::ops::Drop for MarkerReaderCallbacks {
+    fn drop(&mut self) {
+        if !self.is_null() {
+            unsafe { OTF2_MarkerReaderCallbacks_Delete(self.take()) };
+        }
+    }
+}
+
+impl MarkerReaderCallbacks {
+    pub fn new() -> Status<Self> {
+        let mut this = Self(Handle::from_raw(unsafe { OTF2_MarkerReaderCallbacks_New() })
+            .expect("Failed to create MarkerReaderCallbacks: null pointer"));
+        this.set_callbacks()?;
+        Ok(this)
+    }
+
+    fn set_callbacks(&mut self) -> Status<()> {
+        use marker_callbacks::*;
+        let cbs = self.as_mut_ptr();
+        unsafe {
+            OTF2_MarkerReaderCallbacks_SetDefMarkerCallback(cbs, Some(read_def_marker))?;
+            OTF2_MarkerReaderCallbacks_SetMarkerCallback(cbs, Some(read_marker))?;
+        }
+        Ok(())
+    }
+}
+
+mod marker_callbacks {
+    use super::*;
+    use std::os::raw::{c_char, c_void};
+
+    #[inline]
+    fn as_markers<'a>(data: *mut c_void) -> &'a mut Markers {
+        assert!(!data.is_null(), "callback user data must not be null pointer");
+        unsafe { &mut *(data as *mut _) }
+    }
+
+    #[inline]
+    unsafe fn to_string(value: *const c_char) -> String {
+        if value.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(value) }.to_string_lossy().into_owned()
+        }
+    }
+
+    pub unsafe extern "C" fn read_def_marker(user_data: *mut c_void, defn: OTF2_MarkerRef, group: *const c_char, category: *const c_char, severity: OTF2_MarkerSeverity) -> OTF2_CallbackCode {
+        let Some(severity) = MarkerSeverity::from_raw(severity) else {
+            return OTF2_CallbackCode::OTF2_CALLBACK_ERROR;
+        };
+        let (group, category) = unsafe { (to_string(group), to_string(category)) };
+        as_markers(user_data).definitions.push(MarkerDef { defn, group, category, severity });
+        OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
+    }
+
+    pub unsafe extern "C" fn read_marker(user_data: *mut c_void, time: OTF2_TimeStamp, duration: OTF2_TimeStamp, marker: OTF2_MarkerRef, scope: OTF2_MarkerScope, scope_ref: u64, text: *const c_char) -> OTF2_CallbackCode {
+        let Some(scope) = MarkerScope::from_raw(scope, scope_ref) else {
+            return OTF2_CallbackCode::OTF2_CALLBACK_ERROR;
+        };
+        let text = unsafe { to_string(text) };
+        as_markers(user_data).markers.push(Marker { time, duration, marker, scope, text });
+        OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
+    }
+}
@@ -0,0 +1,71 @@
+//! Options applied when opening a trace for reading.
+
+use derive_builder::Builder;
+
+/// Configuration of a [`Trace`](crate::reader::Trace), passed to
+/// [`open_with_options`](crate::reader::open_with_options).
+///
+/// ```ignore
+/// let options = ReaderOptions::builder().global_reader_hint(true).batch_size(4096).build()?;
+/// ```
+///
+/// There are no buffer sizes to configure: a reader takes the event and definition chunk sizes
+/// recorded in the anchor file by the writer, see [`Archive`](crate::archive::Archive).
+#[derive(Debug, Clone, PartialEq, Eq, Builder)]
+#[builder(build_fn(validate = "Self::validate"))]
+pub struct ReaderOptions {
+    /// Set `OTF2_HINT_GLOBAL_READER`, telling OTF2 that events will only be read through the
+    /// global (merged) reader. This lets OTF2 skip work needed only for local readers.
+    #[builder(default = "false")]
+    pub global_reader_hint: bool,
+    /// The number of records to read from OTF2 at a time where a method doesn't take a batch size.
+    #[builder(default = "1024")]
+    pub batch_size: u64,
+    /// Read the local definitions of the selected locations before reading their events or
+    /// snapshots. These carry the mappings from local to global references, so turn this off
+    /// only for traces known to have no mappings.
+    #[builder(default = "true")]
+    pub read_local_definitions: bool,
+}
+
+impl ReaderOptions {
+    pub fn builder() -> ReaderOptionsBuilder {
+        ReaderOptionsBuilder::default()
+    }
+}
+
+impl Default for ReaderOptions {
+    fn default() -> Self {
+        Self::builder().build().expect("every option has a default")
+    }
+}
+
+impl ReaderOptionsBuilder {
+    fn validate(&self) -> Result<(), String> {
+        match self.batch_size {
+            Some(0) => Err("batch_size must be greater than 0".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let options = ReaderOptions::default();
+        assert!(!options.global_reader_hint);
+        assert!(options.read_local_definitions);
+        assert_eq!(options.batch_size, 1024);
+    }
+
+    #[test]
+    fn builder_sets_and_validates() {
+        let options = ReaderOptions::builder().global_reader_hint(true).read_local_definitions(false).build().unwrap();
+        assert!(options.global_reader_hint);
+        assert!(!options.read_local_definitions);
+        assert!(ReaderOptions::builder().batch_size(0).build().is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Progress reporting and cancellation for long reads.
+
+use crate::internal::*;
+use crate::event::Event;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Cancels a read from another thread, e.g. a GUI's cancel button.
+///
+/// Once cancelled, the next event callback returns `OTF2_CALLBACK_INTERRUPT` and the iterator
+/// yields a single error for which [`StatusCode::is_interrupted`](crate::error::StatusCode::is_interrupted)
+/// is true, then ends.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a read has got, out of the event counts in the location definitions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Progress {
+    /// Records read by OTF2, including any skipped by an event kind filter.
+    pub events_read: u64,
+    pub total_events: u64,
+    /// Locations all of whose events have been decoded. Events skipped by an event kind filter
+    /// aren't seen, so with a filter every location is counted as done only once the read ends.
+    pub locations_done: usize,
+    pub total_locations: usize,
+}
+
+impl Progress {
+    /// Fraction of events read, between 0 and 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total_events == 0 {
+            1.0
+        } else {
+            (self.events_read as f64 / self.total_events as f64).min(1.0)
+        }
+    }
+}
+
+pub(crate) type ProgressCallback = Box<dyn FnMut(&Progress) + Send>;
+
+pub(crate) struct ProgressTracker {
+    progress: Progress,
+    remaining: BTreeMap<OTF2_LocationRef, u64>,
+    callback: ProgressCallback,
+}
+
+impl std::fmt::Debug for ProgressTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressTracker")
+            .field("progress", &self.progress)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProgressTracker {
+    /// `num_events` gives the number of events of each location being read.
+    pub(crate) fn new(num_events: impl IntoIterator<Item = (OTF2_LocationRef, u64)>, callback: ProgressCallback) -> Self {
+        let remaining: BTreeMap<_, _> = num_events.into_iter().collect();
+        let progress = Progress {
+            events_read: 0,
+            total_events: remaining.values().sum(),
+            locations_done: remaining.values().filter(|&&n| n == 0).count(),
+            total_locations: remaining.len(),
+        };
+        Self { progress, remaining, callback }
+    }
+
+    /// Record a batch of `records_read` records which decoded to `events`, then report progress.
+    /// A batch of no records ends the read, which finishes every location.
+    pub(crate) fn update<'e>(&mut self, records_read: u64, events: impl IntoIterator<Item = &'e Event>) {
+        self.progress.events_read += records_read;
+        if records_read == 0 {
+            self.remaining.clear();
+            self.progress.locations_done = self.progress.total_locations;
+        }
+        for event in events {
+            if let Some(remaining) = self.remaining.get_mut(&event.data.location) {
+                if *remaining == 1 {
+                    self.progress.locations_done += 1;
+                }
+                *remaining = remaining.saturating_sub(1);
+            }
+        }
+        (self.callback)(&self.progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribute::Attributes;
+    use crate::event::EventKind;
+    use std::sync::Mutex;
+
+    #[test]
+    fn tracker_counts_done_locations() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let mut tracker = ProgressTracker::new(
+            [(OTF2_LocationRef(0), 2), (OTF2_LocationRef(1), 1), (OTF2_LocationRef(2), 0)],
+            Box::new(move |progress| sink.lock().unwrap().push(progress.clone())),
+        );
+        let leave = |location| Event::new(OTF2_LocationRef(location), 0, Attributes::default(), EventKind::Leave { region: OTF2_RegionRef(0) });
+        tracker.update(2, &[leave(0), leave(1)]);
+        tracker.update(1, &[leave(0)]);
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports[0], Progress { events_read: 2, total_events: 3, locations_done: 2, total_locations: 3 });
+        assert_eq!(reports[1].locations_done, 3);
+        assert_eq!(reports[1].fraction(), 1.0);
+    }
+
+    #[test]
+    fn the_end_of_a_filtered_read_finishes_every_location() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let mut tracker = ProgressTracker::new(
+            [(OTF2_LocationRef(0), 2), (OTF2_LocationRef(1), 2)],
+            Box::new(move |progress| sink.lock().unwrap().push(progress.locations_done)),
+        );
+        // Only the enter events pass the filter
+        let enter = |location| Event::new(OTF2_LocationRef(location), 0, Attributes::default(), EventKind::Enter { region: OTF2_RegionRef(0) });
+        tracker.update(4, &[enter(0), enter(1)]);
+        tracker.update(0, &[]);
+        assert_eq!(*reports.lock().unwrap(), vec![0, 2]);
+    }
+
+    #[test]
+    fn cancellation_is_shared_between_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+}
@@ -5,14 +5,23 @@
 
 use crate::internal::*;
 use crate::error::{Status, StatusCode};
-use crate::event::{Event, EventKind, GlobalEvtReaderCallbacks};
-use crate::definition::{GlobalDefReaderCallbacks, Definition, DefinitionVisitor, LocationRegistry};
+use crate::event::{Event, EventKind, EventKindId, EventKindSet, EventQueue, EventVisitor, GlobalEvtReaderCallbacks};
+use crate::progress::{CancellationToken, Progress, ProgressTracker};
+use crate::collective::{RankContext, ThreadCommunicator, THREAD_COLLECTIVE_CALLBACKS};
+use crate::locking::MUTEX_LOCKING_CALLBACKS;
+use crate::info::TraceInfo;
+use crate::options::ReaderOptions;
+use crate::snapshot::{snapshot_start_times, GlobalSnapReaderCallbacks, SnapshotRecord};
+use crate::marker::{Marker, MarkerDef, MarkerReaderCallbacks, Markers};
+use crate::thumbnail::{Thumbnail, ThumbnailGenerator, ThumbnailHeader, ThumbnailKind, ThumbnailSample};
+use crate::definition::{AttributeResolver, GlobalDefReaderCallbacks, Definition, DefinitionSet, DefinitionVisitor, LocationRegistry};
 use std::ffi::{CString, CStr};
 use std::ops::ControlFlow;
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, VecDeque};
 
 use OTF2_ErrorCode::*;
 
+#[derive(Debug)]
 struct LocalEvtFiles<'r> {
     reader: &'r mut Trace,
 }
@@ -37,6 +46,30 @@ impl<'r> LocalEvtFiles<'r> {
     }
 }
 
+struct LocalSnapFiles<'r> {
+    reader: &'r mut Trace,
+}
+
+impl core::ops::Drop for LocalSnapFiles<'_> {
+    fn drop(&mut self) {
+        let _ = unsafe { OTF2_Reader_CloseSnapFiles(self.reader.handle.as_mut_ptr()) };
+    }
+}
+
+impl<'r> LocalSnapFiles<'r> {
+    fn open(reader: &'r mut Trace) -> Status<Self> {
+        unsafe { OTF2_Reader_OpenSnapFiles(reader.handle.as_mut_ptr()) }?;
+        Ok(LocalSnapFiles { reader })
+    }
+
+    fn select_local_snap_readers(&mut self, locations: &[OTF2_LocationRef]) {
+        // NOTE: as for event readers, the local snap readers must exist before the global snap reader is created.
+        for &location in locations {
+            unsafe { OTF2_Reader_GetSnapReader(self.reader.handle.as_mut_ptr(), location) };
+        }
+    }
+}
+
 struct LocalDefFiles<'r> {
     reader: &'r mut Trace,
 }
@@ -135,11 +168,185 @@ impl<'r> GlobalDefReader<'r> {
     }
 }
 
+struct MarkerReader<'r> {
+    reader: &'r mut Trace,
+    handle: Handle<OTF2_MarkerReader>,
+}
+
+impl core::ops::Drop for MarkerReader<'_> {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            let _ = unsafe { OTF2_Reader_CloseMarkerReader(self.reader.handle.as_mut_ptr(), self.handle.take()) };
+        }
+    }
+}
+
+impl<'r> MarkerReader<'r> {
+    /// `None` if the trace has no marker file.
+    fn new(reader: &'r mut Trace) -> Option<Self> {
+        let handle = Handle::from_raw(unsafe { OTF2_Reader_GetMarkerReader(reader.handle.as_mut_ptr()) })?;
+        Some(MarkerReader { reader, handle })
+    }
+
+    fn read_markers(mut self, callbacks: &MarkerReaderCallbacks) -> Status<Markers> {
+        let mut markers = Markers::default();
+        let mut markers_read = 0;
+        unsafe {
+            OTF2_Reader_RegisterMarkerCallbacks(
+                self.reader.handle.as_mut_ptr(),
+                self.handle.as_mut_ptr(),
+                callbacks.as_ptr(),
+                &mut markers as *mut Markers as *mut _,
+            )?;
+            OTF2_Reader_ReadAllMarkers(self.reader.handle.as_mut_ptr(), self.handle.as_mut_ptr(), &mut markers_read)?;
+        }
+        Ok(markers)
+    }
+}
+
+/// Writes marker definitions and markers to the marker file of an existing trace.
+///
+/// The markers are written when the writer is dropped. OTF2 doesn't merge them with the markers
+/// already in the trace, so use [`Trace::append_markers`] to keep those.
+#[derive(Debug)]
+pub struct MarkerWriter<'r> {
+    reader: &'r mut Trace,
+    handle: Handle<OTF2_MarkerWriter>,
+}
+
+// SAFETY: as for `Trace`, which this borrows exclusively
+unsafe impl Send for MarkerWriter<'_> {}
+
+impl core::ops::Drop for MarkerWriter<'_> {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            let _ = unsafe { OTF2_Reader_CloseMarkerWriter(self.reader.handle.as_mut_ptr(), self.handle.take()) };
+        }
+    }
+}
+
+impl<'r> MarkerWriter<'r> {
+    fn new(reader: &'r mut Trace) -> Status<Self> {
+        let handle = Handle::from_raw(unsafe { OTF2_Reader_GetMarkerWriter(reader.handle.as_mut_ptr()) })
+            .ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
+        Ok(MarkerWriter { reader, handle })
+    }
+
+    pub fn write_definition(&mut self, def: &MarkerDef) -> Status<()> {
+        let group = to_c_string(&def.group)?;
+        let category = to_c_string(&def.category)?;
+        unsafe { OTF2_MarkerWriter_WriteDefMarker(self.handle.as_mut_ptr(), def.defn, group.as_ptr(), category.as_ptr(), def.severity.to_raw()) }?;
+        Ok(())
+    }
+
+    pub fn write_marker(&mut self, marker: &Marker) -> Status<()> {
+        let text = to_c_string(&marker.text)?;
+        let (scope, scope_ref) = marker.scope.to_raw();
+        unsafe { OTF2_MarkerWriter_WriteMarker(self.handle.as_mut_ptr(), marker.time, marker.duration, marker.marker, scope, scope_ref, text.as_ptr()) }?;
+        Ok(())
+    }
+
+    /// Write all definitions, then all markers.
+    pub fn write_all(&mut self, markers: &Markers) -> Status<()> {
+        for def in &markers.definitions {
+            self.write_definition(def)?;
+        }
+        for marker in &markers.markers {
+            self.write_marker(marker)?;
+        }
+        Ok(())
+    }
+}
+
+fn to_c_string(value: &str) -> Status<CString> {
+    CString::new(value).map_err(|_| StatusCode::from_raw(OTF2_ERROR_INVALID_ARGUMENT))
+}
+
+unsafe extern "C" {
+    // OTF2 allocates the strings and references of a thumbnail header with malloc
+    fn free(ptr: *mut std::os::raw::c_void);
+}
+
+/// Copy a string which OTF2 allocated with malloc, then free it.
+unsafe fn take_string(ptr: *mut std::os::raw::c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let value = unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned();
+    unsafe { free(ptr as *mut _) };
+    value
+}
+
+struct ThumbReader<'r> {
+    reader: &'r mut Trace,
+    handle: Handle<OTF2_ThumbReader>,
+}
+
+impl core::ops::Drop for ThumbReader<'_> {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            let _ = unsafe { OTF2_Reader_CloseThumbReader(self.reader.handle.as_mut_ptr(), self.handle.take()) };
+        }
+    }
+}
+
+impl<'r> ThumbReader<'r> {
+    fn new(reader: &'r mut Trace, number: u32) -> Status<Self> {
+        let handle = Handle::from_raw(unsafe { OTF2_Reader_GetThumbReader(reader.handle.as_mut_ptr(), number) })
+            .ok_or(StatusCode::from_raw(OTF2_ERROR_INDEX_OUT_OF_BOUNDS))?;
+        Ok(ThumbReader { reader, handle })
+    }
+
+    fn read_header(&mut self) -> Status<ThumbnailHeader> {
+        let mut name = std::ptr::null_mut();
+        let mut description = std::ptr::null_mut();
+        let mut kind = OTF2_ThumbnailType(0);
+        let mut number_of_samples = 0;
+        let mut number_of_metrics = 0;
+        let mut refs_to_defs = std::ptr::null_mut();
+        unsafe {
+            OTF2_ThumbReader_GetHeader(self.handle.as_mut_ptr(), &mut name, &mut description, &mut kind, &mut number_of_samples, &mut number_of_metrics, &mut refs_to_defs)?;
+        }
+        let (name, description) = unsafe { (take_string(name), take_string(description)) };
+        let refs = if refs_to_defs.is_null() {
+            Vec::new()
+        } else {
+            let refs = unsafe { slice_from_raw!(refs_to_defs, number_of_metrics) }.to_vec();
+            unsafe { free(refs_to_defs as *mut _) };
+            refs
+        };
+        let kind = ThumbnailKind::from_raw(kind).ok_or(StatusCode::from_raw(OTF2_ERROR_INVALID_DATA))?;
+        Ok(ThumbnailHeader { name, description, kind, number_of_samples, refs_to_defs: refs })
+    }
+
+    fn read_thumbnail(mut self) -> Status<Thumbnail> {
+        let header = self.read_header()?;
+        let samples = (0..header.number_of_samples)
+            .map(|_| {
+                let mut baseline = 0;
+                let mut values = vec![0; header.refs_to_defs.len()];
+                unsafe { OTF2_ThumbReader_ReadSample(self.handle.as_mut_ptr(), &mut baseline, header.number_of_metrics(), values.as_mut_ptr()) }?;
+                Ok(ThumbnailSample { baseline, values })
+            })
+            .collect::<Status<_>>()?;
+        Ok(Thumbnail { header, samples })
+    }
+}
+
 #[derive(Debug)]
 pub struct Trace {
     handle: Handle<OTF2_Reader>,
+    // OTF2 keeps a pointer to this context, so it must outlive the reader
+    collective: Option<Box<RankContext>>,
+    options: ReaderOptions,
 }
 
+// SAFETY: OTF2 objects aren't tied to the thread which created them, and every reader is opened
+// with `MUTEX_LOCKING_CALLBACKS` so OTF2 guards its own shared state. All access to OTF2 goes
+// through `&mut self`, so a shared `&Trace` can't touch the reader.
+unsafe impl Send for Trace {}
+unsafe impl Sync for Trace {}
+
 impl core::ops::Drop for Trace {
     fn drop(&mut self) {
         unsafe {
@@ -149,19 +356,88 @@ impl core::ops::Drop for Trace {
 }
 
 impl Trace {
+    /// Open the reader and apply everything which OTF2 needs before the collective callbacks.
+    fn open_reader(anchor_file: &CStr, options: ReaderOptions, collective: Option<Box<RankContext>>) -> Status<Self> {
+        let handle = Handle::from_raw(unsafe { OTF2_Reader_Open(anchor_file.as_ptr()) }).ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
+        let mut trace = Trace { handle, collective, options };
+        unsafe { OTF2_Reader_SetLockingCallbacks(trace.handle.as_mut_ptr(), &MUTEX_LOCKING_CALLBACKS, std::ptr::null_mut()) }?;
+        if trace.options.global_reader_hint {
+            let mut value: OTF2_Boolean = OTF2_TRUE;
+            unsafe {
+                OTF2_Reader_SetHint(trace.handle.as_mut_ptr(), OTF2_Hint_enum::OTF2_HINT_GLOBAL_READER as OTF2_Hint, &mut value as *mut OTF2_Boolean as *mut _)
+            }?;
+        }
+        Ok(trace)
+    }
+
+    pub fn options(&self) -> &ReaderOptions {
+        &self.options
+    }
+
+    /// Read the properties of the trace recorded in its anchor file.
+    pub fn info(&mut self) -> Status<TraceInfo> {
+        let reader = self.handle.as_mut_ptr();
+        let mut info = TraceInfo::default();
+        let (mut creator, mut description, mut machine_name) = (std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null_mut());
+        let (mut number_of_properties, mut names) = (0, std::ptr::null_mut());
+        unsafe {
+            let (major, minor, bugfix) = &mut info.version;
+            OTF2_Reader_GetVersion(reader, major, minor, bugfix)?;
+            OTF2_Reader_GetTraceId(reader, &mut info.trace_id)?;
+            OTF2_Reader_GetNumberOfLocations(reader, &mut info.number_of_locations)?;
+            OTF2_Reader_GetCreator(reader, &mut creator)?;
+            info.creator = take_string(creator);
+            OTF2_Reader_GetDescription(reader, &mut description)?;
+            info.description = take_string(description);
+            OTF2_Reader_GetMachineName(reader, &mut machine_name)?;
+            info.machine_name = take_string(machine_name);
+            OTF2_Reader_GetPropertyNames(reader, &mut number_of_properties, &mut names)?;
+        }
+        if !names.is_null() {
+            // The names are stored in the same allocation as the array, so only it is freed
+            let properties = unsafe { slice_from_raw!(names, number_of_properties) }.iter()
+                .map(|&name| {
+                    let mut value = std::ptr::null_mut();
+                    unsafe { OTF2_Reader_GetProperty(reader, name, &mut value) }?;
+                    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned();
+                    Ok((name, unsafe { take_string(value) }))
+                })
+                .collect::<Status<_>>();
+            unsafe { free(names as *mut _) };
+            info.properties = properties?;
+        }
+        Ok(info)
+    }
+
     pub fn read_definitions(&mut self) -> Status<(u64, Vec<Definition>)> {
         let mut callbacks = GlobalDefReaderCallbacks::new()?;
         GlobalDefReader::new(self)?.read_global_definitions(&mut callbacks)
     }
 
-    pub fn get_event_reader<'r>(&'r mut self, batch_size: u64) -> Status<EventReader<'r>> {
+    /// Read the global definitions into a resolver for the attributes of events.
+    pub fn attribute_resolver(&mut self) -> Status<AttributeResolver> {
         let (_, definitions) = self.read_definitions()?;
-        let locations = definitions.iter()
+        Ok(AttributeResolver::from_definitions(&definitions))
+    }
+
+    /// Read the global definitions, indexed by reference.
+    pub fn definition_set(&mut self) -> Status<DefinitionSet> {
+        let (_, definitions) = self.read_definitions()?;
+        Ok(definitions.into())
+    }
+
+    fn all_locations(&mut self) -> Status<Vec<OTF2_LocationRef>> {
+        let (_, definitions) = self.read_definitions()?;
+        Ok(definitions.iter()
             .filter_map(|def| match def {
                 Definition::Location { defn, value: _ } => Some(*defn),
                 _ => None,
             })
-            .collect();
+            .collect())
+    }
+
+    pub fn get_event_reader<'r>(&'r mut self, batch_size: u64) -> Status<EventReader<'r>> {
+        let locations = self.all_locations()?;
         self.get_local_event_reader(locations, batch_size)
     }
 
@@ -169,15 +445,154 @@ impl Trace {
         EventReader::new(self, locations, batch_size)
     }
 
+    /// For a trace opened with [`open_collective`], get an event reader for this thread's share of
+    /// the locations. Otherwise the same as [`Trace::get_event_reader`].
+    pub fn get_collective_event_reader<'r>(&'r mut self, batch_size: u64) -> Status<EventReader<'r>> {
+        let mut locations = self.all_locations()?;
+        if let Some(context) = &self.collective {
+            locations = context.split_locations(&locations);
+        }
+        self.get_local_event_reader(locations, batch_size)
+    }
+
+    /// Open the event files to read each of `locations` on its own, e.g. one share of them per
+    /// thread. See [`LocalEventReaders`].
+    pub fn local_event_readers(&mut self, locations: &[OTF2_LocationRef]) -> Status<LocalEventReaders<'_>> {
+        LocalEventReaders::new(self, locations)
+    }
+
     pub fn iter_events<'r>(&'r mut self, batch_size: u64) -> Status<EventIter<'r>> {
         self.get_event_reader(batch_size)?.into_event_iter()
     }
+
+    /// Iterate over only those events whose kind is in `kinds`. Other records are skipped by OTF2.
+    pub fn iter_events_of_kinds<'r>(&'r mut self, batch_size: u64, kinds: EventKindSet) -> Status<EventIter<'r>> {
+        self.get_event_reader(batch_size)?.with_event_kinds(kinds).into_event_iter()
+    }
+
+    /// Hand every event to `visitor` as it is read. See [`EventReader::visit`].
+    pub fn visit_events<V: EventVisitor>(&mut self, batch_size: u64, visitor: &mut V) -> Status<u64> {
+        self.get_event_reader(batch_size)?.visit(visitor)
+    }
+
+    /// The number of snapshots recorded in the trace, according to the anchor file.
+    pub fn number_of_snapshots(&mut self) -> Status<u32> {
+        let mut number_of_snapshots = 0;
+        unsafe { OTF2_Reader_GetNumberOfSnapshots(self.handle.as_mut_ptr(), &mut number_of_snapshots) }?;
+        Ok(number_of_snapshots)
+    }
+
+    pub fn get_snapshot_reader<'r>(&'r mut self, batch_size: u64) -> Status<SnapshotReader<'r>> {
+        let locations = self.all_locations()?;
+        self.get_local_snapshot_reader(locations, batch_size)
+    }
+
+    pub fn get_local_snapshot_reader<'r>(&'r mut self, locations: Vec<OTF2_LocationRef>, batch_size: u64) -> Status<SnapshotReader<'r>> {
+        SnapshotReader::new(self, locations, batch_size)
+    }
+
+    pub fn iter_snapshots<'r>(&'r mut self, batch_size: u64) -> Status<SnapshotIter<'r>> {
+        self.get_snapshot_reader(batch_size)?.into_snapshot_iter()
+    }
+
+    /// The distinct times at which snapshots were taken, in ascending order.
+    ///
+    /// Each of these is the `snap_time` of a `SnapshotStart` record. The records between a
+    /// `SnapshotStart` and its `SnapshotEnd` describe the state of a location at that time, so
+    /// e.g. its `EnterSnap` records give the call stack to resume from.
+    pub fn snapshot_timestamps(&mut self) -> Status<Vec<OTF2_TimeStamp>> {
+        snapshot_start_times(self.iter_snapshots(self.options.batch_size)?)
+    }
+}
+
+impl Trace {
+    /// Read the marker definitions and markers of the trace. Empty if it has no marker file.
+    pub fn markers(&mut self) -> Status<Markers> {
+        let callbacks = MarkerReaderCallbacks::new()?;
+        match MarkerReader::new(self) {
+            Some(marker_reader) => marker_reader.read_markers(&callbacks),
+            None => Ok(Markers::default()),
+        }
+    }
+
+    /// Get a writer for the marker file. Markers already in the trace are replaced.
+    pub fn marker_writer(&mut self) -> Status<MarkerWriter<'_>> {
+        MarkerWriter::new(self)
+    }
+
+    /// Add `markers` to those already in the trace.
+    ///
+    /// New definitions must not reuse a marker reference of an existing one, see
+    /// [`Markers::next_marker_ref`].
+    pub fn append_markers(&mut self, markers: &Markers) -> Status<()> {
+        let existing = self.markers()?;
+        let mut writer = self.marker_writer()?;
+        writer.write_all(&existing)?;
+        writer.write_all(markers)
+    }
+}
+
+impl Trace {
+    pub fn number_of_thumbnails(&mut self) -> Status<u32> {
+        let mut number_of_thumbnails = 0;
+        unsafe { OTF2_Reader_GetNumberOfThumbnails(self.handle.as_mut_ptr(), &mut number_of_thumbnails) }?;
+        Ok(number_of_thumbnails)
+    }
+
+    pub fn read_thumbnail(&mut self, number: u32) -> Status<Thumbnail> {
+        ThumbReader::new(self, number)?.read_thumbnail()
+    }
+
+    /// Read every thumbnail in the trace. This doesn't read any events.
+    pub fn thumbnails(&mut self) -> Status<Vec<Thumbnail>> {
+        (0..self.number_of_thumbnails()?)
+            .map(|number| self.read_thumbnail(number))
+            .collect()
+    }
+
+    /// Compute a region and a metric thumbnail with `bins` samples each from the events of the
+    /// trace. Write them with [`crate::archive::Archive::write_thumbnail`].
+    pub fn generate_thumbnails(&mut self, bins: u32) -> Status<[Thumbnail; 2]> {
+        let (_, definitions) = self.read_definitions()?;
+        let (start, length) = definitions.iter()
+            .find_map(|def| match def {
+                Definition::ClockProperties { value } => Some((value.global_offset, value.trace_length)),
+                _ => None,
+            })
+            .ok_or(StatusCode::from_raw(OTF2_ERROR_INVALID_DATA))?;
+        let mut generator = ThumbnailGenerator::new(start, start + length.max(1), bins);
+        let kinds = EventKindSet::REGIONS.with(EventKindId::Metric);
+        for event in self.iter_events_of_kinds(self.options.batch_size, kinds)? {
+            generator.add_event(&event?);
+        }
+        Ok([
+            generator.region_thumbnail("Regions", "Exclusive time per region"),
+            generator.metric_thumbnail("Metrics", "Maximum value per metric"),
+        ])
+    }
 }
 
 pub fn open(anchor_file: CString) -> Status<Trace> {
-    let mut handle = Handle::from_raw(unsafe { OTF2_Reader_Open(anchor_file.as_ptr()) }).ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
-    unsafe { OTF2_Reader_SetSerialCollectiveCallbacks(handle.as_mut_ptr())}?;
-    Ok(Trace { handle })
+    open_with_options(anchor_file, ReaderOptions::default())
+}
+
+pub fn open_with_options(anchor_file: CString, options: ReaderOptions) -> Status<Trace> {
+    let mut trace = Trace::open_reader(&anchor_file, options, None)?;
+    unsafe { OTF2_Reader_SetSerialCollectiveCallbacks(trace.handle.as_mut_ptr())}?;
+    Ok(trace)
+}
+
+/// Open the trace as rank `rank` of `comm`.
+///
+/// Every rank must call this from its own thread, and the ranks must then make the same sequence
+/// of calls on their traces, as OTF2 synchronises them inside many reader operations.
+pub fn open_collective(anchor_file: CString, comm: &ThreadCommunicator, rank: u32, options: ReaderOptions) -> Status<Trace> {
+    let mut trace = Trace::open_reader(&anchor_file, options, Some(comm.context(rank)))?;
+    let context = trace.collective.as_mut().expect("set above").as_context_ptr();
+    unsafe {
+        OTF2_Reader_SetCollectiveCallbacks(trace.handle.as_mut_ptr(), &THREAD_COLLECTIVE_CALLBACKS, std::ptr::null_mut(), context, std::ptr::null_mut())
+    }?;
+    Ok(trace)
 }
 
 #[derive(Debug)]
@@ -186,8 +601,14 @@ pub struct EventReader<'r> {
     handle: Handle<OTF2_GlobalEvtReader>,
     locations: Vec<OTF2_LocationRef>,
     batch_size: u64,
+    kinds: EventKindSet,
+    progress: Option<ProgressTracker>,
+    cancel: Option<CancellationToken>,
 }
 
+// SAFETY: as for `Trace`, which this borrows exclusively
+unsafe impl Send for EventReader<'_> {}
+
 impl<'r> core::ops::Drop for EventReader<'r> {
     fn drop(&mut self) {
         if !self.handle.is_null() {
@@ -204,11 +625,61 @@ impl<'r> EventReader<'r> {
         for location in &locations {
             unsafe { OTF2_Reader_SelectLocation(reader.handle.as_mut_ptr(), *location) }?;
         }
-        LocalDefFiles::open(reader)?.read_local_definitions(&locations)?;
+        if reader.options.read_local_definitions {
+            LocalDefFiles::open(reader)?.read_local_definitions(&locations)?;
+        }
         LocalEvtFiles::open(reader)?.select_local_evt_readers(&locations);
         let global_evt_reader = Handle::from_raw(unsafe { OTF2_Reader_GetGlobalEvtReader(reader.handle.as_mut_ptr()) })
             .expect("failed to get global evt reader");
-        Ok(EventReader { reader, locations, batch_size, handle: global_evt_reader })
+        Ok(EventReader { reader, locations, batch_size, handle: global_evt_reader, kinds: EventKindSet::all(), progress: None, cancel: None })
+    }
+
+    /// Only decode events whose kind is in `kinds`.
+    pub fn with_event_kinds(mut self, kinds: EventKindSet) -> Self {
+        self.kinds = kinds;
+        self
+    }
+
+    /// Call `callback` after each batch of events is read. The totals come from the `num_events`
+    /// of the definitions of the selected locations.
+    pub fn with_progress(mut self, callback: impl FnMut(&Progress) + Send + 'static) -> Status<Self> {
+        let (_, definitions) = self.reader.read_definitions()?;
+        let num_events = definitions.iter()
+            .filter_map(|def| match def {
+                Definition::Location { defn, value } if self.locations.contains(defn) => Some((*defn, value.num_events)),
+                _ => None,
+            });
+        self.progress = Some(ProgressTracker::new(num_events, Box::new(callback)));
+        Ok(self)
+    }
+
+    /// Stop reading once `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Hand every event to `visitor` as it is read instead of queueing it, decoding attributes
+    /// only if the visitor asks for them. Returns the number of records read. Progress reports
+    /// count records, and only count locations as done once the read ends.
+    pub fn visit<V: EventVisitor>(mut self, visitor: &mut V) -> Status<u64> {
+        let callbacks = GlobalEvtReaderCallbacks::for_visitor::<V>(self.kinds)?;
+        unsafe { OTF2_GlobalEvtReader_SetCallbacks(self.handle.as_mut_ptr(), callbacks.as_ptr(), visitor as *mut V as *mut _) }?;
+        let mut total = 0;
+        loop {
+            if self.cancel.as_ref().is_some_and(CancellationToken::is_cancelled) {
+                return Err(StatusCode::interrupted());
+            }
+            let mut events_read = 0;
+            unsafe { OTF2_GlobalEvtReader_ReadEvents(self.handle.as_mut_ptr(), self.batch_size, &mut events_read) }?;
+            if let Some(progress) = self.progress.as_mut() {
+                progress.update(events_read, std::iter::empty::<&Event>());
+            }
+            if events_read == 0 {
+                return Ok(total);
+            }
+            total += events_read;
+        }
     }
 
     pub fn into_event_iter(self) -> Status<EventIter<'r>> {
@@ -223,42 +694,329 @@ pub struct EventIter<'r> {
     batch_size: u64,
     // The event queue is boxed to give it a persistent location in memory. Its address is passed
     // to OTF2_GlobalEvtReader_SetCallbacks during `new()` then used during `read_next_event_batch`
-    event_queue: Box<VecDeque<Event>>,
+    event_queue: Box<EventQueue>,
+    interrupted: bool,
 }
 
 impl<'r> std::iter::Iterator for EventIter<'r> {
     type Item = Status<Event>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.event_queue.is_empty() {
-            if let Err(code) = self.read_next_event_batch() {
-                return Some(Err(code))
+        let EventIter { evt_reader, batch_size, event_queue, interrupted } = self;
+        next_event(event_queue, interrupted, |queue| read_event_batch(evt_reader, *batch_size, queue))
+    }
+}
+
+/// Pop the next event of `queue`, calling `read_batch` to refill it while it is empty, until a
+/// batch reads no records.
+fn next_event(queue: &mut EventQueue, interrupted: &mut bool, mut read_batch: impl FnMut(&mut EventQueue) -> Status<u64>) -> Option<Status<Event>> {
+    if *interrupted {
+        return None;
+    }
+    // Records without a registered callback are read but not queued, so a batch may leave the
+    // queue empty before the end of the trace.
+    while queue.events.is_empty() {
+        match read_batch(queue) {
+            Ok(0) => return None,
+            Ok(_) => continue,
+            Err(code) => {
+                // A cancelled read ends after reporting the interruption once
+                if code.is_interrupted() {
+                    *interrupted = true;
+                    queue.events.clear();
+                }
+                return Some(Err(code));
             }
         }
-        self.event_queue.pop_front().map(Status::Ok)
     }
+    queue.events.pop_front().map(Status::Ok)
+}
+
+/// Read the next batch of up to `batch_size` records of `evt_reader` into `queue`, whose address
+/// was registered with its callbacks.
+fn read_event_batch(evt_reader: &mut EventReader, batch_size: u64, queue: &mut EventQueue) -> Status<u64> {
+    if queue.is_cancelled() {
+        return Err(StatusCode::interrupted());
+    }
+    let mut events_read = 0;
+    unsafe { OTF2_GlobalEvtReader_ReadEvents(evt_reader.handle.as_mut_ptr(), batch_size, &mut events_read) }?;
+    if let Some(progress) = evt_reader.progress.as_mut() {
+        progress.update(events_read, &queue.events);
+    }
+    Ok(events_read)
 }
 
 impl<'r> EventIter<'r> {
     fn new(mut evt_reader: EventReader<'r>, batch_size: u64) -> Status<Self> {
-        let mut event_queue = Box::new(VecDeque::with_capacity(batch_size as usize));
-        let callbacks = GlobalEvtReaderCallbacks::new()?;
+        let mut event_queue = Box::new(EventQueue::with_capacity(batch_size as usize));
+        event_queue.cancel = evt_reader.cancel.clone();
+        let callbacks = GlobalEvtReaderCallbacks::with_kinds(evt_reader.kinds)?;
         unsafe { OTF2_GlobalEvtReader_SetCallbacks(evt_reader.handle.as_mut_ptr(), callbacks.as_ptr(), event_queue.as_mut() as *const _ as *mut _) }?;
-        Ok(EventIter { evt_reader, batch_size, event_queue })
+        Ok(EventIter { evt_reader, batch_size, event_queue, interrupted: false })
+    }
+}
+
+/// The event files of a trace opened for reading locations independently, possibly from several
+/// threads at once. Each location's [`LocalEventReader`] can be taken once.
+#[derive(Debug)]
+pub struct LocalEventReaders<'r> {
+    files: LocalEvtFiles<'r>,
+    // The locations whose readers haven't been taken yet. OTF2 caches one reader per location,
+    // so handing out a location twice would share it between threads.
+    available: std::sync::Mutex<BTreeSet<OTF2_LocationRef>>,
+}
+
+impl<'r> LocalEventReaders<'r> {
+    fn new(reader: &'r mut Trace, locations: &[OTF2_LocationRef]) -> Status<Self> {
+        if reader.options.read_local_definitions {
+            LocalDefFiles::open(reader)?.read_local_definitions(locations)?;
+        }
+        let files = LocalEvtFiles::open(reader)?;
+        Ok(LocalEventReaders { files, available: std::sync::Mutex::new(locations.iter().copied().collect()) })
+    }
+
+    /// Take the reader of `location`, which must be one of the locations these were opened for
+    /// and not already taken.
+    pub fn reader(&self, location: OTF2_LocationRef) -> Status<LocalEventReader<'_>> {
+        let taken = self.available.lock().unwrap_or_else(std::sync::PoisonError::into_inner).remove(&location);
+        if !taken {
+            return Err(StatusCode::from_raw(OTF2_ERROR_INVALID_ARGUMENT));
+        }
+        // OTF2 only mutates the shared reader state under its locking callbacks
+        let reader = self.files.reader.handle.as_ptr() as *mut OTF2_Reader;
+        let handle = Handle::from_raw(unsafe { OTF2_Reader_GetEvtReader(reader, location) })
+            .ok_or(StatusCode::from_raw(OTF2_ERROR_MEM_ALLOC_FAILED))?;
+        Ok(LocalEventReader { reader, handle, location, files: std::marker::PhantomData })
+    }
+}
+
+/// The events of a single location, read without the merging of a global event reader.
+#[derive(Debug)]
+pub struct LocalEventReader<'f> {
+    reader: *mut OTF2_Reader,
+    handle: Handle<OTF2_EvtReader>,
+    location: OTF2_LocationRef,
+    files: std::marker::PhantomData<&'f LocalEventReaders<'f>>,
+}
+
+// SAFETY: no other reader is given this location's `OTF2_EvtReader`, and the `OTF2_Reader` it
+// shares with them is guarded by `MUTEX_LOCKING_CALLBACKS`
+unsafe impl Send for LocalEventReader<'_> {}
+
+impl core::ops::Drop for LocalEventReader<'_> {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            let _ = unsafe { OTF2_Reader_CloseEvtReader(self.reader, self.handle.take()) };
+        }
+    }
+}
+
+impl LocalEventReader<'_> {
+    pub fn location(&self) -> OTF2_LocationRef {
+        self.location
     }
 
-    fn read_next_event_batch(&mut self) -> Status<u64> {
+    /// Read every event of the location, returning the number of records read. No callbacks are
+    /// registered, so the records are only decoded and counted.
+    pub fn count_events(mut self) -> Status<u64> {
         let mut events_read = 0;
-        unsafe { OTF2_GlobalEvtReader_ReadEvents(self.evt_reader.handle.as_mut_ptr(), self.batch_size, &mut events_read) }?;
+        unsafe { OTF2_Reader_ReadAllLocalEvents(self.reader, self.handle.as_mut_ptr(), &mut events_read) }?;
         Ok(events_read)
     }
 }
 
+#[derive(Debug)]
+pub struct SnapshotReader<'r> {
+    reader: &'r mut Trace,
+    handle: Handle<OTF2_GlobalSnapReader>,
+    locations: Vec<OTF2_LocationRef>,
+    batch_size: u64,
+}
+
+// SAFETY: as for `Trace`, which this borrows exclusively
+unsafe impl Send for SnapshotReader<'_> {}
+
+impl<'r> core::ops::Drop for SnapshotReader<'r> {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            unsafe {
+                let _ = OTF2_Reader_CloseGlobalSnapReader(self.reader.handle.as_mut_ptr(), self.handle.take());
+            }
+        }
+    }
+}
+
+impl<'r> SnapshotReader<'r> {
+
+    pub fn new(reader: &'r mut Trace, locations: Vec<OTF2_LocationRef>, batch_size: u64) -> Status<Self> {
+        for location in &locations {
+            unsafe { OTF2_Reader_SelectLocation(reader.handle.as_mut_ptr(), *location) }?;
+        }
+        if reader.options.read_local_definitions {
+            LocalDefFiles::open(reader)?.read_local_definitions(&locations)?;
+        }
+        LocalSnapFiles::open(reader)?.select_local_snap_readers(&locations);
+        let global_snap_reader = Handle::from_raw(unsafe { OTF2_Reader_GetGlobalSnapReader(reader.handle.as_mut_ptr()) })
+            .expect("failed to get global snap reader");
+        Ok(SnapshotReader { reader, locations, batch_size, handle: global_snap_reader })
+    }
+
+    pub fn into_snapshot_iter(self) -> Status<SnapshotIter<'r>> {
+        let batch_size = self.batch_size;
+        SnapshotIter::new(self, batch_size)
+    }
+}
+
+#[derive(Debug)]
+pub struct SnapshotIter<'r> {
+    snap_reader: SnapshotReader<'r>,
+    batch_size: u64,
+    // Boxed for the same reason as `EventIter::event_queue`
+    #[allow(clippy::box_collection)]
+    snapshot_queue: Box<VecDeque<SnapshotRecord>>,
+}
+
+impl<'r> std::iter::Iterator for SnapshotIter<'r> {
+    type Item = Status<SnapshotRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.snapshot_queue.is_empty() {
+            match self.read_next_snapshot_batch() {
+                Ok(0) => return None,
+                Ok(_) => continue,
+                Err(code) => return Some(Err(code)),
+            }
+        }
+        self.snapshot_queue.pop_front().map(Status::Ok)
+    }
+}
+
+impl<'r> SnapshotIter<'r> {
+    fn new(mut snap_reader: SnapshotReader<'r>, batch_size: u64) -> Status<Self> {
+        let mut snapshot_queue = Box::new(VecDeque::with_capacity(batch_size as usize));
+        let callbacks = GlobalSnapReaderCallbacks::new()?;
+        unsafe { OTF2_GlobalSnapReader_SetCallbacks(snap_reader.handle.as_mut_ptr(), callbacks.as_ptr(), snapshot_queue.as_mut() as *const _ as *mut _) }?;
+        Ok(SnapshotIter { snap_reader, batch_size, snapshot_queue })
+    }
+
+    fn read_next_snapshot_batch(&mut self) -> Status<u64> {
+        let mut records_read = 0;
+        unsafe { OTF2_GlobalSnapReader_ReadSnapshots(self.snap_reader.handle.as_mut_ptr(), self.batch_size, &mut records_read) }?;
+        Ok(records_read)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use core::num;
 
     use super::*;
+    use crate::archive::fixtures::{write_trace, TempDir};
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn readers_can_move_between_threads() {
+        assert_send::<Trace>();
+        assert_sync::<Trace>();
+        assert_send::<EventReader>();
+        assert_send::<EventIter>();
+        assert_send::<SnapshotIter>();
+        assert_send::<MarkerWriter>();
+        assert_sync::<LocalEventReaders>();
+        assert_send::<LocalEventReader>();
+    }
+
+    #[test]
+    fn interrupted_reads_end_after_one_error() {
+        let event = |time| Event::new(OTF2_LocationRef(0), time, crate::attribute::Attributes::default(), EventKind::Enter { region: OTF2_RegionRef(0) });
+        let token = CancellationToken::new();
+        let mut queue = EventQueue::with_capacity(2);
+        queue.cancel = Some(token.clone());
+        let mut interrupted = false;
+        let mut batches = 0;
+        // Like OTF2, queue records until the callbacks see the cancellation, then interrupt
+        let mut read_batch = |queue: &mut EventQueue| {
+            batches += 1;
+            queue.events.push_back(event(2 * batches));
+            if queue.is_cancelled() {
+                return Err(StatusCode::interrupted());
+            }
+            queue.events.push_back(event(2 * batches + 1));
+            Ok(2)
+        };
+        {
+            let mut next = || next_event(&mut queue, &mut interrupted, &mut read_batch).map(|event| event.map(|event| event.data.time));
+            assert_eq!(next(), Some(Ok(2)));
+            token.cancel();
+            assert_eq!(next(), Some(Ok(3)));
+            let error = next().unwrap().unwrap_err();
+            assert!(error.is_interrupted(), "{error:?}");
+            // The event queued by the interrupted batch is dropped and nothing more is read
+            assert_eq!(next(), None);
+            assert_eq!(next(), None);
+        }
+        assert_eq!(batches, 2);
+    }
+
+    #[test]
+    fn reads_disjoint_locations_of_one_trace_concurrently() {
+        let num_events = [2, 4, 6, 8, 10];
+        let dir = TempDir::new("concurrent");
+        write_trace(&dir, "trace", &num_events).expect("Failed to write trace");
+        let mut trace = open(dir.anchor_file("trace")).unwrap();
+        let locations = trace.all_locations().expect("Failed to read locations");
+        let serial_count = count_events(trace.iter_events(1000).expect("Failed to get event iter"));
+        assert_eq!(serial_count as u64, num_events.iter().sum::<u64>());
+
+        let readers = trace.local_event_readers(&locations).expect("Failed to open event files");
+        let threads = 3;
+        let mut counts: Vec<(OTF2_LocationRef, u64)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|i| {
+                    let share: Vec<_> = locations.iter().copied().skip(i).step_by(threads).collect();
+                    let readers = &readers;
+                    scope.spawn(move || {
+                        share.into_iter()
+                            .map(|location| Ok((location, readers.reader(location)?.count_events()?)))
+                            .collect::<Status<Vec<_>>>()
+                            .expect("Failed to read events")
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+        });
+        counts.sort();
+        let expected: Vec<_> = num_events.iter().enumerate().map(|(location, &count)| (OTF2_LocationRef(location as u64), count)).collect();
+        assert_eq!(counts, expected);
+        // Each location is read once
+        assert!(readers.reader(locations[0]).is_err());
+        drop(readers);
+
+        // A trace opened on one thread can be read on another
+        let moved_count = std::thread::spawn(move || count_events(trace.iter_events(1000).unwrap())).join().unwrap();
+        assert_eq!(moved_count, serial_count);
+    }
+
+    #[test]
+    fn filtered_reads_report_every_location_done() {
+        let dir = TempDir::new("progress");
+        write_trace(&dir, "trace", &[2, 4]).expect("Failed to write trace");
+        let mut trace = open(dir.anchor_file("trace")).unwrap();
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let events = trace.get_event_reader(1)
+            .map(|reader| reader.with_event_kinds(EventKindSet::empty().with(EventKindId::Enter)))
+            .and_then(|reader| reader.with_progress(move |progress| sink.lock().unwrap().push(progress.clone())))
+            .and_then(EventReader::into_event_iter)
+            .expect("Failed to get event iter");
+        assert_eq!(count_events(events), 3);
+        let reports = reports.lock().unwrap();
+        let last = reports.last().expect("progress should be reported");
+        assert_eq!((last.events_read, last.total_events), (6, 6));
+        assert_eq!((last.locations_done, last.total_locations), (2, 2));
+    }
 
     use crate::definition::Definition;
     use crate::traits::AsJson;
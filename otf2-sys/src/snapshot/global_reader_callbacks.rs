@@ -0,0 +1,185 @@
+#![allow(unused_variables)]
+
+use crate::error::Status;
+use crate::internal::*;
+use crate::attribute::AttributeListRef;
+
+use super::snapshot_struct::{Snapshot, SnapshotRecord};
+
+use crate::macros::slice_from_raw;
+
+use std::collections::VecDeque;
+
+/// Safe wrapper around OTF2_GlobalSnapReaderCallbacks
+/// 
+/// Registers callbacks for reading snapshot records in OTF2 traces.
+#[derive(Debug, derive_more::Deref, derive_more::DerefMut)]
+pub struct GlobalSnapReaderCallbacks(Handle<OTF2_GlobalSnapReaderCallbacks_struct>);
+
+impl core::ops::Drop for GlobalSnapReaderCallbacks {
+    fn drop(&mut self) {
+        if !self.is_null() {
+            unsafe { OTF2_GlobalSnapReaderCallbacks_Delete(self.take()) };
+        }
+    }
+}
+
+macro_rules! set_callbacks {
+    ($handle:ident, $($setter:ident => $callback:ident),* $(,)?) => {
+        $( $setter(
+            $handle.as_mut_ptr(),
+            Some($callback),
+        )?;)*
+    }
+}
+
+impl GlobalSnapReaderCallbacks {
+    pub fn new() -> Status<Self> {
+        let mut this = Self(Handle::from_raw(unsafe { OTF2_GlobalSnapReaderCallbacks_New() })
+            .expect("Failed to create GlobalSnapReaderCallbacks: null pointer"));
+        this.set_callbacks()?;
+        Ok(this)
+    }
+
+    fn set_callbacks(&mut self) -> Status<()> {
+        use snapshot_queue_callbacks::*;
+        unsafe {
+            set_callbacks!(self,
+                OTF2_GlobalSnapReaderCallbacks_SetUnknownCallback => unknown,
+                OTF2_GlobalSnapReaderCallbacks_SetSnapshotStartCallback => snapshot_start,
+                OTF2_GlobalSnapReaderCallbacks_SetSnapshotEndCallback => snapshot_end,
+                OTF2_GlobalSnapReaderCallbacks_SetMeasurementOnOffSnapCallback => measurement_on_off_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetEnterSnapCallback => enter_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMpiSendSnapCallback => mpi_send_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMpiIsendSnapCallback => mpi_isend_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMpiIsendCompleteSnapCallback => mpi_isend_complete_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMpiRecvSnapCallback => mpi_recv_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMpiIrecvRequestSnapCallback => mpi_irecv_request_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMpiIrecvSnapCallback => mpi_irecv_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMpiCollectiveBeginSnapCallback => mpi_collective_begin_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMpiCollectiveEndSnapCallback => mpi_collective_end_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetOmpForkSnapCallback => omp_fork_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetOmpAcquireLockSnapCallback => omp_acquire_lock_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetOmpTaskCreateSnapCallback => omp_task_create_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetOmpTaskSwitchSnapCallback => omp_task_switch_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetMetricSnapCallback => metric_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetParameterStringSnapCallback => parameter_string_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetParameterIntSnapCallback => parameter_int_snap,
+                OTF2_GlobalSnapReaderCallbacks_SetParameterUnsignedIntSnapCallback => parameter_unsigned_int_snap,
+            );
+        }
+        Ok(())
+    }
+}
+
+mod snapshot_queue_callbacks {
+    use super::*;
+    use std::os::raw::c_void;
+
+    #[inline]
+    fn as_snapshot_queue<'a>(data: *mut c_void) -> &'a mut VecDeque<SnapshotRecord> {
+        assert!(!data.is_null(), "callback user data must not be null pointer");
+        unsafe { &mut *(data as *mut _) }
+    }
+
+    macro_rules! into_attributes {
+        ($list:ident) => {
+            // SAFETY: OTF2 keeps the attribute list alive until the callback returns
+            unsafe { AttributeListRef::from_raw($list) }.to_attributes()
+        }
+    }
+
+    macro_rules! push_snapshot {
+        ($queue:ident, $location:ident, $snap_time:ident, $attr:ident, $kind:expr) => {{
+            as_snapshot_queue($queue).push_back(SnapshotRecord::new($location, $snap_time, into_attributes!($attr), $kind));
+            OTF2_CallbackCode::OTF2_CALLBACK_SUCCESS
+        }}
+    }
+
+    pub unsafe extern "C" fn unknown(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::Unknown{})
+    }
+
+    pub unsafe extern "C" fn snapshot_start(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, number_of_records: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::SnapshotStart { number_of_records })
+    }
+
+    pub unsafe extern "C" fn snapshot_end(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, cont_read_pos: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::SnapshotEnd { cont_read_pos })
+    }
+
+    pub unsafe extern "C" fn measurement_on_off_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, measurement_mode: OTF2_MeasurementMode ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MeasurementOnOffSnap { orig_event_time, measurement_mode: measurement_mode.into() })
+    }
+
+    pub unsafe extern "C" fn enter_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, region: OTF2_RegionRef ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::EnterSnap { orig_event_time, region })
+    }
+
+    pub unsafe extern "C" fn mpi_send_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MpiSendSnap { orig_event_time, receiver, communicator, msg_tag, msg_length })
+    }
+
+    pub unsafe extern "C" fn mpi_isend_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64, request_id: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MpiIsendSnap { orig_event_time, receiver, communicator, msg_tag, msg_length, request_id })
+    }
+
+    pub unsafe extern "C" fn mpi_isend_complete_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, request_id: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MpiIsendCompleteSnap { orig_event_time, request_id })
+    }
+
+    pub unsafe extern "C" fn mpi_recv_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, sender: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MpiRecvSnap { orig_event_time, sender, communicator, msg_tag, msg_length })
+    }
+
+    pub unsafe extern "C" fn mpi_irecv_request_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, request_id: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MpiIrecvRequestSnap { orig_event_time, request_id })
+    }
+
+    pub unsafe extern "C" fn mpi_irecv_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, sender: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64, request_id: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MpiIrecvSnap { orig_event_time, sender, communicator, msg_tag, msg_length, request_id })
+    }
+
+    pub unsafe extern "C" fn mpi_collective_begin_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MpiCollectiveBeginSnap { orig_event_time })
+    }
+
+    pub unsafe extern "C" fn mpi_collective_end_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, collective_op: OTF2_CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MpiCollectiveEndSnap { orig_event_time, collective_op: collective_op.into(), communicator, root, size_sent, size_received })
+    }
+
+    pub unsafe extern "C" fn omp_fork_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, number_of_requested_threads: u32 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::OmpForkSnap { orig_event_time, number_of_requested_threads })
+    }
+
+    pub unsafe extern "C" fn omp_acquire_lock_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, lock_id: u32, acquisition_order: u32 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::OmpAcquireLockSnap { orig_event_time, lock_id, acquisition_order })
+    }
+
+    pub unsafe extern "C" fn omp_task_create_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, task_id: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::OmpTaskCreateSnap { orig_event_time, task_id })
+    }
+
+    pub unsafe extern "C" fn omp_task_switch_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, task_id: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::OmpTaskSwitchSnap { orig_event_time, task_id })
+    }
+
+    pub unsafe extern "C" fn metric_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, metric: OTF2_MetricRef, number_of_metrics: u8, type_ids: *const OTF2_Type, metric_values: *const OTF2_MetricValue ) -> OTF2_CallbackCode {
+        let types = unsafe { slice_from_raw!(type_ids, number_of_metrics) }.to_vec();
+        let raw_values = unsafe { slice_from_raw!(metric_values, number_of_metrics) }.to_vec();
+        let values = types.into_iter().zip(raw_values).map(|(t, v)| MetricValue::new(t, v)).collect();
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::MetricSnap { orig_event_time, metric, values })
+    }
+
+    pub unsafe extern "C" fn parameter_string_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, parameter: OTF2_ParameterRef, string: OTF2_StringRef ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::ParameterStringSnap { orig_event_time, parameter, string })
+    }
+
+    pub unsafe extern "C" fn parameter_int_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, parameter: OTF2_ParameterRef, value: i64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::ParameterIntSnap { orig_event_time, parameter, value })
+    }
+
+    pub unsafe extern "C" fn parameter_unsigned_int_snap(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, queue: *mut c_void, attributes: *mut OTF2_AttributeList, orig_event_time: OTF2_TimeStamp, parameter: OTF2_ParameterRef, value: u64 ) -> OTF2_CallbackCode {
+        push_snapshot!(queue, location, snap_time, attributes, Snapshot::ParameterUnsignedIntSnap { orig_event_time, parameter, value })
+    }
+}
@@ -0,0 +1,6 @@
+mod global_reader_callbacks;
+mod snapshot_struct;
+
+pub use snapshot_struct::{Snapshot, SnapshotData, SnapshotId, SnapshotRecord};
+pub(crate) use snapshot_struct::snapshot_start_times;
+pub use global_reader_callbacks::GlobalSnapReaderCallbacks;
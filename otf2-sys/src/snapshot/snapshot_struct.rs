@@ -0,0 +1,174 @@
+#![allow(unused_variables)]
+
+use crate::error::Status;
+use crate::internal::*;
+use crate::types::{CollectiveOp, MeasurementMode};
+use crate::attribute::Attributes;
+
+/// A single record read from the snapshot files of a trace.
+///
+/// All records between a `SnapshotStart` and the matching `SnapshotEnd` on a location describe the
+/// state of that location at `data.snap_time`. Each such record carries the time of the original
+/// event it summarises, so e.g. the `EnterSnap` records give the call stack in effect at that time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotRecord {
+    pub kind: Snapshot,
+    pub data: SnapshotData,
+}
+
+impl SnapshotRecord {
+    pub fn new(location: OTF2_LocationRef, snap_time: OTF2_TimeStamp, attributes: Attributes, kind: Snapshot) -> Self {
+        Self {
+            kind,
+            data: SnapshotData {
+                location,
+                snap_time,
+                attributes,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for SnapshotRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Snapshot {{ location: {} snap_time: {} kind: {} }}", self.data.location, self.data.snap_time, self.kind.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotData {
+    pub location: OTF2_LocationRef,
+    pub snap_time: OTF2_TimeStamp,
+    pub attributes: Attributes,
+}
+
+declare_named_enum!(
+    #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    // Variant names follow the OTF2 record names, e.g. `SnapshotStart`
+    #[allow(clippy::enum_variant_names)]
+    pub enum Snapshot: SnapshotId {
+        Unknown{},
+        SnapshotStart {number_of_records: u64},
+        SnapshotEnd {cont_read_pos: u64},
+        MeasurementOnOffSnap {orig_event_time: OTF2_TimeStamp, measurement_mode: MeasurementMode},
+        EnterSnap {orig_event_time: OTF2_TimeStamp, region: OTF2_RegionRef},
+        MpiSendSnap {orig_event_time: OTF2_TimeStamp, receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64},
+        MpiIsendSnap {orig_event_time: OTF2_TimeStamp, receiver: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64, request_id: u64},
+        MpiIsendCompleteSnap {orig_event_time: OTF2_TimeStamp, request_id: u64},
+        MpiRecvSnap {orig_event_time: OTF2_TimeStamp, sender: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64},
+        MpiIrecvRequestSnap {orig_event_time: OTF2_TimeStamp, request_id: u64},
+        MpiIrecvSnap {orig_event_time: OTF2_TimeStamp, sender: u32, communicator: OTF2_CommRef, msg_tag: u32, msg_length: u64, request_id: u64},
+        MpiCollectiveBeginSnap {orig_event_time: OTF2_TimeStamp},
+        MpiCollectiveEndSnap {orig_event_time: OTF2_TimeStamp, collective_op: CollectiveOp, communicator: OTF2_CommRef, root: u32, size_sent: u64, size_received: u64},
+        OmpForkSnap {orig_event_time: OTF2_TimeStamp, number_of_requested_threads: u32},
+        OmpAcquireLockSnap {orig_event_time: OTF2_TimeStamp, lock_id: u32, acquisition_order: u32},
+        OmpTaskCreateSnap {orig_event_time: OTF2_TimeStamp, task_id: u64},
+        OmpTaskSwitchSnap {orig_event_time: OTF2_TimeStamp, task_id: u64},
+        MetricSnap {orig_event_time: OTF2_TimeStamp, metric: OTF2_MetricRef, values: Vec<MetricValue>},
+        ParameterStringSnap {orig_event_time: OTF2_TimeStamp, parameter: OTF2_ParameterRef, string: OTF2_StringRef},
+        ParameterIntSnap {orig_event_time: OTF2_TimeStamp, parameter: OTF2_ParameterRef, value: i64},
+        ParameterUnsignedIntSnap {orig_event_time: OTF2_TimeStamp, parameter: OTF2_ParameterRef, value: u64},
+    }
+);
+
+impl Snapshot {
+    /// The time of the original event summarised by this record. `None` for the records which
+    /// delimit a snapshot and for unknown records.
+    pub fn orig_event_time(&self) -> Option<OTF2_TimeStamp> {
+        use Snapshot::*;
+        match self {
+            Unknown {} | SnapshotStart { .. } | SnapshotEnd { .. } => None,
+            MeasurementOnOffSnap { orig_event_time, .. }
+            | EnterSnap { orig_event_time, .. }
+            | MpiSendSnap { orig_event_time, .. }
+            | MpiIsendSnap { orig_event_time, .. }
+            | MpiIsendCompleteSnap { orig_event_time, .. }
+            | MpiRecvSnap { orig_event_time, .. }
+            | MpiIrecvRequestSnap { orig_event_time, .. }
+            | MpiIrecvSnap { orig_event_time, .. }
+            | MpiCollectiveBeginSnap { orig_event_time }
+            | MpiCollectiveEndSnap { orig_event_time, .. }
+            | OmpForkSnap { orig_event_time, .. }
+            | OmpAcquireLockSnap { orig_event_time, .. }
+            | OmpTaskCreateSnap { orig_event_time, .. }
+            | OmpTaskSwitchSnap { orig_event_time, .. }
+            | MetricSnap { orig_event_time, .. }
+            | ParameterStringSnap { orig_event_time, .. }
+            | ParameterIntSnap { orig_event_time, .. }
+            | ParameterUnsignedIntSnap { orig_event_time, .. } => Some(*orig_event_time),
+        }
+    }
+}
+
+/// The distinct `snap_time`s of the `SnapshotStart` records in `records`, in ascending order.
+pub(crate) fn snapshot_start_times(records: impl IntoIterator<Item = Status<SnapshotRecord>>) -> Status<Vec<OTF2_TimeStamp>> {
+    let mut timestamps = Vec::new();
+    for record in records {
+        let record = record?;
+        if let Snapshot::SnapshotStart { .. } = record.kind {
+            timestamps.push(record.data.snap_time);
+        }
+    }
+    timestamps.sort_unstable();
+    timestamps.dedup();
+    Ok(timestamps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribute::AttributeValue;
+    use crate::error::StatusCode;
+
+    fn record(location: u64, snap_time: OTF2_TimeStamp, kind: Snapshot) -> Status<SnapshotRecord> {
+        Ok(SnapshotRecord::new(OTF2_LocationRef(location), snap_time, Attributes::default(), kind))
+    }
+
+    #[test]
+    fn orig_event_time_is_none_for_delimiters() {
+        assert_eq!(Snapshot::Unknown {}.orig_event_time(), None);
+        assert_eq!(Snapshot::SnapshotStart { number_of_records: 3 }.orig_event_time(), None);
+        assert_eq!(Snapshot::SnapshotEnd { cont_read_pos: 12 }.orig_event_time(), None);
+
+        assert_eq!(Snapshot::EnterSnap { orig_event_time: 5, region: OTF2_RegionRef(1) }.orig_event_time(), Some(5));
+        assert_eq!(Snapshot::MpiCollectiveBeginSnap { orig_event_time: 7 }.orig_event_time(), Some(7));
+        let metric = Snapshot::MetricSnap { orig_event_time: 9, metric: OTF2_MetricRef(0), values: vec![MetricValue::Uint64(1)] };
+        assert_eq!(metric.orig_event_time(), Some(9));
+    }
+
+    #[test]
+    fn snapshot_start_times_are_sorted_and_distinct() {
+        let records = vec![
+            record(1, 200, Snapshot::SnapshotStart { number_of_records: 1 }),
+            record(1, 200, Snapshot::EnterSnap { orig_event_time: 150, region: OTF2_RegionRef(0) }),
+            record(1, 200, Snapshot::SnapshotEnd { cont_read_pos: 0 }),
+            record(0, 100, Snapshot::SnapshotStart { number_of_records: 0 }),
+            record(0, 200, Snapshot::SnapshotStart { number_of_records: 0 }),
+            record(0, 100, Snapshot::SnapshotEnd { cont_read_pos: 0 }),
+        ];
+        assert_eq!(snapshot_start_times(records), Ok(vec![100, 200]));
+
+        let failing = vec![record(0, 100, Snapshot::SnapshotStart { number_of_records: 0 }), Err(StatusCode::interrupted())];
+        assert!(snapshot_start_times(failing).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn snapshots_round_trip_through_json() {
+        let attributes = vec![(OTF2_AttributeRef(1), AttributeValue::Uint32(4))].into();
+        let snapshot = SnapshotRecord::new(OTF2_LocationRef(2), 300, attributes, Snapshot::MpiCollectiveEndSnap {
+            orig_event_time: 250,
+            collective_op: CollectiveOp::Allreduce,
+            communicator: OTF2_CommRef(0),
+            root: u32::MAX,
+            size_sent: 8,
+            size_received: 8,
+        });
+        let json = serde_json::to_string(&snapshot).expect("failed to serialize snapshot");
+        let back: SnapshotRecord = serde_json::from_str(&json).expect("failed to deserialize snapshot");
+        assert_eq!(back, snapshot);
+    }
+}
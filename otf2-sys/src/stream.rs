@@ -0,0 +1,174 @@
+//! Events as an asynchronous `Stream`, for use from async code running on Tokio.
+
+use crate::error::Status;
+use crate::event::Event;
+use crate::reader::{self, EventReader};
+
+use std::ffi::CString;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::sync::mpsc;
+
+/// Stream of the events of a trace, read on Tokio's blocking thread pool.
+///
+/// The trace is opened and read on a blocking thread, which sends each event over a bounded
+/// channel. When the channel is full the reading thread waits, so a slow consumer holds back
+/// reading rather than letting events pile up in memory. Dropping the stream stops the read.
+#[derive(Debug)]
+pub struct EventStream {
+    receiver: mpsc::Receiver<Status<Event>>,
+}
+
+impl EventStream {
+    /// Read all events of the trace at `anchor_file` in batches of `batch_size`, with at most
+    /// `capacity` decoded events waiting to be consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime or if `capacity` is 0.
+    pub fn open(anchor_file: CString, batch_size: u64, capacity: usize) -> Self {
+        Self::open_with(anchor_file, batch_size, capacity, |reader| Ok(reader))
+    }
+
+    /// As [`EventStream::open`], with `configure` applied to the event reader before reading, e.g.
+    /// to select event kinds or add a cancellation token.
+    pub fn open_with<F>(anchor_file: CString, batch_size: u64, capacity: usize, configure: F) -> Self
+    where
+        F: for<'r> FnOnce(EventReader<'r>) -> Status<EventReader<'r>> + Send + 'static,
+    {
+        Self::spawn(capacity, move |sender| {
+            let mut trace = match reader::open(anchor_file) {
+                Ok(trace) => trace,
+                Err(code) => {
+                    let _ = sender.blocking_send(Err(code));
+                    return;
+                }
+            };
+            let events = trace.get_event_reader(batch_size)
+                .and_then(configure)
+                .and_then(EventReader::into_event_iter);
+            match events {
+                Ok(events) => forward(&sender, events),
+                Err(code) => {
+                    let _ = sender.blocking_send(Err(code));
+                }
+            }
+        })
+    }
+
+    /// Run `produce` on the blocking thread pool, streaming whatever it sends.
+    fn spawn<F>(capacity: usize, produce: F) -> Self
+    where
+        F: FnOnce(mpsc::Sender<Status<Event>>) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(capacity);
+        tokio::task::spawn_blocking(move || produce(sender));
+        EventStream { receiver }
+    }
+
+    /// The next event, or `None` once the trace has been read.
+    pub async fn next(&mut self) -> Option<Status<Event>> {
+        self.receiver.recv().await
+    }
+}
+
+/// Send each of `events` in turn, waiting while the channel is full.
+fn forward(sender: &mpsc::Sender<Status<Event>>, events: impl IntoIterator<Item = Status<Event>>) {
+    for event in events {
+        // The receiver was dropped, so nobody wants the rest of the events
+        if sender.blocking_send(event).is_err() {
+            break;
+        }
+    }
+}
+
+impl futures_core::Stream for EventStream {
+    type Item = Status<Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::archive::fixtures::{write_trace, TempDir};
+    use crate::attribute::Attributes;
+    use crate::event::EventKind;
+    use crate::internal::*;
+
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread().build().expect("Failed to build runtime")
+    }
+
+    fn event(time: OTF2_TimeStamp) -> Status<Event> {
+        Ok(Event::new(OTF2_LocationRef(0), time, Attributes::default(), EventKind::Enter { region: OTF2_RegionRef(0) }))
+    }
+
+    #[test]
+    fn stream_yields_produced_events_in_order() {
+        let times = runtime().block_on(async {
+            let mut stream = EventStream::spawn(2, |sender| forward(&sender, (0..10).map(event)));
+            let mut times = Vec::new();
+            while let Some(event) = stream.next().await {
+                times.push(event.expect("Failed to read event").data.time);
+            }
+            times
+        });
+        assert_eq!(times, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn dropping_the_stream_stops_the_producer() {
+        let produced = Arc::new(AtomicUsize::new(0));
+        let (done_sender, done) = std::sync::mpsc::channel();
+        let runtime = runtime();
+        runtime.block_on(async {
+            let counter = Arc::clone(&produced);
+            let mut stream = EventStream::spawn(1, move |sender| {
+                let events = (0..).map(|time| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    event(time)
+                });
+                forward(&sender, events);
+                done_sender.send(()).expect("Failed to signal completion");
+            });
+            for _ in 0..3 {
+                stream.next().await.expect("Stream ended early").expect("Failed to read event");
+            }
+        });
+        done.recv_timeout(Duration::from_secs(10)).expect("Producer kept running after the stream was dropped");
+        // 3 consumed, at most 1 waiting in the channel and 1 whose send failed
+        assert!(produced.load(Ordering::SeqCst) <= 5);
+    }
+
+    #[test]
+    fn streams_the_events_of_an_archive() {
+        let num_events = [10, 20, 30];
+        let dir = TempDir::new("stream");
+        write_trace(&dir, "trace", &num_events).expect("Failed to write trace");
+        // A channel much smaller than the trace keeps the reading thread waiting on the consumer
+        let events = runtime().block_on(async {
+            let mut stream = EventStream::open(dir.anchor_file("trace"), 8, 4);
+            let mut events = Vec::new();
+            while let Some(event) = stream.next().await {
+                events.push(event.expect("Failed to read event"));
+            }
+            events
+        });
+        assert_eq!(events.len() as u64, num_events.iter().sum::<u64>());
+        for (location, &count) in num_events.iter().enumerate() {
+            let times: Vec<_> = events.iter()
+                .filter(|event| event.data.location == OTF2_LocationRef(location as u64))
+                .map(|event| event.data.time)
+                .collect();
+            assert_eq!(times, (0..count).map(|time| time * 10).collect::<Vec<_>>());
+        }
+    }
+}
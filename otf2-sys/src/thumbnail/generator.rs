@@ -0,0 +1,265 @@
+//! Compute thumbnails from the event stream of a trace.
+
+use crate::internal::*;
+use crate::event::{Event, EventKind};
+
+use super::thumbnail_struct::{Thumbnail, ThumbnailHeader, ThumbnailKind, ThumbnailSample};
+
+use std::collections::BTreeMap;
+
+/// Accumulates events into a fixed number of equally sized time bins.
+///
+/// The region thumbnail gives, for each bin, the exclusive time spent in each region summed over
+/// all locations. The metric thumbnail gives, for each bin, the largest value of the first member
+/// of each metric on any location. Samples are unsigned integers, so negative values are clamped
+/// to 0 and floating point values are truncated towards zero: a metric with fractional values
+/// should be recorded in a smaller unit to keep its precision. The `location_` variants give the
+/// same thumbnails for a single location.
+///
+/// Events must be added in timestamp order per location, as given by the global event reader.
+#[derive(Debug)]
+pub struct ThumbnailGenerator {
+    start: OTF2_TimeStamp,
+    end: OTF2_TimeStamp,
+    bins: u32,
+    call_stacks: BTreeMap<OTF2_LocationRef, (OTF2_TimeStamp, Vec<OTF2_RegionRef>)>,
+    region_time: BTreeMap<(OTF2_LocationRef, OTF2_RegionRef), Vec<u64>>,
+    metric_max: BTreeMap<(OTF2_LocationRef, OTF2_MetricRef), Vec<u64>>,
+}
+
+impl ThumbnailGenerator {
+    /// Bin the interval `[start, end)` into `bins` samples.
+    pub fn new(start: OTF2_TimeStamp, end: OTF2_TimeStamp, bins: u32) -> Self {
+        assert!(bins > 0, "a thumbnail needs at least one sample");
+        assert!(start < end, "empty time interval for thumbnail");
+        Self {
+            start,
+            end,
+            bins,
+            call_stacks: BTreeMap::new(),
+            region_time: BTreeMap::new(),
+            metric_max: BTreeMap::new(),
+        }
+    }
+
+    fn bin_of(&self, time: OTF2_TimeStamp) -> usize {
+        let offset = time.clamp(self.start, self.end - 1) - self.start;
+        (offset as u128 * self.bins as u128 / (self.end - self.start) as u128) as usize
+    }
+
+    fn bin_start(&self, bin: usize) -> OTF2_TimeStamp {
+        self.start + ((self.end - self.start) as u128 * bin as u128 / self.bins as u128) as u64
+    }
+
+    /// Add `[from, to)` to the time of `region` on `location`, split over the bins it overlaps.
+    fn add_region_time(&mut self, location: OTF2_LocationRef, region: OTF2_RegionRef, from: OTF2_TimeStamp, to: OTF2_TimeStamp) {
+        let (from, to) = (from.max(self.start), to.min(self.end));
+        if from >= to {
+            return;
+        }
+        let (first, last) = (self.bin_of(from), self.bin_of(to - 1));
+        let bounds: Vec<_> = (first..=last)
+            .map(|bin| (bin, self.bin_start(bin).max(from), self.bin_start(bin + 1).min(to)))
+            .collect();
+        let samples = self.region_time.entry((location, region)).or_insert_with(|| vec![0; self.bins as usize]);
+        for (bin, bin_from, bin_to) in bounds {
+            samples[bin] += bin_to - bin_from;
+        }
+    }
+
+    pub fn add_event(&mut self, event: &Event) {
+        let location = event.data.location;
+        let time = event.data.time;
+        match &event.kind {
+            EventKind::Enter { region } => {
+                let (last, stack) = self.call_stacks.entry(location).or_insert((time, Vec::new()));
+                let interval = stack.last().map(|&top| (top, *last));
+                *last = time;
+                stack.push(*region);
+                if let Some((top, from)) = interval {
+                    self.add_region_time(location, top, from, time);
+                }
+            }
+            EventKind::Leave { .. } => {
+                let Some((last, stack)) = self.call_stacks.get_mut(&location) else {
+                    return;
+                };
+                let interval = stack.pop().map(|top| (top, *last));
+                *last = time;
+                if let Some((top, from)) = interval {
+                    self.add_region_time(location, top, from, time);
+                }
+            }
+            EventKind::Metric { metric, values } => {
+                let value = match values.first() {
+                    Some(MetricValue::Uint64(v)) => *v,
+                    Some(MetricValue::Int64(v)) => (*v).max(0) as u64,
+                    // Truncated towards zero, and saturating at 0 for negative values
+                    Some(MetricValue::Float64(v)) => *v as u64,
+                    _ => return,
+                };
+                let bin = self.bin_of(time);
+                let bins = self.bins as usize;
+                let samples = self.metric_max.entry((location, *metric)).or_insert_with(|| vec![0; bins]);
+                samples[bin] = samples[bin].max(value);
+            }
+            _ => {}
+        }
+    }
+
+    fn thumbnail(&self, name: &str, description: &str, kind: ThumbnailKind, per_ref: Vec<(u64, Vec<u64>)>) -> Thumbnail {
+        let samples = (0..self.bins as usize)
+            .map(|bin| ThumbnailSample { baseline: 0, values: per_ref.iter().map(|(_, values)| values[bin]).collect() })
+            .collect();
+        Thumbnail {
+            header: ThumbnailHeader {
+                name: name.to_string(),
+                description: description.to_string(),
+                kind,
+                number_of_samples: self.bins,
+                refs_to_defs: per_ref.into_iter().map(|(reference, _)| reference).collect(),
+            },
+            samples,
+        }
+    }
+
+    /// The samples of each reference of `samples`, combining those of the locations accepted by
+    /// `include` bin by bin with `combine`.
+    fn merge_locations<R: Copy + Ord>(
+        samples: &BTreeMap<(OTF2_LocationRef, R), Vec<u64>>,
+        include: impl Fn(OTF2_LocationRef) -> bool,
+        combine: impl Fn(u64, u64) -> u64,
+    ) -> BTreeMap<R, Vec<u64>> {
+        let mut per_ref: BTreeMap<R, Vec<u64>> = BTreeMap::new();
+        for (&(location, reference), values) in samples {
+            if !include(location) {
+                continue;
+            }
+            match per_ref.get_mut(&reference) {
+                Some(combined) => combined.iter_mut().zip(values).for_each(|(combined, &value)| *combined = combine(*combined, value)),
+                None => { per_ref.insert(reference, values.clone()); },
+            }
+        }
+        per_ref
+    }
+
+    /// The locations with any samples, in order.
+    pub fn locations(&self) -> Vec<OTF2_LocationRef> {
+        let locations: std::collections::BTreeSet<_> = self.region_time.keys().map(|&(location, _)| location)
+            .chain(self.metric_max.keys().map(|&(location, _)| location))
+            .collect();
+        locations.into_iter().collect()
+    }
+
+    pub fn region_thumbnail(&self, name: &str, description: &str) -> Thumbnail {
+        let per_ref = Self::merge_locations(&self.region_time, |_| true, u64::saturating_add).into_iter()
+            .map(|(region, values)| (region.0 as u64, values))
+            .collect();
+        self.thumbnail(name, description, ThumbnailKind::Region, per_ref)
+    }
+
+    pub fn metric_thumbnail(&self, name: &str, description: &str) -> Thumbnail {
+        let per_ref = Self::merge_locations(&self.metric_max, |_| true, u64::max).into_iter()
+            .map(|(metric, values)| (metric.0 as u64, values))
+            .collect();
+        self.thumbnail(name, description, ThumbnailKind::Metric, per_ref)
+    }
+
+    /// The region thumbnail of `location` alone.
+    pub fn location_region_thumbnail(&self, location: OTF2_LocationRef, name: &str, description: &str) -> Thumbnail {
+        let per_ref = Self::merge_locations(&self.region_time, |other| other == location, u64::saturating_add).into_iter()
+            .map(|(region, values)| (region.0 as u64, values))
+            .collect();
+        self.thumbnail(name, description, ThumbnailKind::Region, per_ref)
+    }
+
+    /// The metric thumbnail of `location` alone.
+    pub fn location_metric_thumbnail(&self, location: OTF2_LocationRef, name: &str, description: &str) -> Thumbnail {
+        let per_ref = Self::merge_locations(&self.metric_max, |other| other == location, u64::max).into_iter()
+            .map(|(metric, values)| (metric.0 as u64, values))
+            .collect();
+        self.thumbnail(name, description, ThumbnailKind::Metric, per_ref)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribute::Attributes;
+
+    fn event(location: u64, time: u64, kind: EventKind) -> Event {
+        Event::new(OTF2_LocationRef(location), time, Attributes::default(), kind)
+    }
+
+    #[test]
+    fn region_time_is_split_over_bins() {
+        let (main, work) = (OTF2_RegionRef(0), OTF2_RegionRef(7));
+        let mut generator = ThumbnailGenerator::new(100, 200, 4);
+        for e in [
+            event(0, 100, EventKind::Enter { region: main }),
+            event(0, 110, EventKind::Enter { region: work }),
+            event(0, 160, EventKind::Leave { region: work }),
+            event(0, 200, EventKind::Leave { region: main }),
+            event(1, 150, EventKind::Enter { region: work }),
+            event(1, 175, EventKind::Leave { region: work }),
+        ] {
+            generator.add_event(&e);
+        }
+        let thumbnail = generator.region_thumbnail("regions", "");
+        assert_eq!(thumbnail.header.kind, ThumbnailKind::Region);
+        assert_eq!(thumbnail.header.refs_to_defs, vec![0, 7]);
+        let values: Vec<_> = thumbnail.samples.iter().map(|s| s.values.clone()).collect();
+        assert_eq!(values, vec![vec![10, 15], vec![0, 25], vec![15, 35], vec![25, 0]]);
+    }
+
+    #[test]
+    fn metric_takes_max_per_bin() {
+        let metric = OTF2_MetricRef(2);
+        let mut generator = ThumbnailGenerator::new(0, 10, 2);
+        for (time, value) in [(1, 3), (2, 9), (7, -4)] {
+            generator.add_event(&event(0, time, EventKind::Metric { metric, values: vec![MetricValue::Int64(value)] }));
+        }
+        let thumbnail = generator.metric_thumbnail("metrics", "");
+        assert_eq!(thumbnail.header.refs_to_defs, vec![2]);
+        assert_eq!(thumbnail.samples[0].values, vec![9]);
+        assert_eq!(thumbnail.samples[1].values, vec![0]);
+    }
+
+    #[test]
+    fn fractional_metrics_are_truncated() {
+        let metric = OTF2_MetricRef(1);
+        let mut generator = ThumbnailGenerator::new(0, 10, 2);
+        for (time, value) in [(1, 2.9), (2, 0.5), (6, -1.5), (8, 0.99)] {
+            generator.add_event(&event(0, time, EventKind::Metric { metric, values: vec![MetricValue::Float64(value)] }));
+        }
+        let thumbnail = generator.metric_thumbnail("metrics", "");
+        assert_eq!(thumbnail.samples[0].values, vec![2]);
+        assert_eq!(thumbnail.samples[1].values, vec![0]);
+    }
+
+    #[test]
+    fn locations_have_their_own_samples() {
+        let (work, metric) = (OTF2_RegionRef(3), OTF2_MetricRef(0));
+        let mut generator = ThumbnailGenerator::new(0, 100, 2);
+        for e in [
+            event(0, 0, EventKind::Enter { region: work }),
+            event(0, 40, EventKind::Leave { region: work }),
+            event(0, 60, EventKind::Metric { metric, values: vec![MetricValue::Uint64(5)] }),
+            event(1, 20, EventKind::Enter { region: work }),
+            event(1, 80, EventKind::Leave { region: work }),
+            event(1, 70, EventKind::Metric { metric, values: vec![MetricValue::Uint64(8)] }),
+        ] {
+            generator.add_event(&e);
+        }
+        assert_eq!(generator.locations(), vec![OTF2_LocationRef(0), OTF2_LocationRef(1)]);
+        let values = |thumbnail: Thumbnail| -> Vec<_> { thumbnail.samples.into_iter().map(|s| s.values).collect() };
+        assert_eq!(values(generator.region_thumbnail("regions", "")), vec![vec![70], vec![30]]);
+        assert_eq!(values(generator.location_region_thumbnail(OTF2_LocationRef(0), "regions", "")), vec![vec![40], vec![0]]);
+        assert_eq!(values(generator.location_region_thumbnail(OTF2_LocationRef(1), "regions", "")), vec![vec![30], vec![30]]);
+        assert_eq!(values(generator.metric_thumbnail("metrics", "")), vec![vec![0], vec![8]]);
+        assert_eq!(values(generator.location_metric_thumbnail(OTF2_LocationRef(0), "metrics", "")), vec![vec![0], vec![5]]);
+        // A location without samples has an empty thumbnail
+        let empty = generator.location_region_thumbnail(OTF2_LocationRef(2), "regions", "");
+        assert!(empty.header.refs_to_defs.is_empty());
+    }
+}
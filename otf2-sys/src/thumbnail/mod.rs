@@ -0,0 +1,5 @@
+mod thumbnail_struct;
+mod generator;
+
+pub use thumbnail_struct::{Thumbnail, ThumbnailHeader, ThumbnailKind, ThumbnailSample};
+pub use generator::ThumbnailGenerator;
@@ -0,0 +1,65 @@
+use crate::internal::*;
+
+use OTF2_ThumbnailType_enum::*;
+
+/// What the definition references of a thumbnail refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThumbnailKind {
+    Region,
+    Metric,
+    Attributes,
+}
+
+impl ThumbnailKind {
+    pub fn from_raw(kind: OTF2_ThumbnailType) -> Option<Self> {
+        match kind.0 {
+            k if k == OTF2_THUMBNAIL_TYPE_REGION as u8 => Some(Self::Region),
+            k if k == OTF2_THUMBNAIL_TYPE_METRIC as u8 => Some(Self::Metric),
+            k if k == OTF2_THUMBNAIL_TYPE_ATTRIBUTES as u8 => Some(Self::Attributes),
+            _ => None,
+        }
+    }
+
+    pub fn to_raw(self) -> OTF2_ThumbnailType {
+        OTF2_ThumbnailType((match self {
+            Self::Region => OTF2_THUMBNAIL_TYPE_REGION,
+            Self::Metric => OTF2_THUMBNAIL_TYPE_METRIC,
+            Self::Attributes => OTF2_THUMBNAIL_TYPE_ATTRIBUTES,
+        }) as u8)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThumbnailHeader {
+    pub name: String,
+    pub description: String,
+    pub kind: ThumbnailKind,
+    pub number_of_samples: u32,
+    /// One reference per value of each sample, to a definition of the type given by `kind`.
+    pub refs_to_defs: Vec<u64>,
+}
+
+impl ThumbnailHeader {
+    pub fn number_of_metrics(&self) -> u32 {
+        self.refs_to_defs.len() as u32
+    }
+}
+
+/// One sample of a thumbnail, with one value per entry of `ThumbnailHeader::refs_to_defs`.
+///
+/// A `baseline` of 0 means the baseline is the sum of `values`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThumbnailSample {
+    pub baseline: u64,
+    pub values: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Thumbnail {
+    pub header: ThumbnailHeader,
+    pub samples: Vec<ThumbnailSample>,
+}
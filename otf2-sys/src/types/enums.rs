@@ -0,0 +1,367 @@
+use crate::internal::*;
+
+declare_otf2_enum!(
+    pub enum Type: OTF2_Type_enum as u8, wraps OTF2_Type {
+        None = OTF2_TYPE_NONE,
+        Uint8 = OTF2_TYPE_UINT8,
+        Uint16 = OTF2_TYPE_UINT16,
+        Uint32 = OTF2_TYPE_UINT32,
+        Uint64 = OTF2_TYPE_UINT64,
+        Int8 = OTF2_TYPE_INT8,
+        Int16 = OTF2_TYPE_INT16,
+        Int32 = OTF2_TYPE_INT32,
+        Int64 = OTF2_TYPE_INT64,
+        Float = OTF2_TYPE_FLOAT,
+        Double = OTF2_TYPE_DOUBLE,
+        String = OTF2_TYPE_STRING,
+        Attribute = OTF2_TYPE_ATTRIBUTE,
+        Location = OTF2_TYPE_LOCATION,
+        Region = OTF2_TYPE_REGION,
+        Group = OTF2_TYPE_GROUP,
+        Metric = OTF2_TYPE_METRIC,
+        Comm = OTF2_TYPE_COMM,
+        Parameter = OTF2_TYPE_PARAMETER,
+        RmaWin = OTF2_TYPE_RMA_WIN,
+        SourceCodeLocation = OTF2_TYPE_SOURCE_CODE_LOCATION,
+        CallingContext = OTF2_TYPE_CALLING_CONTEXT,
+        InterruptGenerator = OTF2_TYPE_INTERRUPT_GENERATOR,
+        IoFile = OTF2_TYPE_IO_FILE,
+        IoHandle = OTF2_TYPE_IO_HANDLE,
+        LocationGroup = OTF2_TYPE_LOCATION_GROUP,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum Paradigm: OTF2_Paradigm_enum as OTF2_Paradigm {
+        Unknown = OTF2_PARADIGM_UNKNOWN,
+        User = OTF2_PARADIGM_USER,
+        Compiler = OTF2_PARADIGM_COMPILER,
+        OpenMp = OTF2_PARADIGM_OPENMP,
+        Mpi = OTF2_PARADIGM_MPI,
+        Cuda = OTF2_PARADIGM_CUDA,
+        MeasurementSystem = OTF2_PARADIGM_MEASUREMENT_SYSTEM,
+        Pthread = OTF2_PARADIGM_PTHREAD,
+        Hmpp = OTF2_PARADIGM_HMPP,
+        OmpSs = OTF2_PARADIGM_OMPSS,
+        Hardware = OTF2_PARADIGM_HARDWARE,
+        Gaspi = OTF2_PARADIGM_GASPI,
+        Upc = OTF2_PARADIGM_UPC,
+        Shmem = OTF2_PARADIGM_SHMEM,
+        WinThread = OTF2_PARADIGM_WINTHREAD,
+        QtThread = OTF2_PARADIGM_QTTHREAD,
+        AceThread = OTF2_PARADIGM_ACE_THREAD,
+        TbbThread = OTF2_PARADIGM_TBB_THREAD,
+        OpenAcc = OTF2_PARADIGM_OPENACC,
+        OpenCl = OTF2_PARADIGM_OPENCL,
+        Mtapi = OTF2_PARADIGM_MTAPI,
+        Sampling = OTF2_PARADIGM_SAMPLING,
+        None = OTF2_PARADIGM_NONE,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum ParadigmClass: OTF2_ParadigmClass_enum as OTF2_ParadigmClass {
+        Process = OTF2_PARADIGM_CLASS_PROCESS,
+        ThreadForkJoin = OTF2_PARADIGM_CLASS_THREAD_FORK_JOIN,
+        ThreadCreateWait = OTF2_PARADIGM_CLASS_THREAD_CREATE_WAIT,
+        Accelerator = OTF2_PARADIGM_CLASS_ACCELERATOR,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum ParadigmProperty: OTF2_ParadigmProperty_enum as OTF2_ParadigmProperty {
+        CommNameTemplate = OTF2_PARADIGM_PROPERTY_COMM_NAME_TEMPLATE,
+        RmaWinNameTemplate = OTF2_PARADIGM_PROPERTY_RMA_WIN_NAME_TEMPLATE,
+        RmaOnly = OTF2_PARADIGM_PROPERTY_RMA_ONLY,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum IoParadigmClass: OTF2_IoParadigmClass_enum as OTF2_IoParadigmClass {
+        Serial = OTF2_IO_PARADIGM_CLASS_SERIAL,
+        Parallel = OTF2_IO_PARADIGM_CLASS_PARALLEL,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum IoParadigmProperty: OTF2_IoParadigmProperty_enum as OTF2_IoParadigmProperty {
+        Version = OTF2_IO_PARADIGM_PROPERTY_VERSION,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum RegionRole: OTF2_RegionRole_enum as OTF2_RegionRole {
+        Unknown = OTF2_REGION_ROLE_UNKNOWN,
+        Function = OTF2_REGION_ROLE_FUNCTION,
+        Wrapper = OTF2_REGION_ROLE_WRAPPER,
+        Loop = OTF2_REGION_ROLE_LOOP,
+        Code = OTF2_REGION_ROLE_CODE,
+        Parallel = OTF2_REGION_ROLE_PARALLEL,
+        Sections = OTF2_REGION_ROLE_SECTIONS,
+        Section = OTF2_REGION_ROLE_SECTION,
+        Workshare = OTF2_REGION_ROLE_WORKSHARE,
+        Single = OTF2_REGION_ROLE_SINGLE,
+        SingleSblock = OTF2_REGION_ROLE_SINGLE_SBLOCK,
+        Master = OTF2_REGION_ROLE_MASTER,
+        Critical = OTF2_REGION_ROLE_CRITICAL,
+        CriticalSblock = OTF2_REGION_ROLE_CRITICAL_SBLOCK,
+        Atomic = OTF2_REGION_ROLE_ATOMIC,
+        Barrier = OTF2_REGION_ROLE_BARRIER,
+        ImplicitBarrier = OTF2_REGION_ROLE_IMPLICIT_BARRIER,
+        Flush = OTF2_REGION_ROLE_FLUSH,
+        Ordered = OTF2_REGION_ROLE_ORDERED,
+        OrderedSblock = OTF2_REGION_ROLE_ORDERED_SBLOCK,
+        Task = OTF2_REGION_ROLE_TASK,
+        TaskCreate = OTF2_REGION_ROLE_TASK_CREATE,
+        TaskWait = OTF2_REGION_ROLE_TASK_WAIT,
+        CollOne2All = OTF2_REGION_ROLE_COLL_ONE2ALL,
+        CollAll2One = OTF2_REGION_ROLE_COLL_ALL2ONE,
+        CollAll2All = OTF2_REGION_ROLE_COLL_ALL2ALL,
+        CollOther = OTF2_REGION_ROLE_COLL_OTHER,
+        FileIo = OTF2_REGION_ROLE_FILE_IO,
+        Point2Point = OTF2_REGION_ROLE_POINT2POINT,
+        Rma = OTF2_REGION_ROLE_RMA,
+        DataTransfer = OTF2_REGION_ROLE_DATA_TRANSFER,
+        Artificial = OTF2_REGION_ROLE_ARTIFICIAL,
+        ThreadCreate = OTF2_REGION_ROLE_THREAD_CREATE,
+        ThreadWait = OTF2_REGION_ROLE_THREAD_WAIT,
+        TaskUntied = OTF2_REGION_ROLE_TASK_UNTIED,
+        Allocate = OTF2_REGION_ROLE_ALLOCATE,
+        Deallocate = OTF2_REGION_ROLE_DEALLOCATE,
+        Reallocate = OTF2_REGION_ROLE_REALLOCATE,
+        FileIoMetadata = OTF2_REGION_ROLE_FILE_IO_METADATA,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum SystemTreeDomain: OTF2_SystemTreeDomain_enum as OTF2_SystemTreeDomain {
+        Machine = OTF2_SYSTEM_TREE_DOMAIN_MACHINE,
+        SharedMemory = OTF2_SYSTEM_TREE_DOMAIN_SHARED_MEMORY,
+        Numa = OTF2_SYSTEM_TREE_DOMAIN_NUMA,
+        Socket = OTF2_SYSTEM_TREE_DOMAIN_SOCKET,
+        Cache = OTF2_SYSTEM_TREE_DOMAIN_CACHE,
+        Core = OTF2_SYSTEM_TREE_DOMAIN_CORE,
+        Pu = OTF2_SYSTEM_TREE_DOMAIN_PU,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum LocationType: OTF2_LocationType_enum as u8, wraps OTF2_LocationType {
+        Unknown = OTF2_LOCATION_TYPE_UNKNOWN,
+        CpuThread = OTF2_LOCATION_TYPE_CPU_THREAD,
+        AcceleratorStream = OTF2_LOCATION_TYPE_ACCELERATOR_STREAM,
+        Metric = OTF2_LOCATION_TYPE_METRIC,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum LocationGroupType: OTF2_LocationGroupType_enum as u8, wraps OTF2_LocationGroupType {
+        Unknown = OTF2_LOCATION_GROUP_TYPE_UNKNOWN,
+        Process = OTF2_LOCATION_GROUP_TYPE_PROCESS,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum GroupType: OTF2_GroupType_enum as u8, wraps OTF2_GroupType {
+        Unknown = OTF2_GROUP_TYPE_UNKNOWN,
+        Locations = OTF2_GROUP_TYPE_LOCATIONS,
+        Regions = OTF2_GROUP_TYPE_REGIONS,
+        Metric = OTF2_GROUP_TYPE_METRIC,
+        CommLocations = OTF2_GROUP_TYPE_COMM_LOCATIONS,
+        CommGroup = OTF2_GROUP_TYPE_COMM_GROUP,
+        CommSelf = OTF2_GROUP_TYPE_COMM_SELF,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum MetricType: OTF2_MetricType_enum as u8, wraps OTF2_MetricType {
+        OtherMetric = OTF2_METRIC_TYPE_OTHER,
+        Papi = OTF2_METRIC_TYPE_PAPI,
+        Rusage = OTF2_METRIC_TYPE_RUSAGE,
+        User = OTF2_METRIC_TYPE_USER,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum MetricMode: OTF2_MetricMode_enum as OTF2_MetricMode {
+        AccumulatedStart = OTF2_METRIC_ACCUMULATED_START,
+        AccumulatedPoint = OTF2_METRIC_ACCUMULATED_POINT,
+        AccumulatedLast = OTF2_METRIC_ACCUMULATED_LAST,
+        AccumulatedNext = OTF2_METRIC_ACCUMULATED_NEXT,
+        AbsolutePoint = OTF2_METRIC_ABSOLUTE_POINT,
+        AbsoluteLast = OTF2_METRIC_ABSOLUTE_LAST,
+        AbsoluteNext = OTF2_METRIC_ABSOLUTE_NEXT,
+        RelativePoint = OTF2_METRIC_RELATIVE_POINT,
+        RelativeLast = OTF2_METRIC_RELATIVE_LAST,
+        RelativeNext = OTF2_METRIC_RELATIVE_NEXT,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum MetricOccurrence: OTF2_MetricOccurrence_enum as OTF2_MetricOccurrence {
+        SynchronousStrict = OTF2_METRIC_SYNCHRONOUS_STRICT,
+        Synchronous = OTF2_METRIC_SYNCHRONOUS,
+        Asynchronous = OTF2_METRIC_ASYNCHRONOUS,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum MetricScope: OTF2_MetricScope_enum as OTF2_MetricScope {
+        Location = OTF2_SCOPE_LOCATION,
+        LocationGroup = OTF2_SCOPE_LOCATION_GROUP,
+        SystemTreeNode = OTF2_SCOPE_SYSTEM_TREE_NODE,
+        Group = OTF2_SCOPE_GROUP,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum RecorderKind: OTF2_RecorderKind_enum as OTF2_RecorderKind {
+        Unknown = OTF2_RECORDER_KIND_UNKNOWN,
+        Abstract = OTF2_RECORDER_KIND_ABSTRACT,
+        Cpu = OTF2_RECORDER_KIND_CPU,
+        Gpu = OTF2_RECORDER_KIND_GPU,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum Base: OTF2_Base_enum as OTF2_Base {
+        Binary = OTF2_BASE_BINARY,
+        Decimal = OTF2_BASE_DECIMAL,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum ParameterType: OTF2_ParameterType_enum as u8, wraps OTF2_ParameterType {
+        String = OTF2_PARAMETER_TYPE_STRING,
+        Int64 = OTF2_PARAMETER_TYPE_INT64,
+        Uint64 = OTF2_PARAMETER_TYPE_UINT64,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum CartPeriodicity: OTF2_CartPeriodicity_enum as OTF2_CartPeriodicity {
+        False = OTF2_CART_PERIODIC_FALSE,
+        True = OTF2_CART_PERIODIC_TRUE,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum InterruptGeneratorMode: OTF2_InterruptGeneratorMode_enum as OTF2_InterruptGeneratorMode {
+        Time = OTF2_INTERRUPT_GENERATOR_MODE_TIME,
+        Count = OTF2_INTERRUPT_GENERATOR_MODE_COUNT,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum MeasurementMode: OTF2_MeasurementMode_enum as OTF2_MeasurementMode {
+        On = OTF2_MEASUREMENT_ON,
+        Off = OTF2_MEASUREMENT_OFF,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum CollectiveOp: OTF2_CollectiveOp_enum as OTF2_CollectiveOp {
+        Barrier = OTF2_COLLECTIVE_OP_BARRIER,
+        Bcast = OTF2_COLLECTIVE_OP_BCAST,
+        Gather = OTF2_COLLECTIVE_OP_GATHER,
+        Gatherv = OTF2_COLLECTIVE_OP_GATHERV,
+        Scatter = OTF2_COLLECTIVE_OP_SCATTER,
+        Scatterv = OTF2_COLLECTIVE_OP_SCATTERV,
+        Allgather = OTF2_COLLECTIVE_OP_ALLGATHER,
+        Allgatherv = OTF2_COLLECTIVE_OP_ALLGATHERV,
+        Alltoall = OTF2_COLLECTIVE_OP_ALLTOALL,
+        Alltoallv = OTF2_COLLECTIVE_OP_ALLTOALLV,
+        Alltoallw = OTF2_COLLECTIVE_OP_ALLTOALLW,
+        Allreduce = OTF2_COLLECTIVE_OP_ALLREDUCE,
+        Reduce = OTF2_COLLECTIVE_OP_REDUCE,
+        ReduceScatter = OTF2_COLLECTIVE_OP_REDUCE_SCATTER,
+        Scan = OTF2_COLLECTIVE_OP_SCAN,
+        Exscan = OTF2_COLLECTIVE_OP_EXSCAN,
+        ReduceScatterBlock = OTF2_COLLECTIVE_OP_REDUCE_SCATTER_BLOCK,
+        CreateHandle = OTF2_COLLECTIVE_OP_CREATE_HANDLE,
+        DestroyHandle = OTF2_COLLECTIVE_OP_DESTROY_HANDLE,
+        Allocate = OTF2_COLLECTIVE_OP_ALLOCATE,
+        Deallocate = OTF2_COLLECTIVE_OP_DEALLOCATE,
+        CreateHandleAndAllocate = OTF2_COLLECTIVE_OP_CREATE_HANDLE_AND_ALLOCATE,
+        DestroyHandleAndDeallocate = OTF2_COLLECTIVE_OP_DESTROY_HANDLE_AND_DEALLOCATE,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum LockType: OTF2_LockType_enum as u8, wraps OTF2_LockType {
+        Exclusive = OTF2_LOCK_EXCLUSIVE,
+        Shared = OTF2_LOCK_SHARED,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum RmaSyncType: OTF2_RmaSyncType_enum as u8, wraps OTF2_RmaSyncType {
+        Memory = OTF2_RMA_SYNC_TYPE_MEMORY,
+        NotifyIn = OTF2_RMA_SYNC_TYPE_NOTIFY_IN,
+        NotifyOut = OTF2_RMA_SYNC_TYPE_NOTIFY_OUT,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum RmaAtomicType: OTF2_RmaAtomicType_enum as u8, wraps OTF2_RmaAtomicType {
+        Accumulate = OTF2_RMA_ATOMIC_TYPE_ACCUMULATE,
+        Increment = OTF2_RMA_ATOMIC_TYPE_INCREMENT,
+        TestAndSet = OTF2_RMA_ATOMIC_TYPE_TEST_AND_SET,
+        CompareAndSwap = OTF2_RMA_ATOMIC_TYPE_COMPARE_AND_SWAP,
+        Swap = OTF2_RMA_ATOMIC_TYPE_SWAP,
+        FetchAndAdd = OTF2_RMA_ATOMIC_TYPE_FETCH_AND_ADD,
+        FetchAndIncrement = OTF2_RMA_ATOMIC_TYPE_FETCH_AND_INCREMENT,
+        Add = OTF2_RMA_ATOMIC_TYPE_ADD,
+        FetchAndAccumulate = OTF2_RMA_ATOMIC_TYPE_FETCH_AND_ACCUMULATE,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum IoAccessMode: OTF2_IoAccessMode_enum as OTF2_IoAccessMode {
+        ReadOnly = OTF2_IO_ACCESS_MODE_READ_ONLY,
+        WriteOnly = OTF2_IO_ACCESS_MODE_WRITE_ONLY,
+        ReadWrite = OTF2_IO_ACCESS_MODE_READ_WRITE,
+        ExecuteOnly = OTF2_IO_ACCESS_MODE_EXECUTE_ONLY,
+        SearchOnly = OTF2_IO_ACCESS_MODE_SEARCH_ONLY,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum IoSeekOption: OTF2_IoSeekOption_enum as OTF2_IoSeekOption {
+        FromStart = OTF2_IO_SEEK_FROM_START,
+        FromCurrent = OTF2_IO_SEEK_FROM_CURRENT,
+        FromEnd = OTF2_IO_SEEK_FROM_END,
+        Data = OTF2_IO_SEEK_DATA,
+        Hole = OTF2_IO_SEEK_HOLE,
+    }
+);
+
+declare_otf2_enum!(
+    pub enum IoOperationMode: OTF2_IoOperationMode_enum as OTF2_IoOperationMode {
+        Read = OTF2_IO_OPERATION_MODE_READ,
+        Write = OTF2_IO_OPERATION_MODE_WRITE,
+        Flush = OTF2_IO_OPERATION_MODE_FLUSH,
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_values_are_preserved() {
+        assert_eq!(CollectiveOp::from_raw(OTF2_CollectiveOp_enum::OTF2_COLLECTIVE_OP_ALLREDUCE as _), CollectiveOp::Allreduce);
+        assert_eq!(CollectiveOp::from_raw(200), CollectiveOp::Other(200));
+        assert_eq!(CollectiveOp::Other(200).to_raw(), 200);
+        assert_eq!(LockType::from(OTF2_LockType(1)), LockType::Shared);
+    }
+
+    #[test]
+    fn enums_round_trip_through_strings() {
+        assert_eq!(RegionRole::Function.to_string(), "Function");
+        assert_eq!("Function".parse::<RegionRole>(), Ok(RegionRole::Function));
+        assert_eq!(Paradigm::Other(250).to_string(), "250");
+        assert_eq!("250".parse::<Paradigm>(), Ok(Paradigm::Other(250)));
+        assert!("NotAParadigm".parse::<Paradigm>().is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::internal::*;
+
+declare_otf2_flags!(
+    pub struct RegionFlags: OTF2_RegionFlag_enum as OTF2_RegionFlag {
+        DYNAMIC = OTF2_REGION_FLAG_DYNAMIC,
+        PHASE = OTF2_REGION_FLAG_PHASE,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct GroupFlags: OTF2_GroupFlag_enum as OTF2_GroupFlag {
+        GLOBAL_MEMBERS = OTF2_GROUP_FLAG_GLOBAL_MEMBERS,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct CommFlags: OTF2_CommFlag_enum as OTF2_CommFlag {
+        CREATE_DESTROY_EVENTS = OTF2_COMM_FLAG_CREATE_DESTROY_EVENTS,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct RmaWinFlags: OTF2_RmaWinFlag_enum as OTF2_RmaWinFlag {
+        CREATE_DESTROY_EVENTS = OTF2_RMA_WIN_FLAG_CREATE_DESTROY_EVENTS,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct RmaSyncLevel: OTF2_RmaSyncLevel_enum as OTF2_RmaSyncLevel {
+        PROCESS = OTF2_RMA_SYNC_LEVEL_PROCESS,
+        MEMORY = OTF2_RMA_SYNC_LEVEL_MEMORY,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct IoParadigmFlags: OTF2_IoParadigmFlag_enum as OTF2_IoParadigmFlag {
+        OS = OTF2_IO_PARADIGM_FLAG_OS,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct IoHandleFlags: OTF2_IoHandleFlag_enum as OTF2_IoHandleFlag {
+        PRE_CREATED = OTF2_IO_HANDLE_FLAG_PRE_CREATED,
+        ALL_PROXY = OTF2_IO_HANDLE_FLAG_ALL_PROXY,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct IoCreationFlags: OTF2_IoCreationFlag_enum as OTF2_IoCreationFlag {
+        CREATE = OTF2_IO_CREATION_FLAG_CREATE,
+        TRUNCATE = OTF2_IO_CREATION_FLAG_TRUNCATE,
+        DIRECTORY = OTF2_IO_CREATION_FLAG_DIRECTORY,
+        EXCLUSIVE = OTF2_IO_CREATION_FLAG_EXCLUSIVE,
+        NO_CONTROLLING_TERMINAL = OTF2_IO_CREATION_FLAG_NO_CONTROLLING_TERMINAL,
+        NO_FOLLOW = OTF2_IO_CREATION_FLAG_NO_FOLLOW,
+        PATH = OTF2_IO_CREATION_FLAG_PATH,
+        TEMPORARY_FILE = OTF2_IO_CREATION_FLAG_TEMPORARY_FILE,
+        LARGEFILE = OTF2_IO_CREATION_FLAG_LARGEFILE,
+        NO_SEEK = OTF2_IO_CREATION_FLAG_NO_SEEK,
+        UNIQUE = OTF2_IO_CREATION_FLAG_UNIQUE,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct IoStatusFlags: OTF2_IoStatusFlag_enum as OTF2_IoStatusFlag {
+        CLOSE_ON_EXEC = OTF2_IO_STATUS_FLAG_CLOSE_ON_EXEC,
+        APPEND = OTF2_IO_STATUS_FLAG_APPEND,
+        NON_BLOCKING = OTF2_IO_STATUS_FLAG_NON_BLOCKING,
+        ASYNC = OTF2_IO_STATUS_FLAG_ASYNC,
+        SYNC = OTF2_IO_STATUS_FLAG_SYNC,
+        DATA_SYNC = OTF2_IO_STATUS_FLAG_DATA_SYNC,
+        AVOID_CACHING = OTF2_IO_STATUS_FLAG_AVOID_CACHING,
+        NO_ACCESS_TIME = OTF2_IO_STATUS_FLAG_NO_ACCESS_TIME,
+        DELETE_ON_CLOSE = OTF2_IO_STATUS_FLAG_DELETE_ON_CLOSE,
+    }
+);
+
+declare_otf2_flags!(
+    pub struct IoOperationFlags: OTF2_IoOperationFlag_enum as OTF2_IoOperationFlag {
+        NON_BLOCKING = OTF2_IO_OPERATION_FLAG_NON_BLOCKING,
+        COLLECTIVE = OTF2_IO_OPERATION_FLAG_COLLECTIVE,
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_bits_are_preserved() {
+        let flags = RegionFlags::from_bits_retain(RegionFlags::PHASE.bits() | 0x100);
+        assert!(flags.contains(RegionFlags::PHASE));
+        assert!(!flags.contains(RegionFlags::DYNAMIC));
+        assert_eq!(flags.unknown_bits(), 0x100);
+        assert_eq!(OTF2_RegionFlag::from(flags), RegionFlags::PHASE.bits() | 0x100);
+    }
+
+    #[test]
+    fn flags_round_trip_through_strings() {
+        let flags = IoStatusFlags::APPEND | IoStatusFlags::SYNC | IoStatusFlags::from_bits_retain(1 << 30);
+        let text = flags.to_string();
+        assert_eq!(text, "APPEND | SYNC | 0x40000000");
+        assert_eq!(text.parse::<IoStatusFlags>(), Ok(flags));
+        assert_eq!("NONE".parse::<IoStatusFlags>(), Ok(IoStatusFlags::empty()));
+        assert!("APPEND | BOGUS".parse::<IoStatusFlags>().is_err());
+    }
+}
@@ -0,0 +1,21 @@
+//! Rust types for the enum and flag typedefs of OTF2, which bindgen leaves as plain integers.
+
+mod enums;
+mod flags;
+
+pub use enums::*;
+pub use flags::*;
+
+/// A string which doesn't name a variant or flag of `kind`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid {kind}: {input:?}")]
+pub struct ParseError {
+    pub kind: &'static str,
+    pub input: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: &'static str, input: &str) -> Self {
+        Self { kind, input: input.to_string() }
+    }
+}